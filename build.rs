@@ -0,0 +1,6 @@
+fn main() {
+	// Configure the linker for loading as a Node.js native addon, if required.
+
+	#[cfg(feature = "node")]
+	napi_build::setup();
+}
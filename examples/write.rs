@@ -23,4 +23,6 @@ fn main() {
 			.write(file.file_name().to_str().expect("failed to convert file name to string"), &mut src)
 			.expect("failed to write entry");
 	}
+
+	writer.finish().expect("failed to finish archive");
 }
@@ -0,0 +1,330 @@
+use std::{
+	collections::{BTreeSet, HashMap},
+	io::{self, BufRead, BufReader, Read, Seek},
+};
+
+use crate::{error::WriteError, overlay::Overlay, read::Archive, write::Writer};
+
+/// Represents the prefix of a comment line in an IDE or IPL file.
+const COMMENT: char = '#';
+
+/// Represents the line terminating a section in an IDE or IPL file.
+const SECTION_END: &str = "end";
+
+/// Represents the sections of an IDE file which define a model and its texture dictionary as their second and third fields.
+const IDE_SECTIONS: [&str; 7] = ["objs", "tobj", "anim", "cars", "peds", "weap", "hier"];
+
+/// Represents the section of an IDE file which defines parent texture dictionaries.
+const IDE_TXDP: &str = "txdp";
+
+/// Represents the section of an IPL file which places instances of models.
+const IPL_INST: &str = "inst";
+
+/// Represents the name used to indicate that no texture dictionary is used.
+const NO_TEXTURE: &str = "null";
+
+/// Represents the extension of model entries.
+const MODEL_EXTENSION: &str = "dff";
+
+/// Represents the extension of texture dictionary entries.
+const TEXTURE_EXTENSION: &str = "txd";
+
+/// Represents the models and texture dictionaries referenced by a set of IDE and IPL files.
+/// Names are stored in lowercase, in line with the case-insensitive behaviour of the games.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Definitions {
+	models: BTreeSet<String>,
+	textures: BTreeSet<String>,
+}
+
+/// Represents the outcome of packing the entries referenced by a set of definitions.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct Packed {
+	/// The names of the entries written.
+	pub written: Vec<String>,
+
+	/// The names of the entries referenced but not found within the pool.
+	pub unresolved: Vec<String>,
+}
+
+/// Represents a finding from auditing an archive against a set of definitions.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum Finding {
+	/// Indicates that a model or texture dictionary is referenced but is not present in the archive.
+	Missing {
+		/// The name of the expected entry.
+		name: String,
+	},
+
+	/// Indicates that a model or texture dictionary entry is present in the archive but is never referenced.
+	Orphaned {
+		/// The name of the entry.
+		name: String,
+	},
+}
+
+impl Definitions {
+	/// Creates a new, empty set of definitions.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reads the models and texture dictionaries defined by the IDE file from `src`.
+	pub fn read_ide<R>(&mut self, src: &mut R) -> Result<(), io::Error>
+	where
+		R: Read,
+	{
+		for (section, fields) in sections(src)? {
+			if IDE_SECTIONS.contains(&section.as_str()) {
+				if let Some(model) = fields.get(1) {
+					self.models.insert(model.to_ascii_lowercase());
+				}
+
+				if let Some(texture) = fields.get(2) {
+					self.insert_texture(texture);
+				}
+			} else if section == IDE_TXDP {
+				for texture in fields.iter().take(2) {
+					self.insert_texture(texture);
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Reads the models placed by the text-based IPL file from `src`.
+	pub fn read_ipl<R>(&mut self, src: &mut R) -> Result<(), io::Error>
+	where
+		R: Read,
+	{
+		for (section, fields) in sections(src)? {
+			if section == IPL_INST {
+				if let Some(model) = fields.get(1) {
+					self.models.insert(model.to_ascii_lowercase());
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Adds a reference to the model with the specified name (without an extension), for use with a plain list of names.
+	pub fn add_model(&mut self, model: &str) {
+		self.models.insert(model.to_ascii_lowercase());
+	}
+
+	/// Adds a reference to the texture dictionary with the specified name (without an extension), for use with a plain list of names.
+	pub fn add_texture(&mut self, texture: &str) {
+		self.insert_texture(texture);
+	}
+
+	/// Returns an iterator over the names of the referenced models.
+	pub fn models(&self) -> impl Iterator<Item = &str> {
+		self.models.iter().map(String::as_str)
+	}
+
+	/// Returns an iterator over the names of the referenced texture dictionaries.
+	pub fn textures(&self) -> impl Iterator<Item = &str> {
+		self.textures.iter().map(String::as_str)
+	}
+
+	fn insert_texture(&mut self, texture: &str) {
+		if !texture.eq_ignore_ascii_case(NO_TEXTURE) {
+			self.textures.insert(texture.to_ascii_lowercase());
+		}
+	}
+}
+
+/// Audits the entries of the archive against the definitions, returning any referenced models or texture dictionaries missing from the archive, followed by any orphaned entries.
+/// Only model (`.dff`) and texture dictionary (`.txd`) entries are considered.
+pub fn audit<I>(archive: &Archive<I>, definitions: &Definitions) -> Vec<Finding> {
+	let present: BTreeSet<_> = archive.iter().map(|entry| entry.name.to_ascii_lowercase()).collect();
+	let expected = expected(definitions);
+
+	// Report anything referenced but not present.
+
+	let mut findings: Vec<_> = expected
+		.difference(&present)
+		.map(|name| Finding::Missing {
+			name: name.clone(),
+		})
+		.collect();
+
+	// Report any models or texture dictionaries present but never referenced.
+
+	for entry in archive.iter() {
+		let assessed = entry.has_extension(MODEL_EXTENSION) || entry.has_extension(TEXTURE_EXTENSION);
+
+		if assessed && !expected.contains(&entry.name.to_ascii_lowercase()) {
+			findings.push(Finding::Orphaned {
+				name: entry.name.clone(),
+			});
+		}
+	}
+
+	findings
+}
+
+/// Packs only the models and texture dictionaries referenced by the definitions, resolved from the specified pool of archives and folders, using the specified writer.
+/// Entries are written in order of their names, retaining the names as found within the pool, and any which cannot be resolved are reported rather than treated as an error.
+pub fn pack<I, W>(definitions: &Definitions, pool: &mut Overlay<I>, writer: &mut W) -> Result<Packed, WriteError>
+where
+	I: Read + Seek,
+	W: Writer,
+{
+	let names: HashMap<String, String> = pool.entries().into_iter().map(|(name, _)| (name.to_ascii_lowercase(), name)).collect();
+
+	let mut packed = Packed::default();
+
+	for expected in expected(definitions) {
+		let Some(name) = names.get(&expected) else {
+			packed.unresolved.push(expected);
+
+			continue;
+		};
+
+		match pool.open(name)? {
+			Some(mut src) => writer.write(name, &mut src)?,
+			None => {
+				packed.unresolved.push(expected);
+
+				continue;
+			}
+		}
+
+		packed.written.push(name.clone());
+	}
+
+	Ok(packed)
+}
+
+/// Returns the names of the entries referenced by the definitions, in lowercase.
+fn expected(definitions: &Definitions) -> BTreeSet<String> {
+	definitions
+		.models()
+		.map(|model| format!("{}.{}", model, MODEL_EXTENSION))
+		.chain(definitions.textures().map(|texture| format!("{}.{}", texture, TEXTURE_EXTENSION)))
+		.collect()
+}
+
+/// Splits the lines of the file from `src` into their sections and fields, discarding any comments and blank lines.
+fn sections<R>(src: &mut R) -> Result<Vec<(String, Vec<String>)>, io::Error>
+where
+	R: Read,
+{
+	let mut lines = Vec::new();
+	let mut section: Option<String> = None;
+
+	for line in BufReader::new(src).lines() {
+		let line = line?;
+		let line = line.split(COMMENT).next().unwrap_or_default().trim();
+
+		if line.is_empty() {
+			continue;
+		}
+
+		// Lines outside of a section begin a new one, and the end line closes it.
+
+		let Some(current) = &section else {
+			section = Some(line.to_ascii_lowercase());
+
+			continue;
+		};
+
+		if line.eq_ignore_ascii_case(SECTION_END) {
+			section = None;
+
+			continue;
+		}
+
+		let fields = line.split(',').map(|field| field.trim().to_owned()).collect();
+
+		lines.push((current.clone(), fields));
+	}
+
+	Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		overlay::Overlay,
+		read::{Archive, Reader, V2Reader},
+		write::V2Writer,
+	};
+
+	use super::{audit, pack, Definitions, Finding};
+
+	#[test]
+	fn test_read_definitions() {
+		let mut definitions = Definitions::new();
+
+		let mut ide = Cursor::new("# Vehicles\ncars\n90, landstal, landstal, car, LANDSTAL\n91, kuruma, kuruma, car, KURUMA\nend\nobjs\n1000, Wall01, null, 1, 100, 0\nend\ntxdp\ngeneric, vehicle\nend\n");
+		let mut ipl = Cursor::new("inst\n92, virgo, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1\nend\ncull\n0, 0, 0\nend\n");
+
+		definitions.read_ide(&mut ide).expect("failed to read ide");
+		definitions.read_ipl(&mut ipl).expect("failed to read ipl");
+
+		assert_eq!(definitions.models().collect::<Vec<_>>(), ["kuruma", "landstal", "virgo", "wall01"]);
+		assert_eq!(definitions.textures().collect::<Vec<_>>(), ["generic", "kuruma", "landstal", "vehicle"]);
+	}
+
+	#[test]
+	fn test_audit() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut definitions = Definitions::new();
+
+		definitions.read_ide(&mut Cursor::new("cars\n90, landstal, null\n91, kuruma, null\nend\n")).expect("failed to read ide");
+
+		let findings = audit(&archive, &definitions);
+
+		assert_eq!(findings.len(), 2);
+		assert_eq!(
+			findings[0],
+			Finding::Missing {
+				name: "kuruma.dff".to_owned()
+			}
+		);
+		assert_eq!(
+			findings[1],
+			Finding::Orphaned {
+				name: "VIRGO.DFF".to_owned()
+			}
+		);
+	}
+
+	#[test]
+	fn test_pack() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut pool = Overlay::new(archive);
+		let mut definitions = Definitions::new();
+
+		definitions.add_model("virgo");
+		definitions.add_texture("virgo");
+
+		// Pack the referenced entries, which omits the unreferenced entries within the pool.
+
+		let mut packed = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut packed, 1).expect("failed to create writer");
+
+		let outcome = pack(&definitions, &mut pool, &mut writer).expect("failed to pack archive");
+
+		writer.finish().expect("failed to finish archive");
+
+		assert_eq!(outcome.written, ["VIRGO.DFF"]);
+		assert_eq!(outcome.unresolved, ["virgo.txd"]);
+
+		packed.set_position(0);
+
+		let archive: Archive<_> = V2Reader::new(&mut packed).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 1);
+	}
+}
@@ -0,0 +1,279 @@
+use std::{
+	ffi::OsString,
+	fs::{self, File, OpenOptions},
+	io::{self, Cursor, Read, Seek, SeekFrom, Write},
+	path::{Path, PathBuf},
+};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{
+	error::ReadError,
+	read::{Archive, Reader, V1Reader, V2Reader},
+	workspace::ArchivePaths,
+	ENTRY_SIZE, SECTOR_SIZE, VERSION_2_HEADER, VERSION_2_HEADER_ENTRY_OFFSET,
+};
+
+/// Represents the extension appended to the paths of files backed up in full.
+const BACKUP_EXTENSION: &str = "bak";
+
+/// Represents how much of an archive is backed up.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq)]
+pub enum BackupMode {
+	/// Backs up only the directory and the data of the affected entries, which suffices to revert any modifications made in place (including appending entries).
+	#[default]
+	Entries,
+
+	/// Backs up the archive in full, copying each of its files alongside the originals with an additional `.bak` extension.
+	Full,
+}
+
+/// Represents a backup of an archive taken before its first modification, which can later be restored.
+/// Backups are taken by [`crate::install::apply`] before rewriting each archive in place; edits made via [`crate::read::ArchiveMut`] are held in memory and written to new files, so leave the original untouched.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Backup {
+	paths: ArchivePaths,
+
+	snapshot: Snapshot,
+}
+
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+enum Snapshot {
+	Entries { directory: Vec<u8>, entries: Vec<(u64, Vec<u8>)>, len: u64 },
+	Full { img: PathBuf, dir: Option<PathBuf> },
+}
+
+impl Backup {
+	/// Backs up the archive at the specified paths, including the entries with the specified names (case-insensitively) when backing up only the affected entries.
+	pub fn create(paths: &ArchivePaths, names: &[&str], mode: BackupMode) -> Result<Self, ReadError> {
+		let snapshot = match mode {
+			BackupMode::Entries => snapshot_entries(paths, names)?,
+			BackupMode::Full => {
+				let img = backup_path(paths.img());
+
+				fs::copy(paths.img(), &img)?;
+
+				let dir = match paths {
+					ArchivePaths::V1(paths) => {
+						let dir = backup_path(&paths.dir);
+
+						fs::copy(&paths.dir, &dir)?;
+
+						Some(dir)
+					}
+					ArchivePaths::V2(_) => None,
+				};
+
+				Snapshot::Full {
+					img,
+					dir,
+				}
+			}
+		};
+
+		Ok(Self {
+			paths: paths.clone(),
+			snapshot,
+		})
+	}
+
+	/// Returns the paths of the archive backed up.
+	pub fn paths(&self) -> &ArchivePaths {
+		&self.paths
+	}
+
+	/// Restores the archive from the backup, reverting any modifications made since it was taken.
+	pub fn restore(&self) -> Result<(), std::io::Error> {
+		match &self.snapshot {
+			Snapshot::Entries {
+				directory,
+				entries,
+				len,
+			} => {
+				let mut img = OpenOptions::new().write(true).open(self.paths.img())?;
+
+				// Restore the directory, either as the `dir` file or as the header.
+
+				match &self.paths {
+					ArchivePaths::V1(paths) => fs::write(&paths.dir, directory)?,
+					ArchivePaths::V2(_) => img.write_all(directory)?,
+				}
+
+				for (offset, data) in entries {
+					img.seek(SeekFrom::Start(*offset))?;
+					img.write_all(data)?;
+				}
+
+				// Discard anything appended since.
+
+				img.set_len(*len)?;
+				img.flush()
+			}
+			Snapshot::Full {
+				img,
+				dir,
+			} => {
+				fs::copy(img, self.paths.img())?;
+
+				if let (Some(dir), ArchivePaths::V1(paths)) = (dir, &self.paths) {
+					fs::copy(dir, &paths.dir)?;
+				}
+
+				Ok(())
+			}
+		}
+	}
+}
+
+fn snapshot_entries(paths: &ArchivePaths, names: &[&str]) -> Result<Snapshot, ReadError> {
+	let mut img = File::open(paths.img())?;
+	let len = img.metadata()?.len();
+
+	// Read the directory, either from the `dir` file or from the header.
+
+	let directory = match paths {
+		ArchivePaths::V1(paths) => fs::read(&paths.dir)?,
+		ArchivePaths::V2(_) => {
+			let mut header = vec![0; VERSION_2_HEADER_ENTRY_OFFSET as usize];
+
+			img.read_exact(&mut header)?;
+
+			let count = (&header[VERSION_2_HEADER.len()..]).read_u32::<LittleEndian>()? as u64;
+			let end = VERSION_2_HEADER_ENTRY_OFFSET + count * ENTRY_SIZE;
+
+			// Check the declared header fits within the archive before allocating for it, as a malformed archive could otherwise exhaust memory.
+
+			if end > len {
+				return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+			}
+
+			// Include the padding up to the end of the final sector of the header, such that any records appended since are also reverted.
+
+			let end = end.next_multiple_of(SECTOR_SIZE).min(len);
+
+			header.resize(end as usize, 0);
+			img.read_exact(&mut header[VERSION_2_HEADER_ENTRY_OFFSET as usize..])?;
+			img.rewind()?;
+
+			header
+		}
+	};
+
+	let mut archive: Archive<_> = match paths {
+		ArchivePaths::V1(_) => V1Reader::new(&mut Cursor::new(&directory), &mut img).read()?,
+		ArchivePaths::V2(_) => V2Reader::new(&mut img).read()?,
+	};
+
+	// Back up the data of each of the affected entries.
+
+	let mut entries = Vec::new();
+
+	for index in 0..archive.len() {
+		let Some(entry) = archive.get(index).filter(|entry| names.iter().any(|name| entry.name.eq_ignore_ascii_case(name))) else {
+			continue;
+		};
+
+		let offset = entry.offset_bytes();
+		let data = archive.read_entry(index)?.unwrap_or_default();
+
+		entries.push((offset, data));
+	}
+
+	Ok(Snapshot::Entries {
+		directory,
+		entries,
+		len,
+	})
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+	let mut path = OsString::from(path);
+
+	path.push(".");
+	path.push(BACKUP_EXTENSION);
+
+	PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		fs::{self, OpenOptions},
+		io::{Seek, SeekFrom, Write},
+		path::PathBuf,
+	};
+
+	use crate::{error::ReadError, paths::V1Paths, workspace::ArchivePaths};
+
+	use super::{Backup, BackupMode};
+
+	fn copy_fixtures(name: &str) -> PathBuf {
+		let folder = std::env::temp_dir().join(format!("gta-img-backup-{}-{}", name, std::process::id()));
+
+		fs::create_dir_all(&folder).expect("failed to create folder");
+
+		for file in ["v1.img", "v1.dir", "v2.img"] {
+			fs::copy(PathBuf::from("test").join(file), folder.join(file)).expect("failed to copy fixture");
+		}
+
+		folder
+	}
+
+	fn modify(path: &PathBuf) {
+		let mut file = OpenOptions::new().write(true).open(path).expect("failed to open file");
+
+		file.seek(SeekFrom::Start(2048)).expect("failed to seek file");
+		file.write_all(&[0xEE; 16]).expect("failed to modify file");
+		file.seek(SeekFrom::End(0)).expect("failed to seek file");
+		file.write_all(&[0xEE; 2048]).expect("failed to append to file");
+	}
+
+	#[test]
+	fn test_restore_entries() {
+		let folder = copy_fixtures("entries");
+		let paths = ArchivePaths::V2(folder.join("v2.img"));
+
+		let backup = Backup::create(&paths, &["virgo.dff"], BackupMode::Entries).expect("failed to create backup");
+
+		modify(&folder.join("v2.img"));
+		backup.restore().expect("failed to restore backup");
+
+		assert_eq!(fs::read(folder.join("v2.img")).expect("failed to read file"), include_bytes!("../test/v2.img"));
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+
+	#[test]
+	fn test_create_hostile_count() {
+		let folder = copy_fixtures("hostile");
+		let paths = ArchivePaths::V2(folder.join("v2.img"));
+
+		let mut file = OpenOptions::new().write(true).open(folder.join("v2.img")).expect("failed to open file");
+
+		file.seek(SeekFrom::Start(4)).expect("failed to seek file");
+		file.write_all(&u32::MAX.to_le_bytes()).expect("failed to modify file");
+
+		assert!(matches!(Backup::create(&paths, &["virgo.dff"], BackupMode::Entries), Err(ReadError::IoError(_))));
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+
+	#[test]
+	fn test_restore_full() {
+		let folder = copy_fixtures("full");
+		let paths = ArchivePaths::V1(V1Paths::from_img(folder.join("v1.img")).expect("failed to locate archive"));
+
+		let backup = Backup::create(&paths, &[], BackupMode::Full).expect("failed to create backup");
+
+		assert!(folder.join("v1.img.bak").exists());
+
+		modify(&folder.join("v1.img"));
+		fs::write(folder.join("v1.dir"), []).expect("failed to truncate dir");
+		backup.restore().expect("failed to restore backup");
+
+		assert_eq!(fs::read(folder.join("v1.img")).expect("failed to read file"), include_bytes!("../test/v1.img"));
+		assert_eq!(fs::read(folder.join("v1.dir")).expect("failed to read file"), include_bytes!("../test/v1.dir"));
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+}
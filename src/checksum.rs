@@ -0,0 +1,220 @@
+use std::io::{self, BufRead, BufReader, Read, Seek, Write};
+
+use crate::{error::ChecksumError, read::Archive, SECTOR_SIZE};
+
+/// Represents the prefix of a comment line in an `sfv` file.
+const SFV_COMMENT: char = ';';
+
+/// Represents a discrepancy found when verifying an archive against a checksum file.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum Discrepancy {
+	/// Indicates that the checksum of an entry did not match the expected checksum.
+	Mismatch {
+		/// The name of the entry.
+		name: String,
+
+		/// The checksum listed in the checksum file.
+		expected: u32,
+
+		/// The checksum calculated from the archive.
+		actual: u32,
+	},
+
+	/// Indicates that an entry listed in the checksum file is not present in the archive.
+	Missing {
+		/// The name of the entry.
+		name: String,
+	},
+
+	/// Indicates that an entry present in the archive is not listed in the checksum file.
+	Unlisted {
+		/// The name of the entry.
+		name: String,
+	},
+}
+
+/// Calculates the CRC32 checksum of the data of the entry at the specified index, if it exists.
+/// The checksum includes any padding up to the end of the final sector of the entry.
+pub fn entry_crc32<I>(archive: &mut Archive<I>, index: usize) -> Result<Option<u32>, io::Error>
+where
+	I: Read + Seek,
+{
+	let Some(mut open) = archive.open(index) else {
+		return Ok(None);
+	};
+
+	let mut hasher = crc32fast::Hasher::new();
+	let mut buf = [0; SECTOR_SIZE as usize];
+
+	loop {
+		let len = open.read(&mut buf)?;
+
+		if len == 0 {
+			break;
+		}
+
+		hasher.update(&buf[0..len]);
+	}
+
+	Ok(Some(hasher.finalize()))
+}
+
+/// Calculates the CRC32 checksum of the data of each of the entries in the archive, attaching it to the attributes of each entry.
+pub fn annotate_crc32<I>(archive: &mut Archive<I>) -> Result<(), io::Error>
+where
+	I: Read + Seek,
+{
+	for index in 0..archive.len() {
+		let crc = entry_crc32(archive, index)?;
+
+		archive.entries_mut()[index].attributes.crc32 = crc;
+	}
+
+	Ok(())
+}
+
+/// Writes an `sfv` file to `dst` listing the name and CRC32 checksum of each of the entries in the archive.
+pub fn write_sfv<I, W>(archive: &mut Archive<I>, dst: &mut W) -> Result<(), ChecksumError>
+where
+	I: Read + Seek,
+	W: Write,
+{
+	for index in 0..archive.len() {
+		let crc = entry_crc32(archive, index)?.unwrap_or_default();
+
+		if let Some(entry) = archive.get(index) {
+			write_line(dst, &entry.name, crc)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Writes a single line of an `sfv` file to `dst`, listing the specified name and CRC32 checksum.
+pub(crate) fn write_line<W>(dst: &mut W, name: &str, crc: u32) -> Result<(), io::Error>
+where
+	W: Write,
+{
+	writeln!(dst, "{} {:08X}", name, crc)
+}
+
+/// Verifies each of the entries in the archive against the `sfv` file from `src`, returning any discrepancies found.
+/// Names are compared case-insensitively, in line with the behaviour of the games.
+pub fn verify_sfv<I, S>(archive: &mut Archive<I>, src: &mut S) -> Result<Vec<Discrepancy>, ChecksumError>
+where
+	I: Read + Seek,
+	S: Read,
+{
+	let mut discrepancies = Vec::new();
+	let mut listed = vec![false; archive.len()];
+
+	for (number, line) in BufReader::new(src).lines().enumerate() {
+		let line = line?;
+		let line = line.trim();
+
+		// Skip any blank lines and comments.
+
+		if line.is_empty() || line.starts_with(SFV_COMMENT) {
+			continue;
+		}
+
+		// Split the line into the name and the checksum, which is always the last field.
+
+		let (name, crc) = line.rsplit_once(char::is_whitespace).ok_or(ChecksumError::InvalidLine(number + 1))?;
+		let name = name.trim_end();
+		let expected = u32::from_str_radix(crc, 16).map_err(|_| ChecksumError::InvalidLine(number + 1))?;
+
+		// Find the matching entry and compare the checksums.
+
+		let Some(index) = archive.iter().position(|entry| entry.name.eq_ignore_ascii_case(name)) else {
+			discrepancies.push(Discrepancy::Missing {
+				name: name.to_owned(),
+			});
+
+			continue;
+		};
+
+		listed[index] = true;
+
+		let actual = entry_crc32(archive, index)?.unwrap_or_default();
+
+		if actual != expected {
+			discrepancies.push(Discrepancy::Mismatch {
+				name: name.to_owned(),
+				expected,
+				actual,
+			});
+		}
+	}
+
+	// Report any entries that were never listed.
+
+	for (entry, _) in archive.iter().zip(listed).filter(|(_, listed)| !listed) {
+		discrepancies.push(Discrepancy::Unlisted {
+			name: entry.name.clone(),
+		});
+	}
+
+	Ok(discrepancies)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::read::{Archive, Reader, V2Reader};
+
+	use super::{annotate_crc32, entry_crc32, verify_sfv, write_sfv, Discrepancy};
+
+	#[test]
+	fn test_write_sfv() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut sfv = Vec::new();
+
+		write_sfv(&mut archive, &mut sfv).expect("failed to write sfv");
+
+		let sfv = String::from_utf8(sfv).expect("expected valid sfv");
+		let lines: Vec<_> = sfv.lines().collect();
+
+		assert_eq!(lines.len(), 3);
+		assert!(lines[0].starts_with("VIRGO.DFF "));
+		assert_eq!(lines[0].len(), "VIRGO.DFF ".len() + 8);
+	}
+
+	#[test]
+	fn test_annotate_crc32() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		annotate_crc32(&mut archive).expect("failed to annotate archive");
+
+		let expected = entry_crc32(&mut archive, 0).expect("failed to calculate checksum");
+
+		assert!(expected.is_some());
+		assert_eq!(archive.get(0).and_then(|entry| entry.attributes.crc32), expected);
+	}
+
+	#[test]
+	fn test_verify_sfv() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut sfv = Vec::new();
+
+		write_sfv(&mut archive, &mut sfv).expect("failed to write sfv");
+
+		let discrepancies = verify_sfv(&mut archive, &mut Cursor::new(&sfv)).expect("failed to verify sfv");
+
+		assert!(discrepancies.is_empty());
+
+		let mut sfv = Cursor::new("; comment\nvirgo.dff 00000000\nMISSING.TXD 12345678\n");
+		let discrepancies = verify_sfv(&mut archive, &mut sfv).expect("failed to verify sfv");
+
+		assert_eq!(discrepancies.len(), 4);
+		assert!(matches!(&discrepancies[0], Discrepancy::Mismatch { name, expected: 0, .. } if name == "virgo.dff"));
+		assert!(matches!(&discrepancies[1], Discrepancy::Missing { name } if name == "MISSING.TXD"));
+		assert!(matches!(&discrepancies[2], Discrepancy::Unlisted { name } if name == "LANDSTAL.DFF"));
+	}
+}
@@ -0,0 +1,220 @@
+use std::{
+	fmt,
+	io::{self, Read, Seek, Write},
+};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::read::{self, Archive};
+
+/// Represents the extension of collision entries.
+const COL_EXTENSION: &str = "col";
+
+/// Represents the four-character codes identifying each version of collision model.
+pub(crate) const COL_FOURCCS: [&[u8; 4]; 4] = [b"COLL", b"COL2", b"COL3", b"COL4"];
+
+/// Represents the size of the header of a collision model preceding its size field.
+const COL_HEADER_SIZE: usize = 8;
+
+/// Represents the length of the name of a collision model, including the null terminator.
+const COL_NAME_SIZE: usize = 22;
+
+/// Represents a single collision model within a collision entry, which may contain several.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Collision {
+	/// The version of the collision model, from 1 to 4.
+	pub version: u8,
+
+	/// The name of the model the collision model belongs to.
+	pub name: String,
+
+	/// The identifier of the model the collision model belongs to (unused by version 1).
+	pub model_id: u16,
+
+	/// The raw data of the collision model, including its header.
+	pub data: Vec<u8>,
+}
+
+/// Reads each of the collision models from `src`, splitting them into individual collision models.
+/// Reading stops at the end of the source or at any zero padding (e.g. up to the end of the final sector of an entry).
+pub fn read_collisions<R>(src: &mut R) -> Result<Vec<Collision>, io::Error>
+where
+	R: Read,
+{
+	let mut collisions = Vec::new();
+
+	loop {
+		// Read the four-character code and the size of the remainder of the collision model, stopping at the end or at padding.
+
+		let mut header = [0; COL_HEADER_SIZE];
+		let len = read::read_fully(src, &mut header)?;
+
+		if len == 0 || header[..len].iter().all(|&b| b == 0) {
+			break;
+		}
+
+		if len < header.len() {
+			return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+		}
+
+		let Some(version) = COL_FOURCCS.iter().position(|fourcc| header[..4] == fourcc[..]) else {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "unrecognised collision model"));
+		};
+
+		let size = (&header[4..]).read_u32::<LittleEndian>()? as usize;
+
+		if size < COL_NAME_SIZE + 2 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "collision model too small"));
+		}
+
+		let mut data = header.to_vec();
+
+		src.take(size as u64).read_to_end(&mut data)?;
+
+		if data.len() < COL_HEADER_SIZE + size {
+			return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+		}
+
+		// Read the name as a null-terminated string, followed by the identifier.
+
+		let name = data[COL_HEADER_SIZE..COL_HEADER_SIZE + COL_NAME_SIZE].iter().take_while(|&&b| b != 0).map(|&b| b as char).collect();
+		let model_id = (&data[COL_HEADER_SIZE + COL_NAME_SIZE..]).read_u16::<LittleEndian>()?;
+
+		collisions.push(Collision {
+			version: version as u8 + 1,
+			name,
+			model_id,
+			data,
+		});
+	}
+
+	Ok(collisions)
+}
+
+/// Merges the specified collision models into a single collision entry, written to `dst`.
+pub fn merge_collisions<W>(collisions: &[Collision], dst: &mut W) -> Result<(), io::Error>
+where
+	W: Write,
+{
+	for collision in collisions {
+		dst.write_all(&collision.data)?;
+	}
+
+	Ok(())
+}
+
+/// Reads each of the collision models within the entry at the specified index, if the entry exists and is a collision entry.
+pub fn entry_collisions<I>(archive: &mut Archive<I>, index: usize) -> Result<Option<Vec<Collision>>, io::Error>
+where
+	I: Read + Seek,
+{
+	let is_col = archive.get(index).is_some_and(|entry| entry.has_extension(COL_EXTENSION));
+
+	if !is_col {
+		return Ok(None);
+	}
+
+	match archive.open(index) {
+		Some(mut open) => read_collisions(&mut open).map(Some),
+		None => Ok(None),
+	}
+}
+
+/// Finds the collision model belonging to the model with the specified name across each of the collision entries, returning the index of the entry along with the collision model itself.
+/// Names are compared case-insensitively, in line with the behaviour of the games.
+pub fn find_collision<I>(archive: &mut Archive<I>, name: &str) -> Result<Option<(usize, Collision)>, io::Error>
+where
+	I: Read + Seek,
+{
+	for index in 0..archive.len() {
+		let collisions = entry_collisions(archive, index)?.unwrap_or_default();
+
+		if let Some(collision) = collisions.into_iter().find(|collision| collision.name.eq_ignore_ascii_case(name)) {
+			return Ok(Some((index, collision)));
+		}
+	}
+
+	Ok(None)
+}
+
+impl fmt::Display for Collision {
+	/// Formats the collision model as its name, followed by its version and identifier (e.g. `landstal (COL3, #400)`).
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let fourcc = COL_FOURCCS[(self.version as usize).saturating_sub(1).min(COL_FOURCCS.len() - 1)];
+
+		write!(f, "{} ({}, #{})", self.name, String::from_utf8_lossy(fourcc), self.model_id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::read::{Archive, Entry};
+
+	use super::{entry_collisions, find_collision, merge_collisions, read_collisions};
+
+	fn collision(fourcc: &[u8; 4], name: &str, model_id: u16) -> Vec<u8> {
+		let mut data = fourcc.to_vec();
+
+		data.extend_from_slice(&32u32.to_le_bytes());
+		data.extend(name.bytes().chain(std::iter::repeat(0)).take(22));
+		data.extend_from_slice(&model_id.to_le_bytes());
+		data.extend([0xAA; 8]);
+		data
+	}
+
+	#[test]
+	fn test_read_collisions() {
+		let mut data = collision(b"COL3", "landstal", 400);
+
+		data.extend(collision(b"COLL", "virgo", 0));
+
+		let collisions = read_collisions(&mut Cursor::new(&data)).expect("failed to read collisions");
+
+		assert_eq!(collisions.len(), 2);
+		assert_eq!(collisions[0].to_string(), "landstal (COL3, #400)");
+		assert_eq!(collisions[1].to_string(), "virgo (COLL, #0)");
+
+		// Versions outside of the range should be formatted as the nearest version, rather than panicking.
+
+		let mut invalid = collisions[1].clone();
+
+		invalid.version = 0;
+
+		assert_eq!(invalid.to_string(), "virgo (COLL, #0)");
+
+		let mut merged = Vec::new();
+
+		merge_collisions(&collisions, &mut merged).expect("failed to merge collisions");
+
+		assert_eq!(merged, data);
+
+		assert!(read_collisions(&mut Cursor::new(b"XXXX\x20\0\0\0")).is_err());
+	}
+
+	#[test]
+	fn test_entry_collisions() {
+		let mut data = collision(b"COL2", "vehicles", 0);
+
+		data.resize(2048, 0);
+
+		let mut img = Cursor::new(data);
+		let mut archive = Archive::from_entries(
+			&mut img,
+			vec![
+				Entry::new("VEHICLES.COL", 0, 1).expect("failed to create entry"),
+				Entry::new("VEHICLES.TXD", 0, 1).expect("failed to create entry"),
+			],
+		);
+
+		let collisions = entry_collisions(&mut archive, 0).expect("failed to read collisions").expect("expected collisions");
+
+		assert_eq!(collisions.len(), 1);
+		assert_eq!(collisions[0].name, "vehicles");
+		assert_eq!(entry_collisions(&mut archive, 1).expect("failed to read collisions"), None);
+
+		assert_eq!(find_collision(&mut archive, "VEHICLES").expect("failed to find collision").map(|(index, _)| index), Some(0));
+		assert!(find_collision(&mut archive, "missing").expect("failed to find collision").is_none());
+	}
+}
@@ -0,0 +1,185 @@
+use std::{
+	collections::HashMap,
+	io::{self, Read, Seek},
+};
+
+use crate::{
+	checksum,
+	read::{Archive, Entry},
+};
+
+/// Represents the differences between two archives, with entries matched by name (case-insensitively) and compared by their content.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct ArchiveDiff {
+	/// The entries present only in the second archive.
+	pub added: Vec<Entry>,
+
+	/// The entries present only in the first archive.
+	pub removed: Vec<Entry>,
+
+	/// The entries present in both archives with the same content, but with different names.
+	pub renamed: Vec<Change>,
+
+	/// The entries present in both archives with the same name, but with different content.
+	pub modified: Vec<Change>,
+}
+
+/// Represents an entry changed between two archives.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Change {
+	/// The entry within the first archive.
+	pub before: Entry,
+
+	/// The entry within the second archive.
+	pub after: Entry,
+}
+
+impl ArchiveDiff {
+	/// Returns whether the archives have no differences.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty() && self.modified.is_empty()
+	}
+}
+
+/// Compares the entries of two archives, returning the differences from `a` to `b`.
+/// The content of entries is compared by length and CRC32 checksum, such that entries moved within the archive are not considered modified.
+pub fn diff<A, B>(a: &mut Archive<A>, b: &mut Archive<B>) -> Result<ArchiveDiff, io::Error>
+where
+	A: Read + Seek,
+	B: Read + Seek,
+{
+	let before = contents(a)?;
+	let after = contents(b)?;
+
+	let names = |contents: &[(Entry, u32)]| -> HashMap<String, usize> {
+		let mut names = HashMap::new();
+
+		for (index, (entry, _)) in contents.iter().enumerate().rev() {
+			names.insert(entry.name.to_ascii_lowercase(), index);
+		}
+
+		names
+	};
+
+	let names_before = names(&before);
+	let names_after = names(&after);
+
+	let mut diff = ArchiveDiff::default();
+
+	// Compare the entries present in both archives, setting aside those present in only one.
+
+	let mut removed = Vec::new();
+
+	for (index, (entry, crc)) in before.iter().enumerate() {
+		if names_before.get(&entry.name.to_ascii_lowercase()) != Some(&index) {
+			continue;
+		}
+
+		match names_after.get(&entry.name.to_ascii_lowercase()) {
+			Some(&other) => {
+				let (other, other_crc) = &after[other];
+
+				if entry.length != other.length || crc != other_crc {
+					diff.modified.push(Change {
+						before: entry.clone(),
+						after: other.clone(),
+					});
+				}
+			}
+			None => removed.push((entry, *crc)),
+		}
+	}
+
+	let mut added: Vec<_> = after
+		.iter()
+		.enumerate()
+		.filter(|(index, (entry, _))| {
+			names_after.get(&entry.name.to_ascii_lowercase()) == Some(index) && !names_before.contains_key(&entry.name.to_ascii_lowercase())
+		})
+		.map(|(_, (entry, crc))| (entry, *crc))
+		.collect();
+
+	// Match any removed entries against added entries with the same content, as renames.
+
+	for (entry, crc) in removed {
+		match added.iter().position(|(other, other_crc)| other.length == entry.length && *other_crc == crc) {
+			Some(position) => {
+				let (other, _) = added.remove(position);
+
+				diff.renamed.push(Change {
+					before: entry.clone(),
+					after: other.clone(),
+				});
+			}
+			None => diff.removed.push(entry.clone()),
+		}
+	}
+
+	diff.added = added.into_iter().map(|(entry, _)| entry.clone()).collect();
+
+	Ok(diff)
+}
+
+fn contents<I>(archive: &mut Archive<I>) -> Result<Vec<(Entry, u32)>, io::Error>
+where
+	I: Read + Seek,
+{
+	let mut contents = Vec::with_capacity(archive.len());
+
+	for index in 0..archive.len() {
+		let crc = checksum::entry_crc32(archive, index)?.unwrap_or_default();
+
+		if let Some(entry) = archive.get(index) {
+			contents.push((entry.clone(), crc));
+		}
+	}
+
+	Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::read::{Archive, Entry};
+
+	use super::diff;
+
+	#[test]
+	fn test_diff() {
+		let mut a = Cursor::new([vec![1; 2048], vec![2; 2048], vec![3; 2048], vec![4; 2048]].concat());
+		let mut b = Cursor::new([vec![1; 2048], vec![5; 2048], vec![3; 2048], vec![6; 2048]].concat());
+
+		let mut a = Archive::from_entries(
+			&mut a,
+			vec![
+				Entry::new("SAME.DFF", 0, 1).expect("failed to create entry"),
+				Entry::new("MODIFIED.DFF", 1, 1).expect("failed to create entry"),
+				Entry::new("OLD.TXD", 2, 1).expect("failed to create entry"),
+				Entry::new("REMOVED.COL", 3, 1).expect("failed to create entry"),
+			],
+		);
+
+		let mut b = Archive::from_entries(
+			&mut b,
+			vec![
+				Entry::new("same.dff", 0, 1).expect("failed to create entry"),
+				Entry::new("MODIFIED.DFF", 1, 1).expect("failed to create entry"),
+				Entry::new("NEW.TXD", 2, 1).expect("failed to create entry"),
+				Entry::new("ADDED.IFP", 3, 1).expect("failed to create entry"),
+			],
+		);
+
+		let diff = diff(&mut a, &mut b).expect("failed to diff archives");
+
+		assert_eq!(diff.added.iter().map(|entry| entry.name.as_str()).collect::<Vec<_>>(), ["ADDED.IFP"]);
+		assert_eq!(diff.removed.iter().map(|entry| entry.name.as_str()).collect::<Vec<_>>(), ["REMOVED.COL"]);
+		assert_eq!(
+			diff.renamed.iter().map(|change| (change.before.name.as_str(), change.after.name.as_str())).collect::<Vec<_>>(),
+			[("OLD.TXD", "NEW.TXD")]
+		);
+		assert_eq!(diff.modified.iter().map(|change| change.before.name.as_str()).collect::<Vec<_>>(), ["MODIFIED.DFF"]);
+
+		assert!(!diff.is_empty());
+	}
+}
@@ -1,5 +1,7 @@
 use core::fmt;
-use std::{error::Error, io};
+use std::{error::Error, io, path::PathBuf};
+
+use crate::{game::Violation, limit::Exceeded};
 
 /// Represents a read-related error.
 #[derive(Debug)]
@@ -9,6 +11,99 @@ pub enum ReadError {
 
 	/// Indicates that the header was not in the expected format for the version.
 	InvalidHeader,
+
+	/// Indicates that the archive was detected as a V1-styled archive, but no `dir` file was provided.
+	MissingDirectory,
+
+	/// Indicates that an I/O error occurred whilst reading an entry from the directory.
+	EntryError {
+		/// The index of the entry.
+		index: usize,
+
+		/// The name of the entry, if known.
+		name: Option<String>,
+
+		/// The offset, in bytes, within the directory at which the error occurred.
+		offset: u64,
+
+		/// The underlying error.
+		error: io::Error,
+	},
+
+	/// Indicates that the name of an entry contained characters rejected by the name policy.
+	InvalidName {
+		/// The index of the entry.
+		index: usize,
+
+		/// The name of the entry, with any rejected characters replaced.
+		name: String,
+
+		/// The offset, in bytes, of the name within the directory.
+		offset: u64,
+	},
+
+	/// Indicates that there is no entry at the specified index.
+	InvalidIndex(usize),
+
+	/// Indicates that the data of an entry extends beyond the end of the archive.
+	OutOfBounds {
+		/// The index of the entry.
+		index: usize,
+
+		/// The name of the entry.
+		name: String,
+
+		/// The offset, in bytes, of the end of the entry.
+		end: u64,
+
+		/// The length, in bytes, of the archive.
+		len: u64,
+	},
+
+	/// Indicates that the archive contains more entries than permitted.
+	TooManyEntries {
+		/// The number of entries encountered.
+		count: usize,
+
+		/// The maximum number of entries permitted.
+		max: usize,
+	},
+
+	/// Indicates that the directory of the archive is larger than permitted.
+	DirectoryTooLarge {
+		/// The size, in bytes, of the directory encountered.
+		size: u64,
+
+		/// The maximum size, in bytes, of the directory permitted.
+		max: u64,
+	},
+
+	/// Indicates that a value of an entry exceeds a limit imposed by the format of the archive.
+	LimitExceeded {
+		/// The index of the entry.
+		index: usize,
+
+		/// The limit exceeded, and by which value.
+		exceeded: Exceeded,
+	},
+
+	/// Indicates that the directory ended part-way through an entry, as detected when reading strictly.
+	TruncatedDirectory {
+		/// The index of the entry.
+		index: usize,
+
+		/// The offset, in bytes, within the directory at which it ended.
+		offset: u64,
+	},
+
+	/// Indicates that the unclaimed regions of the archive contain more data than permitted.
+	UnclaimedTooLarge {
+		/// The size, in bytes, of the data encountered so far.
+		size: u64,
+
+		/// The maximum size, in bytes, of the data permitted.
+		max: u64,
+	},
 }
 
 /// Represents a write-related error.
@@ -18,20 +113,251 @@ pub enum WriteError {
 	IoError(io::Error),
 
 	/// Indicates that there is insufficient size in the header to add further entries.
-	InsufficientHeaderSize,
+	InsufficientHeaderSize {
+		/// The index the entry would have been written at.
+		index: usize,
+
+		/// The name of the entry.
+		name: String,
+	},
 
 	/// Indicates that the provided name of an entry is longer than 23 characters.
-	InvalidNameLength
+	#[deprecated(note = "names which are too long are reported as `LimitExceeded` instead")]
+	InvalidNameLength,
+
+	/// Indicates that the provided name of an entry contains a character that cannot be represented as a single byte, or that is rejected by the write options.
+	InvalidName {
+		/// The index the entry would have been written at.
+		index: usize,
+
+		/// The name of the entry.
+		name: String,
+
+		/// The offending character.
+		character: char,
+	},
+
+	/// Indicates that a value of an entry exceeds a limit imposed by the format of the archive, and so cannot be written as-is.
+	LimitExceeded {
+		/// The index the entry would have been written at.
+		index: usize,
+
+		/// The name of the entry.
+		name: String,
+
+		/// The limit exceeded, and by which value.
+		exceeded: Exceeded,
+	},
+
+	/// Indicates that writing an entry would violate the game profile of the write options.
+	ProfileViolation {
+		/// The index the entry would have been written at.
+		index: usize,
+
+		/// The name of the entry.
+		name: String,
+
+		/// The violation.
+		violation: Violation,
+	},
+
+	/// Indicates that an I/O error occurred whilst writing a particular entry.
+	EntryError {
+		/// The index of the entry.
+		index: usize,
+
+		/// The name of the entry.
+		name: String,
+
+		/// The underlying I/O error.
+		error: io::Error,
+	},
+}
+
+/// Represents a path-related error.
+#[derive(Debug)]
+pub enum PathError {
+	/// Indicates that a generic I/O error occurred.
+	IoError(io::Error),
+
+	/// Indicates that the file at the specified path does not exist.
+	Missing(PathBuf),
+
+	/// Indicates that the `img` file and the `dir` file do not share the same stem.
+	MismatchedStems {
+		/// The path of the `img` file.
+		img: PathBuf,
+
+		/// The path of the `dir` file.
+		dir: PathBuf,
+	},
+
+	/// Indicates that the size of the `dir` file is not a multiple of the size of an entry.
+	InvalidDirectorySize(u64),
+}
+
+/// Represents a checksum-related error.
+#[derive(Debug)]
+pub enum ChecksumError {
+	/// Indicates that a generic I/O error occurred.
+	IoError(io::Error),
+
+	/// Indicates that the specified line of a checksum file was not in the expected format.
+	InvalidLine(usize),
+}
+
+/// Represents an install-related error.
+#[derive(Debug)]
+pub enum InstallError {
+	/// Indicates that a generic I/O error occurred.
+	IoError(io::Error),
+
+	/// Indicates that an archive could not be read whilst being installed into.
+	ReadError(ReadError),
+
+	/// Indicates that an archive could not be written whilst being installed into.
+	WriteError(WriteError),
+
+	/// Indicates that no archive matches the specified target of a plan.
+	MissingTarget(String),
+}
+
+/// Represents an index-related error.
+#[derive(Debug)]
+pub enum IndexError {
+	/// Indicates that a generic I/O error occurred.
+	IoError(io::Error),
+
+	/// Indicates that an archive could not be read whilst being indexed.
+	ReadError(ReadError),
+
+	/// Indicates that the specified line of an index file was not in the expected format.
+	InvalidLine(usize),
+
+	/// Indicates that the specified path could not be written to an index file, as it is not valid UTF-8.
+	InvalidPath(PathBuf),
+}
+
+impl Error for ReadError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::IoError(err) => Some(err),
+			Self::EntryError {
+				error,
+				..
+			} => Some(error),
+			Self::LimitExceeded {
+				exceeded,
+				..
+			} => Some(exceeded),
+			_ => None,
+		}
+	}
+}
+
+impl Error for WriteError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::IoError(err) => Some(err),
+			Self::EntryError {
+				error,
+				..
+			} => Some(error),
+			Self::LimitExceeded {
+				exceeded,
+				..
+			} => Some(exceeded),
+			_ => None,
+		}
+	}
+}
+
+impl Error for PathError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::IoError(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl Error for ChecksumError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::IoError(err) => Some(err),
+			_ => None,
+		}
+	}
 }
 
-impl Error for ReadError {}
-impl Error for WriteError {}
+impl Error for InstallError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::IoError(err) => Some(err),
+			Self::ReadError(err) => Some(err),
+			Self::WriteError(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl Error for IndexError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::IoError(err) => Some(err),
+			Self::ReadError(err) => Some(err),
+			_ => None,
+		}
+	}
+}
 
 impl fmt::Display for ReadError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			Self::IoError(err) => write!(f, "input/output error [{}]", err),
 			Self::InvalidHeader => write!(f, "invalid header"),
+			Self::MissingDirectory => write!(f, "missing dir file for V1-styled archive"),
+			Self::EntryError {
+				index,
+				name,
+				offset,
+				error,
+			} => match name {
+				Some(name) => write!(f, "input/output error reading entry [#{} {}] at offset {} [{}]", index, name, offset, error),
+				None => write!(f, "input/output error reading entry [#{}] at offset {} [{}]", index, offset, error),
+			},
+			Self::InvalidName {
+				index,
+				name,
+				offset,
+			} => write!(f, "invalid name of entry [#{} {}] at offset {}", index, name, offset),
+			Self::InvalidIndex(index) => write!(f, "invalid index [{}]", index),
+			Self::OutOfBounds {
+				index,
+				name,
+				end,
+				len,
+			} => write!(f, "entry [#{} {}] ends at offset {} beyond end of archive at {}", index, name, end, len),
+			Self::TooManyEntries {
+				count,
+				max,
+			} => write!(f, "too many entries [{} exceeds {}]", count, max),
+			Self::DirectoryTooLarge {
+				size,
+				max,
+			} => write!(f, "directory too large [{} exceeds {}]", size, max),
+			Self::UnclaimedTooLarge {
+				size,
+				max,
+			} => write!(f, "unclaimed data too large [{} exceeds {}]", size, max),
+			Self::LimitExceeded {
+				index,
+				exceeded,
+			} => write!(f, "{} for entry [#{}]", exceeded, index),
+			Self::TruncatedDirectory {
+				index,
+				offset,
+			} => write!(f, "directory truncated within entry [#{}] at offset {}", index, offset),
 		}
 	}
 }
@@ -40,8 +366,100 @@ impl fmt::Display for WriteError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			Self::IoError(err) => write!(f, "input/output error [{}]", err),
-			Self::InsufficientHeaderSize => write!(f, "insufficient header size"),
-			Self::InvalidNameLength => write!(f, "invalid name length")
+			Self::InsufficientHeaderSize {
+				index,
+				name,
+			} => write!(f, "insufficient header size for entry [#{} {}]", index, name),
+			#[allow(deprecated)]
+			Self::InvalidNameLength => write!(f, "invalid name length"),
+			Self::InvalidName {
+				index,
+				name,
+				character,
+			} => write!(f, "invalid character {:?} in name of entry [#{} {}]", character, index, name),
+			Self::LimitExceeded {
+				index,
+				name,
+				exceeded,
+			} => write!(f, "{} for entry [#{} {}]", exceeded, index, name),
+			Self::ProfileViolation {
+				index,
+				name,
+				violation,
+			} => write!(f, "profile violation ({}) for entry [#{} {}]", violation, index, name),
+			Self::EntryError {
+				index,
+				name,
+				error,
+			} => write!(f, "input/output error writing entry [#{} {}] [{}]", index, name, error),
+		}
+	}
+}
+
+impl fmt::Display for PathError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::IoError(err) => write!(f, "input/output error [{}]", err),
+			Self::Missing(path) => write!(f, "missing file [{}]", path.display()),
+			Self::MismatchedStems {
+				img,
+				dir,
+			} => write!(f, "mismatched img file and dir file [{} {}]", img.display(), dir.display()),
+			Self::InvalidDirectorySize(size) => write!(f, "invalid size of dir file [{}]", size),
+		}
+	}
+}
+
+impl fmt::Display for ChecksumError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::IoError(err) => write!(f, "input/output error [{}]", err),
+			Self::InvalidLine(line) => write!(f, "invalid line [{}]", line),
+		}
+	}
+}
+
+impl fmt::Display for InstallError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::IoError(err) => write!(f, "input/output error [{}]", err),
+			Self::ReadError(err) => write!(f, "read error [{}]", err),
+			Self::WriteError(err) => write!(f, "write error [{}]", err),
+			Self::MissingTarget(target) => write!(f, "missing target archive [{}]", target),
+		}
+	}
+}
+
+impl fmt::Display for IndexError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::IoError(err) => write!(f, "input/output error [{}]", err),
+			Self::ReadError(err) => write!(f, "read error [{}]", err),
+			Self::InvalidLine(line) => write!(f, "invalid line [{}]", line),
+			Self::InvalidPath(path) => write!(f, "invalid path [{}]", path.display()),
+		}
+	}
+}
+
+impl ReadError {
+	/// Creates a new error for the entry at the specified index, for which the name is not yet known.
+	pub(crate) fn entry(index: usize, offset: u64, error: io::Error) -> Self {
+		Self::EntryError {
+			index,
+			name: None,
+			offset,
+			error,
+		}
+	}
+}
+
+impl WriteError {
+	/// Creates a new error for the entry at the specified index.
+	pub(crate) fn entry(index: usize, name: &str, error: io::Error) -> Self {
+		Self::EntryError {
+			index,
+			name: name.to_owned(),
+			error,
 		}
 	}
 }
@@ -57,3 +475,65 @@ impl From<io::Error> for WriteError {
 		Self::IoError(value)
 	}
 }
+
+impl From<io::Error> for PathError {
+	fn from(value: io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<io::Error> for ChecksumError {
+	fn from(value: io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<io::Error> for InstallError {
+	fn from(value: io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<ReadError> for InstallError {
+	fn from(value: ReadError) -> Self {
+		Self::ReadError(value)
+	}
+}
+
+impl From<WriteError> for InstallError {
+	fn from(value: WriteError) -> Self {
+		Self::WriteError(value)
+	}
+}
+
+impl From<io::Error> for IndexError {
+	fn from(value: io::Error) -> Self {
+		Self::IoError(value)
+	}
+}
+
+impl From<ReadError> for IndexError {
+	fn from(value: ReadError) -> Self {
+		Self::ReadError(value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{error::Error, io};
+
+	use super::{ReadError, WriteError};
+
+	#[test]
+	fn test_source() {
+		let error = ReadError::from(io::Error::new(io::ErrorKind::UnexpectedEof, "eof"));
+		let source = error.source().and_then(|source| source.downcast_ref::<io::Error>()).expect("expected source");
+
+		assert_eq!(source.kind(), io::ErrorKind::UnexpectedEof);
+
+		let error = WriteError::entry(0, "VIRGO.DFF", io::Error::other("other"));
+
+		assert!(error.source().is_some_and(|source| source.is::<io::Error>()));
+		assert!(ReadError::InvalidHeader.source().is_none());
+	}
+}
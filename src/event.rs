@@ -0,0 +1,161 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+	game::Violation,
+	read::{Entry, Warning},
+	validate::Issue,
+};
+
+/// Represents an event raised during an operation on an archive.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Event<'a> {
+	/// Indicates that an entry was opened for reading.
+	EntryReadStarted {
+		/// The index of the entry.
+		index: usize,
+
+		/// The entry itself.
+		entry: &'a Entry,
+	},
+
+	/// Indicates that an entry opened for reading has been read to the end.
+	EntryReadFinished {
+		/// The index of the entry.
+		index: usize,
+
+		/// The number of bytes read from the entry.
+		bytes: u64,
+	},
+
+	/// Indicates that an entry was written to an archive.
+	EntryWritten {
+		/// The index of the entry.
+		index: usize,
+
+		/// The name of the entry.
+		name: &'a str,
+
+		/// The length, in sectors, of the entry.
+		length: u64,
+	},
+
+	/// Indicates that an issue was found when validating an archive.
+	Issue(&'a Issue),
+
+	/// Indicates that a non-fatal warning was raised whilst reading an archive.
+	Warning(&'a Warning),
+
+	/// Indicates that an entry written to an archive violates the game profile of the write options in a manner not rejected outright (e.g. exceeding the streaming budget).
+	Violation(&'a Violation),
+}
+
+/// Represents an observer of events raised during operations on an archive.
+/// Implemented for any suitable closure, such that events may be forwarded elsewhere (e.g. to the UI thread of a GUI via a channel).
+pub trait Observer: Send + Sync {
+	/// Notifies the observer of the specified event.
+	fn notify(&self, event: &Event);
+}
+
+/// Represents a shared handle to an observer.
+#[derive(Clone)]
+pub(crate) struct Handle(Arc<dyn Observer>);
+
+impl<F> Observer for F
+where
+	F: Fn(&Event) + Send + Sync,
+{
+	fn notify(&self, event: &Event) {
+		self(event)
+	}
+}
+
+impl Handle {
+	/// Creates a new handle to the specified observer.
+	pub(crate) fn new<O>(observer: O) -> Self
+	where
+		O: Observer + 'static,
+	{
+		Self(Arc::new(observer))
+	}
+
+	/// Notifies the observer of the specified event.
+	pub(crate) fn notify(&self, event: &Event) {
+		self.0.notify(event)
+	}
+}
+
+impl fmt::Debug for Handle {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Handle")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		io::{self, Cursor},
+		sync::{Arc, Mutex},
+	};
+
+	use crate::{
+		read::{Archive, Reader, V2Reader},
+		write::{V1Writer, Writer},
+	};
+
+	use super::Event;
+
+	#[test]
+	fn test_observe_read() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let events = Arc::new(Mutex::new(Vec::new()));
+		let observed = Arc::clone(&events);
+
+		let mut archive = archive.with_observer(move |event: &Event| {
+			observed.lock().expect("failed to lock events").push(match event {
+				Event::EntryReadStarted {
+					index,
+					entry,
+				} => format!("started {} {}", index, entry.name),
+				Event::EntryReadFinished {
+					index,
+					bytes,
+				} => format!("finished {} {}", index, bytes),
+				_ => String::new(),
+			})
+		});
+
+		let mut open = archive.open(1).expect("expected second entry");
+
+		io::copy(&mut open, &mut io::sink()).expect("failed to read entry");
+
+		assert_eq!(*events.lock().expect("failed to lock events"), vec!["started 1 LANDSTAL.DFF", "finished 1 2048"]);
+	}
+
+	#[test]
+	fn test_observe_write() {
+		let mut dir = Cursor::new(Vec::new());
+		let mut img = Cursor::new(Vec::new());
+
+		let events = Arc::new(Mutex::new(Vec::new()));
+		let observed = Arc::clone(&events);
+
+		let mut writer = V1Writer::new(&mut dir, &mut img).with_observer(move |event: &Event| {
+			if let Event::EntryWritten {
+				index,
+				name,
+				length,
+			} = event
+			{
+				observed.lock().expect("failed to lock events").push(format!("written {} {} {}", index, name, length));
+			}
+		});
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write first entry");
+		writer.write("LANDSTAL.DFF", &mut Cursor::new(include_bytes!("../test/landstal.dff"))).expect("failed to write second entry");
+
+		assert_eq!(*events.lock().expect("failed to lock events"), vec!["written 0 VIRGO.DFF 1", "written 1 LANDSTAL.DFF 1"]);
+	}
+}
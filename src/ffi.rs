@@ -0,0 +1,120 @@
+//! Foreign-language bindings (Swift, Kotlin, etc.), exposing the listing and extraction of archives via `uniffi`.
+//! The crate must be built as a `cdylib` or `staticlib`, with the bindings generated by `uniffi-bindgen` from the resulting library.
+
+use std::{error::Error, fmt, fs::File, io, path::Path};
+
+use crate::read::{Archive, Reader, V1Reader, V2Reader};
+
+/// Represents an entry as exposed to foreign languages.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiEntry {
+	/// The name of the entry.
+	pub name: String,
+
+	/// The offset, in sectors, of the entry.
+	pub offset: u64,
+
+	/// The length, in sectors, of the entry.
+	pub length: u64,
+}
+
+/// Represents an error as exposed to foreign languages.
+#[derive(Debug, uniffi::Error)]
+pub enum FfiError {
+	/// Indicates that the archive could not be read.
+	Read {
+		/// The description of the error.
+		message: String,
+	},
+
+	/// Indicates that the entry at the specified index could not be opened.
+	Open {
+		/// The index of the entry.
+		index: u64,
+	},
+}
+
+/// Lists the entries of the archive at `img`, using the V1 format if `dir` is specified and the V2 format otherwise.
+#[uniffi::export]
+pub fn list(img: String, dir: Option<String>) -> Result<Vec<FfiEntry>, FfiError> {
+	with_archive(&img, dir.as_deref(), |archive| {
+		Ok(archive
+			.iter()
+			.map(|entry| FfiEntry {
+				name: entry.name.clone(),
+				offset: entry.offset,
+				length: entry.length,
+			})
+			.collect())
+	})
+}
+
+/// Extracts the entry at the specified index of the archive at `img` to the `target` file, returning the number of bytes extracted.
+#[uniffi::export]
+pub fn extract(img: String, dir: Option<String>, index: u64, target: String) -> Result<u64, FfiError> {
+	with_archive(&img, dir.as_deref(), |archive| {
+		let mut open = archive.open(index as usize).ok_or(FfiError::Open {
+			index,
+		})?;
+
+		let mut file = File::create(Path::new(&target))?;
+
+		Ok(io::copy(&mut open, &mut file)?)
+	})
+}
+
+/// Extracts each of the entries of the archive at `img` to the `target` directory, named such that they are valid on every platform, returning the number of entries extracted.
+#[uniffi::export]
+pub fn extract_all(img: String, dir: Option<String>, target: String) -> Result<u64, FfiError> {
+	with_archive(&img, dir.as_deref(), |archive| {
+		// Fail upon the first entry which could not be extracted.
+
+		Ok(archive.extract_all(&target)?.into_iter().try_fold(0, |count, extracted| extracted.result.map(|_| count + 1))?)
+	})
+}
+
+fn with_archive<T, F>(img: &str, dir: Option<&str>, f: F) -> Result<T, FfiError>
+where
+	F: FnOnce(&mut Archive<File>) -> Result<T, FfiError>,
+{
+	let mut img_file = File::open(img)?;
+
+	match dir {
+		Some(dir) => {
+			let mut dir_file = File::open(dir)?;
+
+			f(&mut V1Reader::new(&mut dir_file, &mut img_file).read().map_err(to_ffi_error)?)
+		}
+		None => f(&mut V2Reader::new(&mut img_file).read().map_err(to_ffi_error)?),
+	}
+}
+
+fn to_ffi_error<E>(error: E) -> FfiError
+where
+	E: ToString,
+{
+	FfiError::Read {
+		message: error.to_string(),
+	}
+}
+
+impl Error for FfiError {}
+
+impl fmt::Display for FfiError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Read {
+				message,
+			} => write!(f, "read error [{}]", message),
+			Self::Open {
+				index,
+			} => write!(f, "failed to open entry [{}]", index),
+		}
+	}
+}
+
+impl From<io::Error> for FfiError {
+	fn from(value: io::Error) -> Self {
+		to_ffi_error(value)
+	}
+}
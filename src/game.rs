@@ -0,0 +1,485 @@
+use std::{cmp, fmt};
+
+use crate::{
+	read::{Archive, Entry},
+	SECTOR_SIZE,
+};
+
+/// Represents the names of entries found only within archives of Grand Theft Auto: III.
+const III_MARKERS: [&str; 5] = ["kuruma.dff", "yakuza.dff", "diablos.dff", "yardie.dff", "mafia.dff"];
+
+/// Represents the names of entries found only within archives of Grand Theft Auto: Vice City.
+const VICE_CITY_MARKERS: [&str; 5] = ["vicechee.dff", "cuban.dff", "gangbur.dff", "sabretur.dff", "deluxo.dff"];
+
+/// Represents the maximum size, in bytes, of an archive loadable by any of the games, which address archives using signed 32-bit positions.
+const MAX_ARCHIVE_SIZE: u64 = i32::MAX as u64;
+
+/// Represents the size, in bytes, of a single entry expected to fit comfortably within the streaming memory of Grand Theft Auto: III and Vice City.
+const V1_STREAMING_BUDGET: u64 = 8 * 1024 * 1024;
+
+/// Represents the size, in bytes, of a single entry expected to fit comfortably within the streaming memory of Grand Theft Auto: San Andreas.
+const V2_STREAMING_BUDGET: u64 = 16 * 1024 * 1024;
+
+/// Represents a game using archives.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Game {
+	/// Indicates Grand Theft Auto: III.
+	III,
+
+	/// Indicates Grand Theft Auto: Vice City.
+	ViceCity,
+
+	/// Indicates Grand Theft Auto: San Andreas.
+	SanAndreas,
+}
+
+/// Represents the version of an archive.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Version {
+	/// Indicates a V1-styled archive, with a separate `dir` file.
+	V1,
+
+	/// Indicates a V2-styled archive, with the directory in the header of the `img` file.
+	V2,
+}
+
+/// Represents the constraints of archives loadable by a particular game.
+/// Profiles can be supplied to readers, which raise warnings for any violations, and to writers, which reject them outright.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct GameProfile {
+	/// The game the profile is for.
+	pub game: Game,
+
+	/// The version of archive loaded by the game.
+	pub version: Version,
+
+	/// The maximum number of entries, based on the number of streaming slots for models and texture dictionaries.
+	pub max_entries: usize,
+
+	/// The maximum size, in bytes, of the archive.
+	pub max_size: u64,
+
+	/// The size, in bytes, beyond which a single entry is likely to exhaust the streaming memory of the game.
+	/// Unlike the other constraints, exceeding this is never rejected by writers, but instead raised as [`crate::event::Event::Violation`].
+	pub streaming_budget: u64,
+
+	/// Whether names of entries containing control characters or reserved characters are rejected by writers.
+	pub reject_reserved: bool,
+}
+
+/// Represents a violation of the constraints of a game profile.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum Violation {
+	/// Indicates that the archive is not of the version loaded by the game.
+	Version {
+		/// The version loaded by the game.
+		expected: Version,
+
+		/// The version of the archive.
+		actual: Version,
+	},
+
+	/// Indicates that the archive contains more entries than the game can load.
+	TooManyEntries {
+		/// The number of entries.
+		count: usize,
+
+		/// The maximum number of entries.
+		max: usize,
+	},
+
+	/// Indicates that the archive is larger than the game can load.
+	TooLarge {
+		/// The size, in bytes, of the archive.
+		size: u64,
+
+		/// The maximum size, in bytes.
+		max: u64,
+	},
+
+	/// Indicates that an entry is larger than the streaming budget of the game.
+	OverBudget {
+		/// The index of the entry.
+		index: usize,
+
+		/// The size, in bytes, of the entry.
+		size: u64,
+
+		/// The streaming budget, in bytes.
+		max: u64,
+	},
+}
+
+impl Version {
+	/// Returns the version of an archive from the number of entries declared within its header, which only V2-styled archives declare.
+	pub(crate) fn from_declared(declared: Option<usize>) -> Self {
+		match declared {
+			Some(_) => Self::V2,
+			None => Self::V1,
+		}
+	}
+}
+
+impl Game {
+	/// Returns the profile of the constraints of archives loadable by the game.
+	pub fn profile(self) -> GameProfile {
+		let (version, max_entries, streaming_budget) = match self {
+			Self::III => (Version::V1, 6350, V1_STREAMING_BUDGET),
+			Self::ViceCity => (Version::V1, 7885, V1_STREAMING_BUDGET),
+			Self::SanAndreas => (Version::V2, 25000, V2_STREAMING_BUDGET),
+		};
+
+		GameProfile {
+			game: self,
+			version,
+			max_entries,
+			max_size: MAX_ARCHIVE_SIZE,
+			streaming_budget,
+			reject_reserved: true,
+		}
+	}
+}
+
+impl GameProfile {
+	/// Checks the archive against the profile, returning any violations found.
+	pub fn check<I>(&self, archive: &Archive<I>) -> Vec<Violation> {
+		self.violations(archive.metadata().version, archive.iter())
+	}
+
+	/// Checks archives of the specified version with the specified entries against the profile.
+	pub(crate) fn violations<'e>(&self, version: Version, entries: impl Iterator<Item = &'e Entry>) -> Vec<Violation> {
+		let mut violations = Vec::new();
+		let mut count = 0;
+		let mut end = 0;
+
+		// Check each entry against the streaming budget, whilst determining the number of entries and the end of the furthest entry.
+
+		for (index, entry) in entries.enumerate() {
+			if let Err(violation) = self.check_budget(index, entry.len_bytes()) {
+				violations.push(violation);
+			}
+
+			count += 1;
+			end = cmp::max(end, entry.offset.saturating_add(entry.length));
+		}
+
+		if let Err(violation) = self.check_version(version) {
+			violations.push(violation);
+		}
+
+		if let Err(violation) = self.check_count(count) {
+			violations.push(violation);
+		}
+
+		if let Err(violation) = self.check_size(end.saturating_mul(SECTOR_SIZE)) {
+			violations.push(violation);
+		}
+
+		violations
+	}
+
+	/// Checks that archives of the specified version are loadable.
+	pub(crate) fn check_version(&self, version: Version) -> Result<(), Violation> {
+		match version == self.version {
+			true => Ok(()),
+			false => Err(Violation::Version {
+				expected: self.version,
+				actual: version,
+			}),
+		}
+	}
+
+	/// Checks that archives containing the specified number of entries are loadable.
+	pub(crate) fn check_count(&self, count: usize) -> Result<(), Violation> {
+		match count > self.max_entries {
+			true => Err(Violation::TooManyEntries {
+				count,
+				max: self.max_entries,
+			}),
+			false => Ok(()),
+		}
+	}
+
+	/// Checks that the entry at the specified index, of the specified size in bytes, lies within the streaming budget.
+	pub(crate) fn check_budget(&self, index: usize, size: u64) -> Result<(), Violation> {
+		match size > self.streaming_budget {
+			true => Err(Violation::OverBudget {
+				index,
+				size,
+				max: self.streaming_budget,
+			}),
+			false => Ok(()),
+		}
+	}
+
+	/// Checks that archives of the specified size, in bytes, are loadable.
+	pub(crate) fn check_size(&self, size: u64) -> Result<(), Violation> {
+		match size > self.max_size {
+			true => Err(Violation::TooLarge {
+				size,
+				max: self.max_size,
+			}),
+			false => Ok(()),
+		}
+	}
+}
+
+impl<I> Archive<'_, I> {
+	/// Guesses the game the archive is from, based on its version and the names of well-known entries.
+	/// As V2-styled archives were introduced by Grand Theft Auto: San Andreas, any such archive is assumed to be from it.
+	/// Returns `None` if there is not enough evidence either way (e.g. an archive containing no well-known entries).
+	pub fn guess_game(&self) -> Option<Game> {
+		if Version::from_declared(self.declared()) == Version::V2 {
+			return Some(Game::SanAndreas);
+		}
+
+		// Count the well-known entries of each game, favouring whichever has the most.
+
+		let count = |markers: &[&str]| self.iter().filter(|entry| markers.iter().any(|marker| entry.name.eq_ignore_ascii_case(marker))).count();
+
+		let iii = count(&III_MARKERS);
+		let vice_city = count(&VICE_CITY_MARKERS);
+
+		match iii.cmp(&vice_city) {
+			std::cmp::Ordering::Greater => Some(Game::III),
+			std::cmp::Ordering::Less => Some(Game::ViceCity),
+			std::cmp::Ordering::Equal => None,
+		}
+	}
+}
+
+impl fmt::Display for Game {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::III => write!(f, "Grand Theft Auto: III"),
+			Self::ViceCity => write!(f, "Grand Theft Auto: Vice City"),
+			Self::SanAndreas => write!(f, "Grand Theft Auto: San Andreas"),
+		}
+	}
+}
+
+impl fmt::Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::V1 => write!(f, "V1"),
+			Self::V2 => write!(f, "V2"),
+		}
+	}
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Version {
+				expected,
+				actual,
+			} => write!(f, "{} archive where {} archive expected", actual, expected),
+			Self::TooManyEntries {
+				count,
+				max,
+			} => write!(f, "{} entries exceeds maximum of {}", count, max),
+			Self::TooLarge {
+				size,
+				max,
+			} => write!(f, "size of {} bytes exceeds maximum of {}", size, max),
+			Self::OverBudget {
+				index,
+				size,
+				max,
+			} => write!(f, "size of {} bytes of entry [#{}] exceeds streaming budget of {}", size, index, max),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		io::Cursor,
+		sync::{Arc, Mutex},
+	};
+
+	use crate::{
+		error::WriteError,
+		event::Event,
+		read::{Archive, ReadOptions, Reader, V1Reader, V2Reader, Warning},
+		write::{V1Writer, V2Writer, WriteOptions, Writer},
+	};
+
+	use super::{Game, GameProfile, Version, Violation};
+
+	fn guess(names: &[&str]) -> Option<Game> {
+		let mut dir = Cursor::new(Vec::new());
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V1Writer::new(&mut dir, &mut img);
+
+		for name in names {
+			writer.write(name, &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write entry");
+		}
+
+		dir.set_position(0);
+
+		let archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+
+		archive.guess_game()
+	}
+
+	#[test]
+	fn test_guess_game() {
+		assert_eq!(guess(&["VIRGO.DFF", "KURUMA.DFF", "YAKUZA.TXD"]), Some(Game::III));
+		assert_eq!(guess(&["VIRGO.DFF", "vicechee.dff", "cuban.dff", "kuruma.dff"]), Some(Game::ViceCity));
+		assert_eq!(guess(&["VIRGO.DFF", "LANDSTAL.DFF"]), None);
+
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.guess_game(), Some(Game::SanAndreas));
+	}
+
+	#[test]
+	fn test_profile_check() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert!(Game::SanAndreas.profile().check(&archive).is_empty());
+		assert_eq!(
+			Game::III.profile().check(&archive),
+			[Violation::Version {
+				expected: Version::V1,
+				actual: Version::V2,
+			}]
+		);
+
+		let profile = GameProfile {
+			max_entries: 2,
+			max_size: 4096,
+			..Game::SanAndreas.profile()
+		};
+
+		assert_eq!(
+			profile.check(&archive),
+			[
+				Violation::TooManyEntries {
+					count: 3,
+					max: 2,
+				},
+				Violation::TooLarge {
+					size: 22528,
+					max: 4096,
+				}
+			]
+		);
+	}
+
+	#[test]
+	fn test_streaming_budget() {
+		let profile = GameProfile {
+			streaming_budget: 4096,
+			..Game::SanAndreas.profile()
+		};
+
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(
+			profile.check(&archive),
+			[Violation::OverBudget {
+				index: 2,
+				size: 16384,
+				max: 4096,
+			}]
+		);
+
+		// Check that writers raise an event, rather than rejecting the entry.
+
+		let violations = Arc::new(Mutex::new(Vec::new()));
+		let observed = Arc::clone(&violations);
+
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 1)
+			.expect("failed to create writer")
+			.with_options(WriteOptions {
+				profile: Some(profile),
+				..Default::default()
+			})
+			.with_observer(move |event: &Event| {
+				if let Event::Violation(violation) = event {
+					observed.lock().expect("failed to lock violations").push((*violation).clone());
+				}
+			});
+
+		writer.write("BIG.DFF", &mut Cursor::new(vec![0; 5000])).expect("failed to write entry");
+
+		assert_eq!(
+			*violations.lock().expect("failed to lock violations"),
+			[Violation::OverBudget {
+				index: 0,
+				size: 6144,
+				max: 4096,
+			}]
+		);
+	}
+
+	#[test]
+	fn test_profile_read() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let archive: Archive<_> = V1Reader::new(&mut dir, &mut img)
+			.with_options(ReadOptions {
+				profile: Some(Game::SanAndreas.profile()),
+				..Default::default()
+			})
+			.read()
+			.expect("failed to read archive");
+
+		assert!(archive.warnings().contains(&Warning::ProfileViolation {
+			violation: Violation::Version {
+				expected: Version::V2,
+				actual: Version::V1,
+			},
+		}));
+	}
+
+	#[test]
+	fn test_profile_write() {
+		let options = WriteOptions {
+			profile: Some(GameProfile {
+				max_entries: 1,
+				..Game::SanAndreas.profile()
+			}),
+			..Default::default()
+		};
+
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 2).expect("failed to create writer").with_options(options.clone());
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write first entry");
+
+		let result = writer.write("LANDSTAL.DFF", &mut Cursor::new(include_bytes!("../test/landstal.dff")));
+
+		assert!(matches!(
+			result,
+			Err(WriteError::ProfileViolation {
+				index: 1,
+				violation: Violation::TooManyEntries {
+					count: 2,
+					max: 1,
+				},
+				..
+			})
+		));
+
+		let mut dir = Cursor::new(Vec::new());
+		let mut img = Cursor::new(Vec::new());
+		let result =
+			V1Writer::new(&mut dir, &mut img).with_options(options).write("VEH/VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff")));
+
+		assert!(matches!(
+			result,
+			Err(WriteError::InvalidName {
+				character: '/',
+				..
+			})
+		));
+	}
+}
@@ -0,0 +1,393 @@
+use std::{
+	collections::HashMap,
+	fs::{self, File},
+	io::{BufRead, BufReader, Read, Seek, Write},
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	checksum,
+	error::{IndexError, ReadError},
+	read::{Archive, Reader, V1Reader, V2Reader},
+	workspace::{ArchivePaths, Workspace},
+	SECTOR_SIZE,
+};
+
+/// Represents the separator between the fields of a line of an index file.
+const SEPARATOR: char = '\t';
+
+/// Represents the character introducing an escaped character within a field of an index file.
+const ESCAPE: char = '\\';
+
+/// Represents a searchable index of the entries of many archives (and loose files), which can be persisted to and restored from an index file.
+/// Names are looked up case-insensitively, in line with the behaviour of the games.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Index {
+	records: Vec<Record>,
+
+	names: HashMap<String, Vec<usize>>,
+}
+
+/// Represents an indexed entry (or loose file).
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Record {
+	/// The name of the entry.
+	pub name: String,
+
+	/// The path of the archive containing the entry, or of the loose file itself.
+	pub path: PathBuf,
+
+	/// The offset, in sectors, of the entry within the archive (always zero for loose files).
+	pub offset: u64,
+
+	/// The length, in sectors, of the entry.
+	pub length: u64,
+
+	/// The CRC32 checksum of the data of the entry, including any padding up to the end of the final sector.
+	pub crc32: u32,
+}
+
+impl Index {
+	/// Creates a new, empty index.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Indexes each of the entries of the archive located at the specified path.
+	pub fn add<P, I>(&mut self, path: P, archive: &mut Archive<I>) -> Result<(), IndexError>
+	where
+		P: AsRef<Path>,
+		I: Read + Seek,
+	{
+		for index in 0..archive.len() {
+			let crc32 = checksum::entry_crc32(archive, index)?.unwrap_or_default();
+			let entry = archive.get(index).ok_or(ReadError::InvalidIndex(index))?;
+
+			self.push(Record {
+				name: entry.name.clone(),
+				path: path.as_ref().to_owned(),
+				offset: entry.offset,
+				length: entry.length,
+				crc32,
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Indexes each of the archives discovered within the workspace.
+	pub fn add_workspace(&mut self, workspace: &Workspace) -> Result<(), IndexError> {
+		for paths in workspace.archives() {
+			match paths {
+				ArchivePaths::V1(paths) => {
+					let (mut dir, mut img) = paths.open()?;
+					let mut archive = V1Reader::new(&mut dir, &mut img).read()?;
+
+					self.add(&paths.img, &mut archive)?;
+				}
+				ArchivePaths::V2(path) => {
+					let mut img = File::open(path)?;
+					let mut archive = V2Reader::new(&mut img).read()?;
+
+					self.add(path, &mut archive)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Indexes each of the loose files directly within the folder at the specified path (e.g. a mod folder), named after their file names.
+	pub fn add_folder<P>(&mut self, path: P) -> Result<(), IndexError>
+	where
+		P: AsRef<Path>,
+	{
+		let mut files = Vec::new();
+
+		for file in fs::read_dir(path)? {
+			let file = file?;
+
+			if file.file_type()?.is_file() {
+				files.push(file.path());
+			}
+		}
+
+		// Sort the files, as the order of directory listings is unspecified.
+
+		files.sort();
+
+		for path in files {
+			let mut data = fs::read(&path)?;
+
+			// Pad the data up to the end of the final sector, in line with the checksums of entries.
+
+			let length = (data.len() as u64).div_ceil(SECTOR_SIZE);
+
+			data.resize((length * SECTOR_SIZE) as usize, 0);
+
+			self.push(Record {
+				name: path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+				path,
+				offset: 0,
+				length,
+				crc32: crc32fast::hash(&data),
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Returns the number of records in the index.
+	pub fn len(&self) -> usize {
+		self.records.len()
+	}
+
+	/// Returns whether the index contains no records.
+	pub fn is_empty(&self) -> bool {
+		self.records.is_empty()
+	}
+
+	/// Returns an iterator over each of the records, in the order they were indexed.
+	pub fn iter(&self) -> impl Iterator<Item = &Record> {
+		self.records.iter()
+	}
+
+	/// Finds each of the records with the specified name.
+	pub fn find(&self, name: &str) -> Vec<&Record> {
+		self.names.get(&name.to_ascii_lowercase()).map_or_else(Vec::new, |indices| indices.iter().map(|&index| &self.records[index]).collect())
+	}
+
+	/// Returns each group of records with identical content (by length and checksum), regardless of their names.
+	/// Groups are ordered by their first record, with records ordered as they were indexed.
+	pub fn duplicates(&self) -> Vec<Vec<&Record>> {
+		let mut groups: Vec<Vec<&Record>> = Vec::new();
+		let mut contents: HashMap<(u64, u32), usize> = HashMap::new();
+
+		for record in &self.records {
+			let group = *contents.entry((record.length, record.crc32)).or_insert_with(|| {
+				groups.push(Vec::new());
+				groups.len() - 1
+			});
+
+			groups[group].push(record);
+		}
+
+		groups.retain(|group| group.len() > 1);
+		groups
+	}
+
+	/// Writes the index to `dst`, with one record per line.
+	/// Any separators, line breaks or escape characters within names and paths are escaped, such that each record occupies a single line.
+	/// Returns an error should the path of any record not be valid UTF-8, as it could not otherwise be restored.
+	pub fn write<W>(&self, dst: &mut W) -> Result<(), IndexError>
+	where
+		W: Write,
+	{
+		for record in &self.records {
+			let path = record.path.to_str().ok_or_else(|| IndexError::InvalidPath(record.path.clone()))?;
+
+			writeln!(
+				dst,
+				"{}{sep}{}{sep}{}{sep}{:08X}{sep}{}",
+				record.offset,
+				record.length,
+				escape(&record.name),
+				record.crc32,
+				escape(path),
+				sep = SEPARATOR
+			)?;
+		}
+
+		Ok(())
+	}
+
+	/// Reads an index previously written by [`Index::write`] from `src`.
+	pub fn read<R>(src: &mut R) -> Result<Self, IndexError>
+	where
+		R: Read,
+	{
+		let mut index = Self::new();
+
+		for (number, line) in BufReader::new(src).lines().enumerate() {
+			let line = line?;
+
+			if line.is_empty() {
+				continue;
+			}
+
+			// Split the line into each of its fields, any separators within which will have been escaped.
+
+			let invalid = || IndexError::InvalidLine(number + 1);
+			let fields: Vec<_> = line.split(SEPARATOR).collect();
+
+			let [offset, length, name, crc32, path] = fields[..] else {
+				return Err(invalid());
+			};
+
+			index.push(Record {
+				name: unescape(name).ok_or_else(invalid)?,
+				path: PathBuf::from(unescape(path).ok_or_else(invalid)?),
+				offset: offset.parse().map_err(|_| invalid())?,
+				length: length.parse().map_err(|_| invalid())?,
+				crc32: u32::from_str_radix(crc32, 16).map_err(|_| invalid())?,
+			});
+		}
+
+		Ok(index)
+	}
+
+	fn push(&mut self, record: Record) {
+		self.names.entry(record.name.to_ascii_lowercase()).or_default().push(self.records.len());
+		self.records.push(record);
+	}
+}
+
+fn escape(field: &str) -> String {
+	let mut escaped = String::with_capacity(field.len());
+
+	for c in field.chars() {
+		match c {
+			ESCAPE => escaped.push_str("\\\\"),
+			SEPARATOR => escaped.push_str("\\t"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			c => escaped.push(c),
+		}
+	}
+
+	escaped
+}
+
+fn unescape(field: &str) -> Option<String> {
+	let mut unescaped = String::with_capacity(field.len());
+	let mut chars = field.chars();
+
+	while let Some(c) = chars.next() {
+		if c != ESCAPE {
+			unescaped.push(c);
+
+			continue;
+		}
+
+		unescaped.push(match chars.next()? {
+			ESCAPE => ESCAPE,
+			't' => SEPARATOR,
+			'n' => '\n',
+			'r' => '\r',
+			_ => return None,
+		});
+	}
+
+	Some(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{fs, io::Cursor, path::PathBuf};
+
+	use crate::{
+		error::IndexError,
+		read::{Archive, Reader, V2Reader},
+		workspace::Workspace,
+	};
+
+	use super::{Index, Record};
+
+	fn copy_fixtures(name: &str) -> PathBuf {
+		let folder = std::env::temp_dir().join(format!("gta-img-index-{}-{}", name, std::process::id()));
+
+		fs::create_dir_all(&folder).expect("failed to create folder");
+
+		for file in ["v1.img", "v1.dir", "v2.img", "virgo.dff", "landstal.dff"] {
+			fs::copy(PathBuf::from("test").join(file), folder.join(file)).expect("failed to copy fixture");
+		}
+
+		folder
+	}
+
+	#[test]
+	fn test_index() {
+		let folder = copy_fixtures("workspace");
+		let mut index = Index::new();
+
+		index.add_workspace(&Workspace::scan(&folder).expect("failed to scan workspace")).expect("failed to index workspace");
+		index.add_folder(&folder).expect("failed to index folder");
+
+		assert_eq!(index.len(), 11);
+
+		let found = index.find("virgo.dff");
+
+		assert_eq!(found.len(), 3);
+		assert_eq!(found[0].path, folder.join("v1.img"));
+		assert_eq!(found[2].path, folder.join("virgo.dff"));
+
+		let mut buf = Vec::new();
+
+		index.write(&mut buf).expect("failed to write index");
+
+		assert_eq!(Index::read(&mut Cursor::new(buf)).expect("failed to read index"), index);
+		assert!(Index::read(&mut Cursor::new("0\tinvalid\n")).is_err());
+
+		// Index the same folder again (e.g. a copy of a mod), duplicating each of the loose files.
+
+		index.add_folder(&folder).expect("failed to index folder");
+
+		let duplicates = index.duplicates();
+
+		assert!(duplicates.iter().any(|group| group.iter().filter(|record| record.path == folder.join("virgo.dff")).count() == 2));
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+
+	#[test]
+	fn test_write_escaped() {
+		let mut index = Index::new();
+
+		index.push(Record {
+			name: "VIRGO\tDFF".to_owned(),
+			path: PathBuf::from("mods\\virgo\n\r.img"),
+			offset: 1,
+			length: 1,
+			crc32: 0,
+		});
+
+		let mut buf = Vec::new();
+
+		index.write(&mut buf).expect("failed to write index");
+
+		assert_eq!(buf, b"1\t1\tVIRGO\\tDFF\t00000000\tmods\\\\virgo\\n\\r.img\n");
+		assert_eq!(Index::read(&mut Cursor::new(buf)).expect("failed to read index"), index);
+		assert!(Index::read(&mut Cursor::new("0\t1\tVIRGO\\xDFF\t00000000\tv2.img\n")).is_err());
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_write_invalid_path() {
+		use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+		let mut index = Index::new();
+
+		index.push(Record {
+			name: "VIRGO.DFF".to_owned(),
+			path: PathBuf::from(OsStr::from_bytes(b"\xFF.img")),
+			offset: 1,
+			length: 1,
+			crc32: 0,
+		});
+
+		assert!(matches!(index.write(&mut Vec::new()), Err(IndexError::InvalidPath(_))));
+	}
+
+	#[test]
+	fn test_add() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut index = Index::new();
+
+		index.add("v2.img", &mut archive).expect("failed to index archive");
+
+		assert_eq!(index.find("LANDSTAL.DFF").len(), 1);
+		assert!(index.find("MISSING.DFF").is_empty());
+	}
+}
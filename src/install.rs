@@ -0,0 +1,642 @@
+use std::{
+	collections::BTreeMap,
+	ffi::OsString,
+	fs::{self, File},
+	io::{self, Read, Seek},
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	backup::{Backup, BackupMode},
+	error::{InstallError, WriteError},
+	read::{Archive, Reader, V1Reader, V2Reader},
+	workspace::ArchivePaths,
+	write::{V1Writer, V2Writer, Writer},
+};
+
+/// Represents the wildcard within the patterns of rules.
+const WILDCARD: char = '*';
+
+/// Represents the extension appended to the paths of files whilst they are rewritten.
+const TEMPORARY_EXTENSION: &str = "tmp";
+
+/// Represents the rules mapping the files of a mod to the archives they are installed into.
+/// Rules are matched against file names case-insensitively, in the order they were added.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct Rules {
+	rules: Vec<Rule>,
+}
+
+/// Represents a rule mapping files to an archive.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Rule {
+	/// The pattern of file names matched, which may contain a single `*` wildcard (e.g. `*.dff` or `player*`).
+	pub pattern: String,
+
+	/// The name of the archive the files are installed into (e.g. `gta3.img`).
+	pub target: String,
+}
+
+/// Represents a file of a mod to be installed as an entry.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct ModFile {
+	/// The name of the entry.
+	pub name: String,
+
+	/// The path of the file.
+	pub path: PathBuf,
+}
+
+/// Represents the files of a mod grouped by the archives they are installed into.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct Plan {
+	/// The files to install into each archive, by the name of the archive.
+	pub targets: BTreeMap<String, Vec<ModFile>>,
+
+	/// The files not matched by any rule, which are not installed.
+	pub unmapped: Vec<PathBuf>,
+}
+
+/// Represents a record of the changes made by installing a mod into an archive, such that it can later be uninstalled.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct Receipt {
+	/// The names of the entries added by the mod.
+	pub added: Vec<String>,
+
+	/// The entries replaced by the mod, along with their original data.
+	pub replaced: Vec<Replaced>,
+}
+
+/// Represents the options for applying a plan to archives.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct ApplyOptions {
+	/// How much of each archive to back up before modifying it, if at all.
+	/// As each archive is rewritten in full, backing up only the affected entries backs up the data of every entry (in memory).
+	pub backup: Option<BackupMode>,
+}
+
+/// Represents the installation of the files of a plan into one of the archives it targets.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Installed {
+	/// The paths of the archive installed into.
+	pub paths: ArchivePaths,
+
+	/// The record of the changes made to the archive.
+	pub receipt: Receipt,
+
+	/// The backup of the archive taken before it was modified, if requested.
+	pub backup: Option<Backup>,
+}
+
+/// Represents an entry replaced by a mod.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Replaced {
+	/// The name of the entry.
+	pub name: String,
+
+	/// The original data of the entry, including any padding up to the end of the final sector.
+	pub original: Vec<u8>,
+}
+
+impl Rules {
+	/// Creates a new, empty set of rules.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a rule installing files matching the specified pattern into the specified archive.
+	pub fn with(mut self, pattern: &str, target: &str) -> Self {
+		self.rules.push(Rule {
+			pattern: pattern.to_owned(),
+			target: target.to_owned(),
+		});
+
+		self
+	}
+
+	/// Returns the name of the archive the file with the specified name is installed into, if matched by any rule.
+	pub fn target(&self, name: &str) -> Option<&str> {
+		self.rules.iter().find(|rule| matches(&rule.pattern, name)).map(|rule| rule.target.as_str())
+	}
+}
+
+/// Plans the installation of the files directly within the mod folder at the specified path according to the rules.
+/// Mods packaged as `zip` files may be planned with `plan_zip` (with the `zip` feature enabled).
+pub fn plan<P>(folder: P, rules: &Rules) -> Result<Plan, io::Error>
+where
+	P: AsRef<Path>,
+{
+	let mut files = Vec::new();
+
+	for file in fs::read_dir(folder)? {
+		let file = file?;
+
+		if file.file_type()?.is_file() {
+			files.push(file.path());
+		}
+	}
+
+	// Sort the files, as the order of directory listings is unspecified.
+
+	files.sort();
+
+	let mut plan = Plan::default();
+
+	for path in files {
+		let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+
+		match rules.target(&name) {
+			Some(target) => plan.targets.entry(target.to_owned()).or_default().push(ModFile {
+				name,
+				path,
+			}),
+			None => plan.unmapped.push(path),
+		}
+	}
+
+	Ok(plan)
+}
+
+/// Plans the installation of the files within the mod `zip` file at the specified path according to the rules, first extracting them into the `folder` directory (which is created if necessary).
+/// Files within folders of the `zip` file are extracted directly into the directory, as mods commonly nest their files within folders.
+#[cfg(feature = "zip")]
+pub fn plan_zip<P, Q>(zip: P, folder: Q, rules: &Rules) -> Result<Plan, io::Error>
+where
+	P: AsRef<Path>,
+	Q: AsRef<Path>,
+{
+	let mut zip = zip::ZipArchive::new(File::open(zip)?)?;
+
+	fs::create_dir_all(folder.as_ref())?;
+
+	for index in 0..zip.len() {
+		let mut file = zip.by_index(index)?;
+
+		// Extract only files, under their file names alone, such that none can be extracted outside of the directory.
+
+		let Some(name) = file.enclosed_name().and_then(|path| path.file_name().map(ToOwned::to_owned)).filter(|_| file.is_file()) else {
+			continue;
+		};
+
+		io::copy(&mut file, &mut File::create(folder.as_ref().join(name))?)?;
+	}
+
+	plan(folder, rules)
+}
+
+/// Applies the plan to the archives at the specified paths (e.g. as discovered by [`crate::workspace::Workspace`]), installing the files of each target into the archive with the same file name (case-insensitively).
+/// Each archive is rewritten in full alongside the original, which is replaced only once the archive has been written successfully.
+/// Every target is resolved before any archive is modified, failing should any target not match an archive, and each archive is backed up before it is modified as per the options.
+pub fn apply(plan: &Plan, archives: &[ArchivePaths], options: &ApplyOptions) -> Result<Vec<Installed>, InstallError> {
+	let mut targets = Vec::with_capacity(plan.targets.len());
+
+	for (target, files) in &plan.targets {
+		let paths = archives
+			.iter()
+			.find(|paths| paths.img().file_name().is_some_and(|name| name.eq_ignore_ascii_case(target)))
+			.ok_or_else(|| InstallError::MissingTarget(target.clone()))?;
+
+		targets.push((paths, files));
+	}
+
+	let mut installed = Vec::with_capacity(targets.len());
+
+	for (paths, files) in targets {
+		let backup = options.backup.map(|mode| back_up(paths, mode)).transpose()?;
+		let receipt = rewrite(paths, Change::Install(files))?;
+
+		installed.push(Installed {
+			paths: paths.clone(),
+			receipt: receipt.unwrap_or_default(),
+			backup,
+		});
+	}
+
+	Ok(installed)
+}
+
+/// Reverts each of the installations made by [`apply`] (in reverse order), uninstalling the mod from each of the archives as per [`uninstall`].
+/// Each archive is rewritten in full alongside the original, which is replaced only once the archive has been written successfully.
+pub fn revert(installed: &[Installed]) -> Result<(), InstallError> {
+	for installed in installed.iter().rev() {
+		rewrite(&installed.paths, Change::Uninstall(&installed.receipt))?;
+	}
+
+	Ok(())
+}
+
+/// Installs the files into the archive, writing the resulting archive with the specified writer and returning a receipt of the changes made.
+/// Entries with the same name as a file (case-insensitively) are replaced in place, whereas any other files are added after the existing entries.
+pub fn install<I, W>(archive: &mut Archive<I>, files: &[ModFile], writer: &mut W) -> Result<Receipt, WriteError>
+where
+	I: Read + Seek,
+	W: Writer,
+{
+	let mut receipt = Receipt::default();
+	let mut installed = vec![false; files.len()];
+
+	for index in 0..archive.len() {
+		let name = archive.get(index).map(|entry| entry.name.clone()).unwrap_or_default();
+
+		// Replace the entry with the matching file, if any, retaining its original data.
+
+		let Some(position) = files.iter().position(|file| file.name.eq_ignore_ascii_case(&name)) else {
+			if let Some(mut open) = archive.open(index) {
+				writer.write(&name, &mut open)?;
+			}
+
+			continue;
+		};
+
+		let original = archive.read_entry(index)?.unwrap_or_default();
+
+		// Leave any later entries with the same name (which are shadowed by the first) untouched.
+
+		if installed[position] {
+			writer.write(&name, &mut original.as_slice())?;
+
+			continue;
+		}
+
+		writer.write(&name, &mut File::open(&files[position].path)?)?;
+
+		installed[position] = true;
+
+		receipt.replaced.push(Replaced {
+			name,
+			original,
+		});
+	}
+
+	// Add any files not replacing an existing entry.
+
+	for (file, _) in files.iter().zip(installed).filter(|(_, installed)| !installed) {
+		writer.write(&file.name, &mut File::open(&file.path)?)?;
+
+		receipt.added.push(file.name.clone());
+	}
+
+	Ok(receipt)
+}
+
+/// Uninstalls a mod from the archive it was installed into, writing the resulting archive with the specified writer and returning the number of entries written.
+/// Entries added by the mod are removed, and entries replaced by the mod are restored to their original data.
+pub fn uninstall<I, W>(archive: &mut Archive<I>, receipt: &Receipt, writer: &mut W) -> Result<usize, WriteError>
+where
+	I: Read + Seek,
+	W: Writer,
+{
+	let mut written = 0;
+	let mut restored = vec![false; receipt.replaced.len()];
+
+	for index in 0..archive.len() {
+		let name = archive.get(index).map(|entry| entry.name.clone()).unwrap_or_default();
+
+		if receipt.added.iter().any(|added| added.eq_ignore_ascii_case(&name)) {
+			continue;
+		}
+
+		// Restore only the first entry with the name, leaving any later entries (which are shadowed by the first) untouched.
+
+		let position = receipt.replaced.iter().position(|replaced| replaced.name.eq_ignore_ascii_case(&name));
+
+		match position.filter(|&position| !restored[position]) {
+			Some(position) => {
+				writer.write(&name, &mut receipt.replaced[position].original.as_slice())?;
+
+				restored[position] = true;
+			}
+			None => {
+				if let Some(mut open) = archive.open(index) {
+					writer.write(&name, &mut open)?;
+				}
+			}
+		}
+
+		written += 1;
+	}
+
+	Ok(written)
+}
+
+/// Represents a change made to an archive by rewriting it.
+enum Change<'a> {
+	Install(&'a [ModFile]),
+	Uninstall(&'a Receipt),
+}
+
+impl Change<'_> {
+	/// Returns the number of entries the archive contains once changed.
+	fn count<I>(&self, archive: &Archive<I>) -> usize {
+		match self {
+			Self::Install(files) => {
+				archive.len() + files.iter().filter(|file| !archive.iter().any(|entry| entry.name.eq_ignore_ascii_case(&file.name))).count()
+			}
+			Self::Uninstall(receipt) => {
+				archive.iter().filter(|entry| !receipt.added.iter().any(|added| added.eq_ignore_ascii_case(&entry.name))).count()
+			}
+		}
+	}
+
+	/// Writes the changed archive with the specified writer, returning the receipt of any installation.
+	fn write<I, W>(&self, archive: &mut Archive<I>, writer: &mut W) -> Result<Option<Receipt>, WriteError>
+	where
+		I: Read + Seek,
+		W: Writer,
+	{
+		match self {
+			Self::Install(files) => install(archive, files, writer).map(Some),
+			Self::Uninstall(receipt) => uninstall(archive, receipt, writer).map(|_| None),
+		}
+	}
+}
+
+impl Default for ApplyOptions {
+	fn default() -> Self {
+		Self {
+			backup: Some(BackupMode::Full),
+		}
+	}
+}
+
+fn back_up(paths: &ArchivePaths, mode: BackupMode) -> Result<Backup, InstallError> {
+	// As the archive is rewritten in full, each of its entries is affected.
+
+	let entries = match mode {
+		BackupMode::Entries => paths.read_entries()?,
+		BackupMode::Full => Vec::new(),
+	};
+
+	let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+
+	Ok(Backup::create(paths, &names, mode)?)
+}
+
+fn rewrite(paths: &ArchivePaths, change: Change) -> Result<Option<Receipt>, InstallError> {
+	let img = temporary_path(paths.img());
+
+	// Write the changed archive alongside the original.
+
+	let receipt = match paths {
+		ArchivePaths::V1(paths) => {
+			let (mut dir_file, mut img_file) = paths.open()?;
+			let mut archive: Archive<_> = V1Reader::new(&mut dir_file, &mut img_file).read()?;
+
+			let mut writer = V1Writer::new(File::create(temporary_path(&paths.dir))?, File::create(&img)?);
+			let receipt = change.write(&mut archive, &mut writer)?;
+
+			writer.finish()?;
+
+			receipt
+		}
+		ArchivePaths::V2(path) => {
+			let mut img_file = File::open(path)?;
+			let mut archive: Archive<_> = V2Reader::new(&mut img_file).read()?;
+
+			let mut writer = V2Writer::new(File::create(&img)?, change.count(&archive))?;
+			let receipt = change.write(&mut archive, &mut writer)?;
+
+			writer.finish()?;
+
+			receipt
+		}
+	};
+
+	// Replace the original with the changed archive, only once written in full.
+
+	if let ArchivePaths::V1(paths) = paths {
+		fs::rename(temporary_path(&paths.dir), &paths.dir)?;
+	}
+
+	fs::rename(img, paths.img())?;
+
+	Ok(receipt)
+}
+
+fn temporary_path(path: &Path) -> PathBuf {
+	let mut path = OsString::from(path);
+
+	path.push(".");
+	path.push(TEMPORARY_EXTENSION);
+
+	PathBuf::from(path)
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+	let pattern = pattern.to_ascii_lowercase();
+	let name = name.to_ascii_lowercase();
+
+	match pattern.split_once(WILDCARD) {
+		Some((prefix, suffix)) => name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix),
+		None => pattern == name,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		fs,
+		io::{Cursor, Read},
+		path::PathBuf,
+	};
+
+	use crate::{
+		backup::BackupMode,
+		error::InstallError,
+		read::{Archive, Entry, Reader, V2Reader},
+		workspace::ArchivePaths,
+		write::{V2Writer, Writer},
+	};
+
+	use super::{apply, install, plan, revert, uninstall, ApplyOptions, Rules};
+
+	fn copy_fixtures(name: &str) -> PathBuf {
+		let folder = std::env::temp_dir().join(format!("gta-img-install-{}-{}", name, std::process::id()));
+
+		fs::create_dir_all(&folder).expect("failed to create folder");
+
+		for file in ["virgo.dff", "landstal.dff", "v2.img"] {
+			fs::copy(PathBuf::from("test").join(file), folder.join(file)).expect("failed to copy fixture");
+		}
+
+		folder
+	}
+
+	#[test]
+	fn test_rules() {
+		let rules = Rules::new().with("player*", "player.img").with("*.dff", "gta3.img").with("*.TXD", "gta3.img");
+
+		assert_eq!(rules.target("PLAYER.DFF"), Some("player.img"));
+		assert_eq!(rules.target("virgo.dff"), Some("gta3.img"));
+		assert_eq!(rules.target("virgo.txd"), Some("gta3.img"));
+		assert_eq!(rules.target("virgo.col"), None);
+	}
+
+	#[test]
+	fn test_install() {
+		let folder = copy_fixtures("mod");
+		let plan = plan(&folder, &Rules::new().with("*.dff", "gta3.img")).expect("failed to plan installation");
+
+		assert_eq!(plan.targets["gta3.img"].len(), 2);
+		assert_eq!(plan.unmapped.len(), 1);
+
+		let mut original = Cursor::new(vec![1; 4096]);
+		let mut archive = Archive::from_entries(
+			&mut original,
+			vec![Entry::new("VIRGO.DFF", 0, 1).expect("failed to create entry"), Entry::new("VIRGO.TXD", 1, 1).expect("failed to create entry")],
+		);
+
+		// Install the mod, replacing one entry and adding another.
+
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 3).expect("failed to create writer");
+
+		let receipt = install(&mut archive, &plan.targets["gta3.img"], &mut writer).expect("failed to install mod");
+
+		writer.finish().expect("failed to finish archive");
+
+		assert_eq!(receipt.added, ["landstal.dff"]);
+		assert_eq!(receipt.replaced.len(), 1);
+		assert_eq!(receipt.replaced[0].name, "VIRGO.DFF");
+
+		img.set_position(0);
+
+		let mut installed: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let mut data = Vec::new();
+
+		installed.open(0).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert!(data.starts_with(include_bytes!("../test/virgo.dff")));
+
+		// Uninstall the mod, restoring the original entries.
+
+		let mut restored = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut restored, 2).expect("failed to create writer");
+
+		assert_eq!(uninstall(&mut installed, &receipt, &mut writer).expect("failed to uninstall mod"), 2);
+
+		writer.finish().expect("failed to finish archive");
+
+		restored.set_position(0);
+
+		let mut restored: Archive<_> = V2Reader::new(&mut restored).read().expect("failed to read archive");
+		let mut data = Vec::new();
+
+		restored.open(0).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert_eq!(data, vec![1; 2048]);
+		assert_eq!(restored.iter().map(|entry| entry.name.as_str()).collect::<Vec<_>>(), ["VIRGO.DFF", "VIRGO.TXD"]);
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+
+	#[test]
+	fn test_apply() {
+		let folder = copy_fixtures("apply");
+
+		// Write an archive to install the mod into.
+
+		let mut writer =
+			V2Writer::new(fs::File::create(folder.join("gta3.img")).expect("failed to create archive"), 2).expect("failed to create writer");
+
+		writer.write("VIRGO.DFF", &mut [1; 2048].as_slice()).expect("failed to write entry");
+		writer.write("VIRGO.TXD", &mut [2; 2048].as_slice()).expect("failed to write entry");
+		writer.finish().expect("failed to finish archive");
+
+		let planned = plan(&folder, &Rules::new().with("*.dff", "GTA3.IMG")).expect("failed to plan installation");
+		let archives = [ArchivePaths::V2(folder.join("v2.img")), ArchivePaths::V2(folder.join("gta3.img"))];
+
+		let read = |folder: &PathBuf| {
+			let mut img = fs::File::open(folder.join("gta3.img")).expect("failed to open archive");
+			let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+			let names: Vec<String> = archive.iter().map(|entry| entry.name.clone()).collect();
+			let data = archive.read_entry(0).expect("failed to read entry").expect("expected entry");
+
+			(names, data)
+		};
+
+		let (original_names, original_data) = read(&folder);
+
+		// Install the mod into the archive in place, replacing one entry and adding another.
+
+		let installed = apply(&planned, &archives, &ApplyOptions::default()).expect("failed to apply plan");
+
+		assert_eq!(installed.len(), 1);
+		assert!(installed[0].backup.is_some());
+		assert!(folder.join("gta3.img.bak").exists());
+		assert_eq!(installed[0].receipt.added, ["landstal.dff"]);
+		assert_eq!(installed[0].receipt.replaced.len(), 1);
+
+		let (names, data) = read(&folder);
+
+		assert_eq!(names.len(), original_names.len() + 1);
+		assert!(data.starts_with(include_bytes!("../test/virgo.dff")));
+
+		// Revert the installation, restoring the original entries.
+
+		revert(&installed).expect("failed to revert installation");
+
+		assert_eq!(read(&folder), (original_names, original_data));
+
+		// Install the mod once more, restoring the archive from a backup of its entries instead.
+
+		let original = fs::read(folder.join("gta3.img")).expect("failed to read archive");
+		let options = ApplyOptions {
+			backup: Some(BackupMode::Entries),
+		};
+
+		let installed = apply(&planned, &archives, &options).expect("failed to apply plan");
+
+		assert_ne!(fs::read(folder.join("gta3.img")).expect("failed to read archive"), original);
+
+		installed[0].backup.as_ref().expect("expected backup").restore().expect("failed to restore backup");
+
+		assert_eq!(fs::read(folder.join("gta3.img")).expect("failed to read archive"), original);
+
+		// Plans targeting archives which are not present should be rejected before modifying any archive.
+
+		let missing = plan(&folder, &Rules::new().with("*.dff", "gta3.img").with("*.img", "gta_int.img")).expect("failed to plan installation");
+
+		assert!(matches!(apply(&missing, &archives, &ApplyOptions::default()), Err(InstallError::MissingTarget(target)) if target == "gta_int.img"));
+		assert_eq!(read(&folder).0, ["VIRGO.DFF", "VIRGO.TXD"]);
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+
+	#[test]
+	#[cfg(feature = "zip")]
+	fn test_plan_zip() {
+		use std::io::Write;
+
+		use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+		use super::plan_zip;
+
+		let folder = std::env::temp_dir().join(format!("gta-img-install-zip-{}", std::process::id()));
+		let path = std::env::temp_dir().join(format!("gta-img-install-zip-{}.zip", std::process::id()));
+
+		// Package a mod with its files nested within a folder, alongside a file attempting to escape the directory.
+
+		let mut zip = ZipWriter::new(fs::File::create(&path).expect("failed to create zip"));
+		let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+		zip.start_file("models/VIRGO.DFF", options).expect("failed to start file");
+		zip.write_all(include_bytes!("../test/virgo.dff")).expect("failed to write file");
+		zip.start_file("../escaped.dff", options).expect("failed to start file");
+		zip.write_all(b"escaped").expect("failed to write file");
+		zip.start_file("readme.txt", options).expect("failed to start file");
+		zip.write_all(b"readme").expect("failed to write file");
+		zip.finish().expect("failed to finish zip");
+
+		let plan = plan_zip(&path, &folder, &Rules::new().with("*.dff", "gta3.img")).expect("failed to plan installation");
+
+		assert_eq!(plan.targets["gta3.img"].len(), 1);
+		assert_eq!(plan.targets["gta3.img"][0].name, "VIRGO.DFF");
+		assert_eq!(fs::read(&plan.targets["gta3.img"][0].path).expect("failed to read file"), include_bytes!("../test/virgo.dff"));
+		assert_eq!(plan.unmapped, [folder.join("readme.txt")]);
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+		fs::remove_file(path).expect("failed to remove zip");
+	}
+}
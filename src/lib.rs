@@ -1,22 +1,227 @@
 //! Library for reading from/writing to `IMG` archives (and supplementary `DIR` files) used throughout the 3D universe-era of Grand Theft Auto games.
 
+use std::io::{self, Read, Seek};
+
+use read::Reader;
+
 /// Contains types for errors.
 pub mod error;
 
+/// Contains types and the accompanying logic for validating archives.
+pub mod validate;
+
+/// Contains types for auditing and packing archives against IDE and IPL definition files.
+pub mod audit;
+
+/// Contains types for identifying the games archives are from, and the constraints of archives loadable by each.
+pub mod game;
+
+/// Contains types for presenting several archives as a single view of their entries.
+pub mod set;
+
+/// Contains types for stacking patch archives and folders over a base archive.
+pub mod overlay;
+
+/// Contains types for inspecting the RenderWare streams within models and texture dictionaries.
+pub mod renderware;
+
+/// Contains types for reading, splitting and merging the collision models within collision entries.
+pub mod collision;
+
+/// Contains types for writing and verifying manifests of checksums embedded within archives.
+pub mod manifest;
+
+/// Contains types for mapping the health of the sectors of archives.
+pub mod map;
+
+/// Contains types for interpreting the names of entries as a tree of directories.
+pub mod tree;
+
+/// Contains types for mapping the names of entries to file names which behave identically across platforms.
+pub mod portable;
+
+/// Contains types for the limits imposed by the format of archives.
+pub mod limit;
+
+/// Contains types for observing events raised during operations on archives.
+pub mod event;
+
+/// Contains the logic for calculating and verifying checksums of entries, including `sfv` files.
+pub mod checksum;
+
+/// Contains types for locating the files making up archives.
+pub mod paths;
+
+/// Contains types for discovering and querying the archives within a game installation.
+pub mod workspace;
+
+/// Contains types for indexing and searching the entries of many archives.
+pub mod index;
+
+/// Contains types for comparing the entries of archives.
+pub mod diff;
+
+/// Contains types for installing and uninstalling mods into archives.
+pub mod install;
+
+/// Contains types for backing up archives before modifying them, and restoring them afterwards.
+pub mod backup;
+
+/// Contains types for repacking and converting archives.
+pub mod repack;
+
+/// Contains the logic for verifying whether archives would be preserved byte-for-byte when rewritten.
+pub mod roundtrip;
+
 /// Contains types and the accompanying logic for reading from archives of different versions.
 pub mod read;
 
 /// Contains types and the accompanying logic for writing to archives of different versions.
 pub mod write;
 
+/// Contains types for reading archives hosted on web servers using HTTP range requests.
+#[cfg(feature = "remote")]
+pub mod remote;
+
+/// Contains types for recording the original layout of archives, such that they can be rebuilt byte-for-byte.
+#[cfg(feature = "sidecar")]
+pub mod sidecar;
+
+/// Contains re-exports of the most commonly used types.
+pub mod prelude;
+
+/// Contains bindings for Node.js.
+#[cfg(feature = "node")]
+pub mod node;
+
+/// Contains bindings for foreign languages (Swift, Kotlin, etc.).
+#[cfg(feature = "uniffi")]
+pub mod ffi;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+/// Reads an archive using the specified reader, which may be any implementation of [`read::Reader`] (including those for formats outside of this crate).
+pub fn read<'a, I, R>(reader: R) -> Result<read::Archive<'a, I>, error::ReadError>
+where
+	R: read::Reader<'a, I>,
+{
+	reader.read()
+}
+
+/// Detects the version of the archive from `img` by the presence of the V2-styled header, rewinding it afterwards.
+/// Sources too short to contain the header are assumed to be V1-styled archives.
+pub fn detect<I>(img: &mut I) -> Result<game::Version, io::Error>
+where
+	I: Read + Seek,
+{
+	let mut header = [0; VERSION_2_HEADER.len()];
+
+	img.rewind()?;
+
+	let result = img.read_exact(&mut header);
+
+	img.rewind()?;
+
+	match result {
+		Ok(()) if header == VERSION_2_HEADER => Ok(game::Version::V2),
+		Ok(()) => Ok(game::Version::V1),
+		Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => Ok(game::Version::V1),
+		Err(error) => Err(error),
+	}
+}
+
+/// Reads the archive from `img` (and `dir`, if a V1-styled archive), detecting its version as per [`detect`].
+/// Returns an error if the archive is detected as a V1-styled archive, but no `dir` is provided.
+pub fn read_auto<'a, I>(img: &'a mut I, dir: Option<&mut dyn Read>) -> Result<read::Archive<'a, I>, error::ReadError>
+where
+	I: Read + Seek,
+{
+	match (detect(img)?, dir) {
+		(game::Version::V2, _) => read::V2Reader::new(img).read(),
+		(game::Version::V1, Some(mut dir)) => read::V1Reader::new(&mut dir, img).read(),
+		(game::Version::V1, None) => Err(error::ReadError::MissingDirectory),
+	}
+}
+
 /// Represents the number of bytes of a sector.
 pub const SECTOR_SIZE: u64 = 2048;
 
 /// Represents the maximum length of the name of an entry, excluding the null-terminator.
 pub const NAME_SIZE: usize = 23;
 
+/// Represents the characters reserved by either the games or common filesystems, which should not appear within the names of entries.
+pub const RESERVED_CHARACTERS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
 /// Represents the null terminator for the names of entries.
 pub const NULL_TERMINATOR: u8 = b'\0';
 
 /// Represents the structure for a V2-style header.
 pub const VERSION_2_HEADER: [u8; 4] = [0x56, 0x45, 0x52, 0x32]; // VER2
+
+/// Represents the offset for where the entries are located in the header of a V2-styled archive.
+pub(crate) const VERSION_2_HEADER_ENTRY_OFFSET: u64 = 8;
+
+/// Represents the size of an individual entry in either a `dir` file or the header of a V2-styled archive.
+pub(crate) const ENTRY_SIZE: u64 = 32;
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		error::ReadError,
+		game::Version,
+		read::{Archive, Entry, Reader, V2Reader},
+	};
+
+	/// Represents a reader of a hypothetical format, in which every sector is an entry.
+	struct SectorReader<'a>(&'a mut Cursor<Vec<u8>>);
+
+	impl<'a> Reader<'a, Cursor<Vec<u8>>> for SectorReader<'a> {
+		fn read(self) -> Result<Archive<'a, Cursor<Vec<u8>>>, ReadError> {
+			let sectors = self.0.get_ref().len() as u64 / crate::SECTOR_SIZE;
+			let entries = (0..sectors).map(|sector| Entry::new(&format!("{}.DAT", sector), sector, 1).expect("failed to create entry")).collect();
+
+			Ok(Archive::from_entries(self.0, entries))
+		}
+	}
+
+	#[test]
+	fn test_read() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive = super::read(V2Reader::new(&mut img)).expect("failed to read archive");
+
+		assert_eq!(archive.len(), 3);
+
+		let mut img = Cursor::new(vec![0; 4096]);
+		let archive = super::read(SectorReader(&mut img)).expect("failed to read archive");
+
+		assert_eq!(archive.len(), 2);
+		assert_eq!(archive.get(1).map(|entry| entry.name.as_str()), Some("1.DAT"));
+	}
+
+	#[test]
+	fn test_detect() {
+		assert_eq!(super::detect(&mut Cursor::new(include_bytes!("../test/v1.img"))).expect("failed to detect version"), Version::V1);
+		assert_eq!(super::detect(&mut Cursor::new(include_bytes!("../test/v2.img"))).expect("failed to detect version"), Version::V2);
+		assert_eq!(super::detect(&mut Cursor::new(b"VE")).expect("failed to detect version"), Version::V1);
+	}
+
+	#[test]
+	fn test_read_auto() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive = super::read_auto(&mut img, None).expect("failed to read archive");
+
+		assert_eq!(archive.len(), 3);
+
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		assert!(matches!(super::read_auto(&mut img, None), Err(ReadError::MissingDirectory)));
+
+		let archive = super::read_auto(&mut img, Some(&mut dir)).expect("failed to read archive");
+
+		assert_eq!(archive.len(), 3);
+	}
+}
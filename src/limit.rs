@@ -0,0 +1,98 @@
+use std::{error::Error, fmt};
+
+use crate::NAME_SIZE;
+
+/// Represents a limit imposed by the format of an archive, beyond which values cannot be represented.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Limit {
+	/// Indicates the offset, in sectors, at which an entry begins or ends.
+	Offset,
+
+	/// Indicates the length, in sectors, of an entry within a V1-styled archive.
+	V1Length,
+
+	/// Indicates the length, in sectors, of an entry within a V2-styled archive.
+	V2Length,
+
+	/// Indicates the length, in characters, of the name of an entry.
+	NameLength,
+
+	/// Indicates the number of entries within a V2-styled archive.
+	V2Count,
+}
+
+/// Represents a value found to exceed a limit.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Exceeded {
+	/// The limit exceeded.
+	pub limit: Limit,
+
+	/// The value exceeding the limit.
+	pub value: u64,
+}
+
+impl Limit {
+	/// Returns the maximum value permitted by the limit.
+	pub fn max(self) -> u64 {
+		match self {
+			Self::Offset => u32::MAX as u64,
+			Self::V1Length => u32::MAX as u64,
+			Self::V2Length => u16::MAX as u64,
+			Self::NameLength => NAME_SIZE as u64,
+			Self::V2Count => u32::MAX as u64,
+		}
+	}
+
+	/// Checks that the specified value lies within the limit.
+	pub fn check(self, value: u64) -> Result<(), Exceeded> {
+		match value > self.max() {
+			true => Err(Exceeded {
+				limit: self,
+				value,
+			}),
+			false => Ok(()),
+		}
+	}
+}
+
+impl Error for Exceeded {}
+
+impl fmt::Display for Limit {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Offset => write!(f, "offset"),
+			Self::V1Length => write!(f, "length"),
+			Self::V2Length => write!(f, "length"),
+			Self::NameLength => write!(f, "name length"),
+			Self::V2Count => write!(f, "entry count"),
+		}
+	}
+}
+
+impl fmt::Display for Exceeded {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} of {} exceeds maximum of {}", self.limit, self.value, self.limit.max())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Exceeded, Limit};
+
+	#[test]
+	fn test_check() {
+		assert!(Limit::V2Length.check(65535).is_ok());
+		assert_eq!(
+			Limit::V2Length.check(65536),
+			Err(Exceeded {
+				limit: Limit::V2Length,
+				value: 65536,
+			})
+		);
+
+		assert!(Limit::NameLength.check(23).is_ok());
+		assert!(Limit::NameLength.check(24).is_err());
+
+		assert!(Limit::Offset.check(u32::MAX as u64 + 1).is_err());
+	}
+}
@@ -1,9 +1,17 @@
 //! Command-line application demonstrating usage of the `gta-img` library.
 
-use std::{fs::File, io, path::PathBuf};
+use std::{fs::File, path::PathBuf};
 
-use clap::{command, Parser, Subcommand};
-use gta_img::read::{Reader, V1Reader, V2Reader};
+use clap::{Parser, Subcommand};
+use gta_img::{
+	paths::V1Paths,
+	read::{Reader, V1Reader, V2Reader},
+	renderware,
+	validate::ValidateOptions,
+};
+
+#[cfg(feature = "sidecar")]
+use gta_img::sidecar::Sidecar;
 
 /// Performs basic operations on IMG/DIR archives
 #[derive(Debug, Parser)]
@@ -21,6 +29,25 @@ enum Operation {
 		/// Specifies the archive to inspect
 		#[command(subcommand)]
 		version: Version,
+
+		/// Lists the textures within each texture dictionary
+		#[arg(short, long)]
+		long: bool,
+	},
+
+	/// Validate the contents of an archive, reporting any issues found
+	Validate {
+		/// Specifies the archive to validate
+		#[command(subcommand)]
+		version: Version,
+
+		/// Checks that the regions not claimed by any entry contain only zero padding
+		#[arg(short, long)]
+		deep: bool,
+
+		/// Prints a map of the sectors of the archive, marking those which are healthy, suspect or unreadable
+		#[arg(short, long)]
+		map: bool,
 	},
 
 	/// Extract the contents of an archive to an output directory
@@ -32,6 +59,15 @@ enum Operation {
 		/// Specifies the output directory
 		#[arg(short, long)]
 		target: PathBuf,
+
+		/// Interprets separators within the names of entries as folders, recreating the structure within the output directory
+		#[arg(short, long)]
+		folders: bool,
+
+		/// Writes a sidecar recording the original layout of the archive alongside the entries, such that it can be rebuilt byte-for-byte
+		#[cfg(feature = "sidecar")]
+		#[arg(short, long)]
+		sidecar: bool,
 	},
 }
 
@@ -43,8 +79,8 @@ enum Version {
 		/// Specifies the img file
 		img: PathBuf,
 
-		/// Specifies the dir file
-		dir: PathBuf,
+		/// Specifies the dir file (otherwise derived from the img file)
+		dir: Option<PathBuf>,
 	},
 	/// Dictates a V2-styled archive (img file only)
 	V2 {
@@ -53,6 +89,10 @@ enum Version {
 	},
 }
 
+/// Represents the name of the file the sidecar is written to within the output directory.
+#[cfg(feature = "sidecar")]
+const SIDECAR_NAME: &str = "sidecar.json";
+
 fn main() {
 	let cli = Cli::parse();
 
@@ -64,10 +104,15 @@ fn main() {
 	let version = match &cli.operation {
 		Operation::Inspect {
 			version,
+			..
+		} => version,
+		Operation::Validate {
+			version,
+			..
 		} => version,
 		Operation::Extract {
 			version,
-			target: _,
+			..
 		} => version,
 	};
 
@@ -78,8 +123,12 @@ fn main() {
 			img,
 			dir,
 		} => {
-			img_file = File::open(img).expect("failed to open img file");
-			dir_file = File::open(dir).expect("failed to open dir file");
+			let paths = match dir {
+				Some(dir) => V1Paths::new(img, dir),
+				None => V1Paths::from_img(img),
+			};
+
+			(dir_file, img_file) = paths.expect("failed to locate archive").open().expect("failed to open archive");
 
 			println!("Reading V1-styled archive...");
 
@@ -96,39 +145,128 @@ fn main() {
 		}
 	};
 
+	for warning in archive.warnings() {
+		println!("Warning: {}", warning);
+	}
+
 	// Perform the operation.
 
 	match cli.operation {
 		Operation::Inspect {
 			version: _,
+			long,
 		} => {
 			println!("Inspecting contents of archive...");
 
-			for entry in archive.iter() {
-				println!("[{:<24}] offset: {}, length: {}", entry.name, entry.offset, entry.length);
+			for index in 0..archive.len() {
+				println!("{}", archive.get(index).expect("failed to get entry"));
+
+				if long {
+					for texture in renderware::entry_textures(&mut archive, index).expect("failed to list textures").unwrap_or_default() {
+						println!("\t{}", texture);
+					}
+				}
 			}
 
-			println!("Inspected {} entries.", archive.len());
+			println!("Inspected {}.", archive.stats());
 		}
-		Operation::Extract {
+		Operation::Validate {
 			version: _,
+			deep,
+			map,
+		} => {
+			println!("Validating contents of archive...");
+
+			let options = ValidateOptions {
+				deep,
+				..ValidateOptions::default()
+			};
+
+			let issues = archive.validate(&options).expect("failed to validate archive");
+
+			for issue in &issues {
+				println!("{}", issue);
+			}
+
+			println!("Found {} issues.", issues.len());
+
+			if map {
+				let map = archive.sector_map(&issues).expect("failed to map archive");
+
+				for region in &map.regions {
+					println!("{}", region);
+				}
+			}
+		}
+		Operation::Extract {
+			version,
 			target,
+			folders,
+			#[cfg(feature = "sidecar")]
+			sidecar,
 		} => {
+			// Capture the original layout of the archive, if requested, naming any shadowed entries after their index.
+
+			#[cfg(feature = "sidecar")]
+			let sidecar = sidecar.then(|| {
+				let sidecar = match version {
+					Version::V1 {
+						img,
+						dir,
+					} => {
+						let paths = match dir {
+							Some(dir) => V1Paths::new(img, dir),
+							None => V1Paths::from_img(img),
+						};
+
+						let (mut dir_file, _) = paths.expect("failed to locate archive").open().expect("failed to open archive");
+
+						Sidecar::capture_dir(&mut archive, &mut dir_file)
+					}
+					Version::V2 {
+						..
+					} => Sidecar::capture(&mut archive),
+				};
+
+				sidecar.expect("failed to capture sidecar")
+			});
+
+			#[cfg(not(feature = "sidecar"))]
+			let _ = version;
+
 			println!("Extracting contents of archive to path...");
 
-			for index in 0..archive.len() {
-				let entry = archive.get(index).expect("failed to get entry");
-				let path = target.join(&entry.name);
+			// Extract to the portable file names recorded by the sidecar, never as folders.
 
-				println!("Extracting entry [{}] to file <{}>...", entry.name, &path.display());
+			#[cfg(feature = "sidecar")]
+			let folders = folders && sidecar.is_none();
 
-				let mut open = archive.open(index).expect("failed to open entry");
-				let mut file = File::create(&path).expect("failed to create entry file");
+			// Extract to portable file names, unless interpreting the names of the entries as folders.
 
-				io::copy(&mut open, &mut file).expect("failed to extract entry to file");
+			let extracted = match folders {
+				true => archive.extract_tree(&target),
+				false => archive.extract_all(&target),
+			};
+
+			let extracted = extracted.expect("failed to extract archive");
+
+			for extracted in &extracted {
+				match &extracted.result {
+					Ok(_) => println!("Extracted entry [{}] to file <{}>.", extracted.name.name, target.join(&extracted.name.file).display()),
+					Err(error) => println!("Failed to extract entry [{}]: {}", extracted.name.name, error),
+				}
 			}
 
-			println!("Extracted {} entries.", archive.len());
+			println!("Extracted {} entries.", extracted.iter().filter(|extracted| extracted.result.is_ok()).count());
+
+			#[cfg(feature = "sidecar")]
+			if let Some(sidecar) = sidecar {
+				let path = target.join(SIDECAR_NAME);
+
+				println!("Writing sidecar to file <{}>...", path.display());
+
+				std::fs::write(path, sidecar.to_json().expect("failed to serialise sidecar")).expect("failed to write sidecar");
+			}
 		}
 	}
 }
@@ -0,0 +1,197 @@
+use std::io::{self, Cursor, Read, Seek};
+
+use crate::{
+	checksum::{self, Discrepancy},
+	error::{ChecksumError, WriteError},
+	read::{Archive, Entry},
+	write::Writer,
+	NULL_TERMINATOR, SECTOR_SIZE,
+};
+
+/// Represents the name of the reserved entry containing the manifest of an archive.
+pub const MANIFEST_NAME: &str = "__CHECKSUM.DAT";
+
+/// Represents a writer which records the CRC32 checksum of each of the entries written, before writing them as a manifest entry within the archive itself.
+/// The manifest is in the same format as an `sfv` file, and is written as the final entry when finished.
+#[derive(Debug)]
+pub struct ManifestWriter<W>
+where
+	W: Writer,
+{
+	inner: W,
+	checksums: Vec<(String, u32)>,
+}
+
+impl<W> ManifestWriter<W>
+where
+	W: Writer,
+{
+	/// Creates a new manifest writer wrapping the specified writer.
+	/// Note that V2-styled writers must be created with capacity for the manifest entry in addition to the other entries.
+	pub fn new(inner: W) -> Self {
+		Self {
+			inner,
+			checksums: Vec::new(),
+		}
+	}
+
+	/// Writes the manifest entry to the archive, returning the wrapped writer.
+	pub fn finish(mut self) -> Result<W, WriteError> {
+		let mut manifest = Vec::new();
+
+		for (name, crc) in &self.checksums {
+			checksum::write_line(&mut manifest, name, *crc)?;
+		}
+
+		self.inner.write(MANIFEST_NAME, &mut manifest.as_slice())?;
+
+		Ok(self.inner)
+	}
+}
+
+impl<W> Writer for ManifestWriter<W>
+where
+	W: Writer,
+{
+	fn write<T>(&mut self, name: &str, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.write_hashed(name, src, |inner, src| inner.write(name, src))
+	}
+
+	fn write_entry<T>(&mut self, entry: &Entry, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.write_hashed(&entry.name, src, |inner, src| inner.write_entry(entry, src))
+	}
+
+	fn write_unclaimed<T>(&mut self, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.inner.write_unclaimed(src)
+	}
+}
+
+impl<W> ManifestWriter<W>
+where
+	W: Writer,
+{
+	fn write_hashed<T, F>(&mut self, name: &str, src: &mut T, write: F) -> Result<(), WriteError>
+	where
+		T: Read,
+		F: FnOnce(&mut W, &mut HashingReader<'_, T>) -> Result<(), WriteError>,
+	{
+		let mut src = HashingReader {
+			inner: src,
+			hasher: crc32fast::Hasher::new(),
+			bytes: 0,
+		};
+
+		write(&mut self.inner, &mut src)?;
+
+		// Include the padding up to the end of the final sector, in line with the checksums of entries.
+
+		let padding = src.bytes.div_ceil(SECTOR_SIZE) * SECTOR_SIZE - src.bytes;
+
+		src.hasher.update(&vec![0; padding as usize]);
+
+		self.checksums.push((name.to_owned(), src.hasher.finalize()));
+
+		Ok(())
+	}
+}
+
+struct HashingReader<'a, T> {
+	inner: &'a mut T,
+	hasher: crc32fast::Hasher,
+	bytes: u64,
+}
+
+impl<T> Read for HashingReader<'_, T>
+where
+	T: Read,
+{
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let len = self.inner.read(buf)?;
+
+		self.hasher.update(&buf[..len]);
+		self.bytes += len as u64;
+
+		Ok(len)
+	}
+}
+
+/// Verifies each of the entries in the archive against the manifest entry within the archive, returning any discrepancies found.
+/// Returns `None` if the archive does not contain a manifest entry.
+pub fn verify_manifest<I>(archive: &mut Archive<I>) -> Result<Option<Vec<Discrepancy>>, ChecksumError>
+where
+	I: Read + Seek,
+{
+	let Some(index) = archive.iter().position(|entry| entry.name.eq_ignore_ascii_case(MANIFEST_NAME)) else {
+		return Ok(None);
+	};
+
+	// Read the manifest, discarding the padding up to the end of the final sector.
+
+	let mut manifest = archive.read_entry(index)?.unwrap_or_default();
+
+	let len = manifest.iter().rposition(|&b| b != NULL_TERMINATOR).map_or(0, |position| position + 1);
+
+	manifest.truncate(len);
+
+	// Verify against the manifest, which naturally does not list itself.
+
+	let mut discrepancies = checksum::verify_sfv(archive, &mut Cursor::new(manifest))?;
+
+	discrepancies.retain(|discrepancy| !matches!(discrepancy, Discrepancy::Unlisted { name } if name.eq_ignore_ascii_case(MANIFEST_NAME)));
+
+	Ok(Some(discrepancies))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		checksum::Discrepancy,
+		read::{Archive, Reader, V2Reader},
+		write::{V2Writer, Writer},
+	};
+
+	use super::{verify_manifest, ManifestWriter, MANIFEST_NAME};
+
+	#[test]
+	fn test_manifest() {
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = ManifestWriter::new(V2Writer::new(&mut img, 3).expect("failed to create writer"));
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write first entry");
+		writer.write("LANDSTAL.DFF", &mut Cursor::new(include_bytes!("../test/landstal.dff"))).expect("failed to write second entry");
+		writer.finish().expect("failed to write manifest");
+
+		// Corrupt the data of the second entry.
+
+		img.get_mut()[2 * 2048 + 4] ^= 0xFF;
+		img.set_position(0);
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.get(2).map(|entry| entry.name.as_str()), Some(MANIFEST_NAME));
+
+		let discrepancies = verify_manifest(&mut archive).expect("failed to verify manifest").expect("expected manifest");
+
+		assert_eq!(discrepancies.len(), 1);
+		assert!(matches!(&discrepancies[0], Discrepancy::Mismatch { name, .. } if name == "LANDSTAL.DFF"));
+	}
+
+	#[test]
+	fn test_manifest_missing() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert!(verify_manifest(&mut archive).expect("failed to verify manifest").is_none());
+	}
+}
@@ -0,0 +1,280 @@
+use std::{
+	fmt,
+	io::{self, Read, Seek, SeekFrom},
+};
+
+use crate::{
+	read::Archive,
+	validate::{find_gaps, Issue, IssueKind, Severity},
+	SECTOR_SIZE,
+};
+
+/// Represents the health of a region of an archive.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Health {
+	/// Indicates that no issues were found within the region.
+	Healthy,
+
+	/// Indicates that the region is concerned by one or more issues, and its contents may be damaged.
+	Suspect,
+
+	/// Indicates that the region could not be read, or lies beyond the end of the archive.
+	Unreadable,
+}
+
+/// Represents a contiguous region of sectors sharing the same health.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Region {
+	/// The sector at which the region begins.
+	pub start: u64,
+
+	/// The sector at which the region ends (exclusive).
+	pub end: u64,
+
+	/// The health of the region.
+	pub health: Health,
+}
+
+/// Represents a contiguous range of sectors not claimed by any entry (nor by the header), which may be reused by further entries.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Gap {
+	/// The sector at which the gap begins.
+	pub start: u64,
+
+	/// The sector at which the gap ends (exclusive).
+	pub end: u64,
+}
+
+/// Represents a sector-level map of an archive, as contiguous regions of differing health.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct SectorMap {
+	/// The regions of the archive, in order of their sectors.
+	pub regions: Vec<Region>,
+}
+
+impl SectorMap {
+	/// Returns the total number of sectors of the specified health.
+	pub fn count(&self, health: Health) -> u64 {
+		self.regions.iter().filter(|region| region.health == health).map(|region| region.end - region.start).sum()
+	}
+
+	fn from_sectors(sectors: &[Health]) -> Self {
+		let mut regions: Vec<Region> = Vec::new();
+
+		for (sector, &health) in sectors.iter().enumerate() {
+			match regions.last_mut() {
+				Some(region) if region.health == health => region.end += 1,
+				_ => regions.push(Region {
+					start: sector as u64,
+					end: sector as u64 + 1,
+					health,
+				}),
+			}
+		}
+
+		Self {
+			regions,
+		}
+	}
+}
+
+impl Gap {
+	/// Returns the length, in sectors, of the gap.
+	pub fn len(&self) -> u64 {
+		self.end - self.start
+	}
+
+	/// Indicates whether the gap is empty.
+	pub fn is_empty(&self) -> bool {
+		self.start == self.end
+	}
+}
+
+impl<I> Archive<'_, I>
+where
+	I: Read + Seek,
+{
+	/// Returns each of the gaps between the entries of the archive (and between the header and the first entry), in order of their sectors.
+	/// Only the gaps before the end of the archive are returned, with any partial sector at the end of the archive counted as a whole.
+	pub fn gaps(&mut self) -> Result<Vec<Gap>, io::Error> {
+		let len = self.stream_len()?;
+
+		let gaps = find_gaps(self.entries(), self.declared(), len)
+			.into_iter()
+			.map(|(offset, end)| Gap {
+				start: offset / SECTOR_SIZE,
+				end: end.div_ceil(SECTOR_SIZE),
+			})
+			.collect();
+
+		Ok(gaps)
+	}
+
+	/// Builds a sector-level map of the archive, marking each of the regions concerned by the specified issues (see `validate`) as suspect.
+	/// Note that this reads every sector of the archive in order to find any which cannot be read.
+	pub fn sector_map(&mut self, issues: &[Issue]) -> Result<SectorMap, io::Error> {
+		let len = self.stream_len()?;
+
+		// Only the sectors present are mapped individually, as entries may reference arbitrarily far beyond the end.
+
+		let present = len.div_ceil(SECTOR_SIZE);
+		let furthest = self.entries().iter().map(|entry| entry.offset + entry.length).max().unwrap_or(0);
+
+		let mut sectors = vec![Health::Healthy; present as usize];
+
+		// Mark any sectors concerned by issues as suspect, including any unclaimed data.
+
+		for issue in issues {
+			let entries = [issue.index, issue.other].into_iter().flatten().filter_map(|index| self.get(index));
+
+			if issue.severity == Severity::Error {
+				for entry in entries {
+					mark(&mut sectors, entry.offset, entry.offset + entry.length, Health::Suspect);
+				}
+			}
+
+			if let (IssueKind::UnclaimedData, Some(offset), Some(length)) = (issue.kind, issue.offset, issue.expected) {
+				mark(&mut sectors, offset / SECTOR_SIZE, (offset + length).div_ceil(SECTOR_SIZE), Health::Suspect);
+			}
+		}
+
+		// Attempt to read each of the sectors, marking any which cannot be read.
+
+		let src = self.inner_mut();
+		let mut buf = [0; SECTOR_SIZE as usize];
+
+		for sector in 0..present {
+			let size = (len - sector * SECTOR_SIZE).min(SECTOR_SIZE) as usize;
+			let result = src.seek(SeekFrom::Start(sector * SECTOR_SIZE)).and_then(|_| src.read_exact(&mut buf[..size]));
+
+			if result.is_err() {
+				mark(&mut sectors, sector, sector + 1, Health::Unreadable);
+			}
+		}
+
+		let mut map = SectorMap::from_sectors(&sectors);
+
+		if furthest > present {
+			match map.regions.last_mut() {
+				Some(region) if region.health == Health::Unreadable => region.end = furthest,
+				_ => map.regions.push(Region {
+					start: present,
+					end: furthest,
+					health: Health::Unreadable,
+				}),
+			}
+		}
+
+		Ok(map)
+	}
+}
+
+fn mark(sectors: &mut [Health], start: u64, end: u64, health: Health) {
+	let end = (end as usize).min(sectors.len());
+	let start = (start as usize).min(end);
+
+	for sector in &mut sectors[start..end] {
+		*sector = (*sector).max(health);
+	}
+}
+
+impl fmt::Display for Health {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Healthy => write!(f, "healthy"),
+			Self::Suspect => write!(f, "suspect"),
+			Self::Unreadable => write!(f, "unreadable"),
+		}
+	}
+}
+
+impl fmt::Display for Region {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "sectors {}..{} {}", self.start, self.end, self.health)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		read::{Archive, Entry, Reader, V1Reader},
+		validate::ValidateOptions,
+	};
+
+	use super::{Gap, Health, Region, SectorMap};
+
+	#[test]
+	fn test_gaps() {
+		let mut img = Cursor::new(vec![0; 7 * 2048 + 1]);
+		let mut archive = Archive::from_entries(
+			&mut img,
+			vec![Entry::new("A", 2, 1).expect("failed to create entry"), Entry::new("B", 4, 2).expect("failed to create entry")],
+		);
+
+		let gaps = archive.gaps().expect("failed to find gaps");
+
+		assert_eq!(
+			gaps,
+			vec![
+				Gap {
+					start: 0,
+					end: 2,
+				},
+				Gap {
+					start: 3,
+					end: 4,
+				},
+				Gap {
+					start: 6,
+					end: 8,
+				},
+			]
+		);
+
+		assert_eq!(gaps.iter().map(Gap::len).sum::<u64>(), 5);
+	}
+
+	#[test]
+	fn test_from_sectors() {
+		let map = SectorMap::from_sectors(&[Health::Healthy, Health::Healthy, Health::Suspect, Health::Unreadable, Health::Unreadable]);
+
+		assert_eq!(map.regions.len(), 3);
+		assert_eq!(map.count(Health::Healthy), 2);
+		assert_eq!(map.count(Health::Suspect), 1);
+		assert_eq!(map.count(Health::Unreadable), 2);
+	}
+
+	#[test]
+	fn test_sector_map() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+
+		let issues = archive.validate(&ValidateOptions::default()).expect("failed to validate archive");
+		let map = archive.sector_map(&issues).expect("failed to map archive");
+
+		assert_eq!(
+			map.regions,
+			vec![
+				Region {
+					start: 0,
+					end: 1,
+					health: Health::Healthy,
+				},
+				Region {
+					start: 1,
+					end: 2,
+					health: Health::Suspect,
+				},
+				Region {
+					start: 2,
+					end: 11,
+					health: Health::Unreadable,
+				},
+			]
+		);
+	}
+}
@@ -0,0 +1,133 @@
+//! Node.js bindings, exposing the listing, extraction and packing of archives to JavaScript via `napi-rs`.
+//! The crate must be built as a `cdylib` for the resulting library to be loaded as a native addon.
+
+use std::{fs::File, path::Path};
+
+use napi_derive::napi;
+
+use crate::{
+	error::WriteError,
+	read::{Archive, Reader, V1Reader, V2Reader},
+	write::{V1Writer, V2Writer, Writer},
+};
+
+/// Represents an entry as exposed to JavaScript.
+#[napi(object)]
+pub struct JsEntry {
+	/// The name of the entry.
+	pub name: String,
+
+	/// The offset, in sectors, of the entry.
+	pub offset: u32,
+
+	/// The length, in sectors, of the entry.
+	pub length: u32,
+}
+
+/// Lists the entries of the archive at `img`, using the V1 format if `dir` is specified and the V2 format otherwise.
+#[napi]
+pub fn list(img: String, dir: Option<String>) -> napi::Result<Vec<JsEntry>> {
+	with_archive(&img, dir.as_deref(), |archive| {
+		// Fail should the offset or length of any entry not fit within a JavaScript-friendly number.
+
+		archive
+			.iter()
+			.map(|entry| {
+				Ok(JsEntry {
+					name: entry.name.clone(),
+					offset: to_u32(entry.offset, "offset", &entry.name)?,
+					length: to_u32(entry.length, "length", &entry.name)?,
+				})
+			})
+			.collect()
+	})
+}
+
+/// Extracts each of the entries of the archive at `img` to the `target` directory, named such that they are valid on every platform, returning the number of entries extracted.
+#[napi]
+pub fn extract(img: String, dir: Option<String>, target: String) -> napi::Result<u32> {
+	with_archive(&img, dir.as_deref(), |archive| {
+		// Fail upon the first entry which could not be extracted.
+
+		archive
+			.extract_all(&target)
+			.map_err(to_napi_error)?
+			.into_iter()
+			.try_fold(0, |count, extracted| extracted.result.map(|_| count + 1))
+			.map_err(to_napi_error)
+	})
+}
+
+/// Packs each of the `files` into a new archive at `img`, using the V1 format if `dir` is specified and the V2 format otherwise.
+/// Returns the number of entries written.
+#[napi]
+pub fn pack(files: Vec<String>, img: String, dir: Option<String>) -> napi::Result<u32> {
+	let mut img_file = File::create(img).map_err(to_napi_error)?;
+
+	match dir {
+		Some(dir) => {
+			let mut dir_file = File::create(dir).map_err(to_napi_error)?;
+
+			let mut writer = V1Writer::new(&mut dir_file, &mut img_file);
+
+			pack_files(&files, &mut writer)?;
+
+			to_count(writer.finish())
+		}
+		None => {
+			let mut writer = V2Writer::new(&mut img_file, files.len()).map_err(to_napi_error)?;
+
+			pack_files(&files, &mut writer)?;
+
+			to_count(writer.finish())
+		}
+	}
+}
+
+fn with_archive<T, F>(img: &str, dir: Option<&str>, f: F) -> napi::Result<T>
+where
+	F: FnOnce(&mut Archive<File>) -> napi::Result<T>,
+{
+	let mut img_file = File::open(img).map_err(to_napi_error)?;
+
+	match dir {
+		Some(dir) => {
+			let mut dir_file = File::open(dir).map_err(to_napi_error)?;
+
+			f(&mut V1Reader::new(&mut dir_file, &mut img_file).read().map_err(to_napi_error)?)
+		}
+		None => f(&mut V2Reader::new(&mut img_file).read().map_err(to_napi_error)?),
+	}
+}
+
+fn pack_files<W>(files: &[String], writer: &mut W) -> napi::Result<()>
+where
+	W: Writer,
+{
+	for file in files {
+		let path = Path::new(file);
+		let name =
+			path.file_name().and_then(|name| name.to_str()).ok_or_else(|| napi::Error::from_reason(format!("invalid file name <{}>", file)))?;
+
+		let mut src = File::open(path).map_err(to_napi_error)?;
+
+		writer.write(name, &mut src).map_err(to_napi_error)?;
+	}
+
+	Ok(())
+}
+
+fn to_count(finished: Result<usize, WriteError>) -> napi::Result<u32> {
+	u32::try_from(finished.map_err(to_napi_error)?).map_err(to_napi_error)
+}
+
+fn to_u32(value: u64, field: &str, name: &str) -> napi::Result<u32> {
+	u32::try_from(value).map_err(|_| napi::Error::from_reason(format!("{} of entry [{}] exceeds {}", field, name, u32::MAX)))
+}
+
+fn to_napi_error<E>(error: E) -> napi::Error
+where
+	E: ToString,
+{
+	napi::Error::from_reason(error.to_string())
+}
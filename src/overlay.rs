@@ -0,0 +1,329 @@
+use std::{
+	collections::HashMap,
+	fs::{self, File},
+	io::{self, Read, Seek},
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	error::WriteError,
+	read::{Archive, OpenEntry},
+	write::Writer,
+};
+
+/// Represents a stack of layers over a base archive, where each layer is either a patch archive or a folder of loose files.
+/// Names resolve to the topmost layer containing them (i.e. the most recently pushed), in line with the behaviour of mod loaders.
+/// Names are resolved case-insensitively.
+#[derive(Debug)]
+pub struct Overlay<'a, I> {
+	layers: Vec<Layer<'a, I>>,
+}
+
+/// Represents the source of an entry within an overlay.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum Source {
+	/// Indicates an entry within an archive layer.
+	Entry {
+		/// The index of the layer, where the base archive is zero.
+		layer: usize,
+
+		/// The index of the entry within the archive.
+		index: usize,
+	},
+
+	/// Indicates a file within a folder layer.
+	File {
+		/// The index of the layer.
+		layer: usize,
+
+		/// The path of the file.
+		path: PathBuf,
+	},
+}
+
+/// Represents an entry of an overlay opened for reading, from whichever layer it resolved to.
+#[derive(Debug)]
+pub enum OverlayEntry<'a, I>
+where
+	I: Read + Seek,
+{
+	/// Indicates an entry opened from an archive layer.
+	Entry(OpenEntry<'a, I>),
+
+	/// Indicates a file opened from a folder layer.
+	File(File),
+}
+
+#[derive(Debug)]
+enum Layer<'a, I> {
+	Archive(Archive<'a, I>),
+	Folder(Vec<(String, PathBuf)>),
+}
+
+impl<'a, I> Overlay<'a, I> {
+	/// Creates a new overlay over the specified base archive.
+	pub fn new(base: Archive<'a, I>) -> Self {
+		Self {
+			layers: vec![Layer::Archive(base)],
+		}
+	}
+
+	/// Pushes the specified patch archive on top of the stack.
+	pub fn push_archive(&mut self, archive: Archive<'a, I>) {
+		self.layers.push(Layer::Archive(archive));
+	}
+
+	/// Pushes the files directly within the folder at the specified path on top of the stack, named after their file names.
+	pub fn push_folder<P>(&mut self, path: P) -> Result<(), io::Error>
+	where
+		P: AsRef<Path>,
+	{
+		let mut files = Vec::new();
+
+		for file in fs::read_dir(path)? {
+			let file = file?;
+
+			if file.file_type()?.is_file() {
+				files.push((file.file_name().to_string_lossy().into_owned(), file.path()));
+			}
+		}
+
+		// Sort the files, as the order of directory listings is unspecified.
+
+		files.sort();
+
+		self.layers.push(Layer::Folder(files));
+
+		Ok(())
+	}
+
+	/// Returns the number of layers, including the base archive.
+	pub fn len(&self) -> usize {
+		self.layers.len()
+	}
+
+	/// Returns whether the overlay has no layers, which is never the case as the base archive is always present.
+	pub fn is_empty(&self) -> bool {
+		self.layers.is_empty()
+	}
+
+	/// Resolves the source of the entry with the specified name from the topmost layer containing it, if any.
+	pub fn resolve(&self, name: &str) -> Option<Source> {
+		self.layers.iter().enumerate().rev().find_map(|(layer, contents)| match contents {
+			Layer::Archive(archive) => archive.iter().position(|entry| entry.name.eq_ignore_ascii_case(name)).map(|index| Source::Entry {
+				layer,
+				index,
+			}),
+			Layer::Folder(files) => files.iter().find(|(file, _)| file.eq_ignore_ascii_case(name)).map(|(_, path)| Source::File {
+				layer,
+				path: path.clone(),
+			}),
+		})
+	}
+
+	/// Returns the name of each resolved entry along with its source.
+	/// Entries are ordered as within the base archive, followed by any entries introduced by subsequent layers in the order they were introduced.
+	pub fn entries(&self) -> Vec<(String, Source)> {
+		let mut entries: Vec<(String, Source)> = Vec::new();
+		let mut positions: HashMap<String, usize> = HashMap::new();
+
+		for (layer, contents) in self.layers.iter().enumerate() {
+			let sources: Vec<(&str, Source)> = match contents {
+				Layer::Archive(archive) => archive
+					.iter()
+					.enumerate()
+					.map(|(index, entry)| {
+						(
+							entry.name.as_str(),
+							Source::Entry {
+								layer,
+								index,
+							},
+						)
+					})
+					.collect(),
+				Layer::Folder(files) => files
+					.iter()
+					.map(|(name, path)| {
+						(
+							name.as_str(),
+							Source::File {
+								layer,
+								path: path.clone(),
+							},
+						)
+					})
+					.collect(),
+			};
+
+			// Replace the sources of names already present from lower layers, keeping their original position.
+			// Within a single archive, the first entry with a given name takes precedence.
+
+			let mut seen = Vec::new();
+
+			for (name, source) in sources {
+				let key = name.to_ascii_lowercase();
+
+				if seen.contains(&key) {
+					continue;
+				}
+
+				match positions.get(&key) {
+					Some(&position) => entries[position].1 = source,
+					None => {
+						positions.insert(key.clone(), entries.len());
+						entries.push((name.to_owned(), source));
+					}
+				}
+
+				seen.push(key);
+			}
+		}
+
+		entries
+	}
+}
+
+impl<I> Overlay<'_, I>
+where
+	I: Read + Seek,
+{
+	/// Opens and returns the entry with the specified name for reading from the topmost layer containing it, if any.
+	pub fn open(&mut self, name: &str) -> Result<Option<OverlayEntry<'_, I>>, io::Error> {
+		let Some(source) = self.resolve(name) else {
+			return Ok(None);
+		};
+
+		self.open_source(source).map(Some)
+	}
+
+	/// Flattens the stack into a single archive using the specified writer, returning the number of entries written.
+	pub fn flatten<W>(&mut self, writer: &mut W) -> Result<usize, WriteError>
+	where
+		W: Writer,
+	{
+		let entries = self.entries();
+
+		for (name, source) in &entries {
+			let mut src = self.open_source(source.clone())?;
+
+			writer.write(name, &mut src)?;
+		}
+
+		Ok(entries.len())
+	}
+
+	fn open_source(&mut self, source: Source) -> Result<OverlayEntry<'_, I>, io::Error> {
+		match source {
+			Source::Entry {
+				layer,
+				index,
+			} => match &mut self.layers[layer] {
+				Layer::Archive(archive) => archive.open(index).map(OverlayEntry::Entry).ok_or_else(|| io::Error::from(io::ErrorKind::NotFound)),
+				Layer::Folder(_) => Err(io::Error::from(io::ErrorKind::NotFound)),
+			},
+			Source::File {
+				path,
+				..
+			} => File::open(path).map(OverlayEntry::File),
+		}
+	}
+}
+
+impl<I> Read for OverlayEntry<'_, I>
+where
+	I: Read + Seek,
+{
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		match self {
+			Self::Entry(entry) => entry.read(buf),
+			Self::File(file) => file.read(buf),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		fs,
+		io::{Cursor, Read},
+		path::PathBuf,
+	};
+
+	use crate::{
+		read::{Archive, Entry, Reader, V2Reader},
+		write::V2Writer,
+	};
+
+	use super::{Overlay, Source};
+
+	fn copy_fixtures(name: &str) -> PathBuf {
+		let folder = std::env::temp_dir().join(format!("gta-img-overlay-{}-{}", name, std::process::id()));
+
+		fs::create_dir_all(&folder).expect("failed to create folder");
+
+		for file in ["virgo.dff", "v2.img"] {
+			fs::copy(PathBuf::from("test").join(file), folder.join(file)).expect("failed to copy fixture");
+		}
+
+		folder
+	}
+
+	#[test]
+	fn test_overlay() {
+		let folder = copy_fixtures("stack");
+
+		let mut base = Cursor::new(vec![1; 4096]);
+		let mut patch = Cursor::new(vec![2; 2048]);
+
+		let mut overlay = Overlay::new(Archive::from_entries(
+			&mut base,
+			vec![Entry::new("VIRGO.DFF", 0, 1).expect("failed to create entry"), Entry::new("LANDSTAL.DFF", 1, 1).expect("failed to create entry")],
+		));
+
+		overlay.push_folder(&folder).expect("failed to push folder");
+		overlay.push_archive(Archive::from_entries(&mut patch, vec![Entry::new("landstal.dff", 0, 1).expect("failed to create entry")]));
+
+		assert_eq!(
+			overlay.resolve("LANDSTAL.DFF"),
+			Some(Source::Entry {
+				layer: 2,
+				index: 0,
+			})
+		);
+		assert!(matches!(
+			overlay.resolve("VIRGO.DFF"),
+			Some(Source::File {
+				layer: 1,
+				..
+			})
+		));
+		assert_eq!(overlay.entries().iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), ["VIRGO.DFF", "LANDSTAL.DFF", "v2.img"]);
+
+		let mut data = Vec::new();
+
+		overlay.open("virgo.dff").expect("failed to open entry").expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert_eq!(data, include_bytes!("../test/virgo.dff"));
+
+		// Flatten the stack into a single archive.
+
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 3).expect("failed to create writer");
+
+		assert_eq!(overlay.flatten(&mut writer).expect("failed to flatten overlay"), 3);
+
+		writer.finish().expect("failed to finish archive");
+
+		img.set_position(0);
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let mut data = Vec::new();
+
+		archive.open(1).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert!(data.iter().all(|&b| b == 2));
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+}
@@ -0,0 +1,124 @@
+use std::{
+	fs::{self, File},
+	io,
+	path::{Path, PathBuf},
+};
+
+use crate::{error::PathError, ENTRY_SIZE};
+
+/// Represents the paths of the `img` file and the accompanying `dir` file of a V1-styled archive.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct V1Paths {
+	/// The path of the `img` file.
+	pub img: PathBuf,
+
+	/// The path of the `dir` file.
+	pub dir: PathBuf,
+}
+
+impl V1Paths {
+	/// Creates a new pair of paths from the specified `img` path and `dir` path, validating that they form a plausible pair.
+	/// Both files must exist, share the same stem (case-insensitively), and the size of the `dir` file must be a multiple of 32 bytes.
+	pub fn new<P, Q>(img: P, dir: Q) -> Result<Self, PathError>
+	where
+		P: Into<PathBuf>,
+		Q: Into<PathBuf>,
+	{
+		let paths = Self {
+			img: img.into(),
+			dir: dir.into(),
+		};
+
+		paths.validate()?;
+
+		Ok(paths)
+	}
+
+	/// Creates a new pair of paths from the specified `img` path, deriving the `dir` path by replacing the extension (preserving its case).
+	/// The pair is then validated as per [`V1Paths::new`].
+	pub fn from_img<P>(img: P) -> Result<Self, PathError>
+	where
+		P: Into<PathBuf>,
+	{
+		let img = img.into();
+		let dir = to_dir(&img);
+
+		Self::new(img, dir)
+	}
+
+	/// Opens both files for reading, returning the `dir` file and the `img` file (in the order expected by [`crate::read::V1Reader::new`]).
+	pub fn open(&self) -> Result<(File, File), io::Error> {
+		Ok((File::open(&self.dir)?, File::open(&self.img)?))
+	}
+
+	fn validate(&self) -> Result<(), PathError> {
+		// Check that both of the files exist.
+
+		for path in [&self.img, &self.dir] {
+			if !path.is_file() {
+				return Err(PathError::Missing(path.clone()));
+			}
+		}
+
+		// Check that both files share the same stem, as they would when accompanying one another.
+
+		let stem = |path: &Path| path.file_stem().map(|stem| stem.to_string_lossy().to_ascii_lowercase());
+
+		if stem(&self.img) != stem(&self.dir) {
+			return Err(PathError::MismatchedStems {
+				img: self.img.clone(),
+				dir: self.dir.clone(),
+			});
+		}
+
+		// Check that the directory is made up of whole entries.
+
+		let size = fs::metadata(&self.dir)?.len();
+
+		if size % ENTRY_SIZE != 0 {
+			return Err(PathError::InvalidDirectorySize(size));
+		}
+
+		Ok(())
+	}
+}
+
+fn to_dir(img: &Path) -> PathBuf {
+	let uppercase = img.extension().is_some_and(|extension| extension.to_string_lossy().chars().any(|c| c.is_ascii_uppercase()));
+
+	match uppercase {
+		true => img.with_extension("DIR"),
+		false => img.with_extension("dir"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use crate::error::PathError;
+
+	use super::{to_dir, V1Paths};
+
+	#[test]
+	fn test_to_dir() {
+		assert_eq!(to_dir(Path::new("models/gta3.img")), Path::new("models/gta3.dir"));
+		assert_eq!(to_dir(Path::new("MODELS/GTA3.IMG")), Path::new("MODELS/GTA3.DIR"));
+		assert_eq!(to_dir(Path::new("gta3")), Path::new("gta3.dir"));
+	}
+
+	#[test]
+	fn test_from_img() {
+		let paths = V1Paths::from_img("test/v1.img").expect("failed to validate paths");
+
+		assert_eq!(paths.dir, Path::new("test/v1.dir"));
+		assert!(paths.open().is_ok());
+	}
+
+	#[test]
+	fn test_new_invalid() {
+		assert!(matches!(V1Paths::new("test/v1.img", "test/v3.dir"), Err(PathError::Missing(_))));
+		assert!(matches!(V1Paths::new("test/v2.img", "test/v1.dir"), Err(PathError::MismatchedStems { .. })));
+		assert!(matches!(V1Paths::new("test/virgo.dff", "test/virgo.dff"), Err(PathError::InvalidDirectorySize(9))));
+	}
+}
@@ -0,0 +1,363 @@
+use std::{
+	collections::HashSet,
+	fs::{self, File},
+	io::{self, Read, Seek},
+	path::{Path, PathBuf},
+};
+
+#[cfg(feature = "parallel")]
+use crate::read::ReadAt;
+use crate::{error::WriteError, read::Archive, write::Writer, RESERVED_CHARACTERS};
+
+/// Represents the names of devices reserved by Windows, which cannot be used as file names regardless of their extension.
+const RESERVED_NAMES: [&str; 22] = [
+	"CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5",
+	"LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Represents the character substituted for characters which cannot appear within file names.
+const REPLACEMENT: char = '_';
+
+/// Represents the separator between a file name and the counter distinguishing it from other file names differing only by case.
+const COUNTER_SEPARATOR: char = '~';
+
+/// Represents the mapping between the name of an entry and the name of the file it is extracted to, or packed from.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct FileName {
+	/// The name of the entry.
+	pub name: String,
+
+	/// The name of the file (or, when extracted as folders, its path relative to the directory).
+	pub file: String,
+}
+
+/// Represents the outcome of extracting a single entry to a file.
+#[derive(Debug)]
+pub struct Extracted {
+	/// The index of the entry.
+	pub index: usize,
+
+	/// The mapping between the name of the entry and the name of the file.
+	pub name: FileName,
+
+	/// The number of bytes written to the file, or the error encountered extracting the entry.
+	pub result: Result<u64, io::Error>,
+}
+
+/// Converts the name of an entry into a file name which is valid on Windows, Linux and macOS alike.
+/// Control and reserved characters are replaced, as are any trailing dots or spaces, and names reserved by Windows (e.g. `CON` or `aux.txd`) are prefixed.
+pub fn to_file_name(name: &str) -> String {
+	let mut file: String = name.chars().map(|c| if c.is_control() || RESERVED_CHARACTERS.contains(&c) { REPLACEMENT } else { c }).collect();
+
+	// Replace any trailing dots or spaces, which are silently stripped by Windows.
+
+	let trimmed = file.trim_end_matches(['.', ' ']).len();
+
+	file.replace_range(trimmed.., &REPLACEMENT.to_string().repeat(file.len() - trimmed));
+
+	let stem = file.split('.').next().unwrap_or_default().trim_end();
+
+	if file.is_empty() || RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+		file.insert(0, REPLACEMENT);
+	}
+
+	file
+}
+
+/// Maps the names of entries to portable file names (as per [`to_file_name`]), which are unique even on case-insensitive filesystems.
+/// File names differing only by case from an earlier file name are distinguished by a counter preceding the extension (e.g. `virgo~1.dff`).
+pub fn to_file_names<'a, N>(names: N) -> Vec<FileName>
+where
+	N: IntoIterator<Item = &'a str>,
+{
+	let mut taken = HashSet::new();
+
+	names
+		.into_iter()
+		.map(|name| {
+			let file = to_file_name(name);
+			let (stem, extension) = match file.rfind('.').filter(|&position| position > 0) {
+				Some(position) => file.split_at(position),
+				None => (file.as_str(), ""),
+			};
+
+			let mut unique = file.clone();
+			let mut counter = 0;
+
+			while !taken.insert(unique.to_lowercase()) {
+				counter += 1;
+				unique = format!("{}{}{}{}", stem, COUNTER_SEPARATOR, counter, extension);
+			}
+
+			FileName {
+				name: name.to_owned(),
+				file: unique,
+			}
+		})
+		.collect()
+}
+
+/// Returns the path in a form which is not limited in length on Windows (i.e. with the `\\?\` prefix), or the path unchanged on other platforms.
+pub fn to_long_path(path: &Path) -> PathBuf {
+	#[cfg(windows)]
+	if let Ok(path) = path.canonicalize() {
+		return path;
+	}
+
+	path.to_path_buf()
+}
+
+impl<I> Archive<'_, I>
+where
+	I: Read + Seek,
+{
+	/// Extracts each of the entries of the archive to files within the `dir` directory (which is created if necessary), named as per [`to_file_names`].
+	/// Names are sanitised such that no entry can be extracted outside of the directory (e.g. via separators or `..`).
+	/// Extraction continues past any entry which fails to extract, returning the outcome for each of the entries.
+	pub fn extract_all<P>(&mut self, dir: P) -> Result<Vec<Extracted>, io::Error>
+	where
+		P: AsRef<Path>,
+	{
+		let names = to_file_names(self.iter().map(|entry| entry.name.as_str()));
+
+		self.extract_named(dir.as_ref(), names)
+	}
+
+	/// Extracts each of the entries of the archive to the files within the `dir` directory named by the specified mapping (in the order of the entries), creating any folders necessary.
+	pub(crate) fn extract_named(&mut self, dir: &Path, names: Vec<FileName>) -> Result<Vec<Extracted>, io::Error> {
+		fs::create_dir_all(dir)?;
+
+		let dir = to_long_path(dir);
+
+		let extracted = names
+			.into_iter()
+			.enumerate()
+			.map(|(index, name)| {
+				let path = dir.join(&name.file);
+				let result = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| extract_entry(self.open(index), &path));
+
+				Extracted {
+					index,
+					name,
+					result,
+				}
+			})
+			.collect();
+
+		Ok(extracted)
+	}
+}
+
+#[cfg(feature = "parallel")]
+impl<I> Archive<'_, I>
+where
+	I: ReadAt + Sync,
+{
+	/// Extracts each of the entries of the archive to files within the `dir` directory in parallel, as per [`Archive::extract_all`].
+	/// Entries are read using positioned reads across the global thread pool of `rayon`, with the outcomes returned in the order of the entries.
+	pub fn extract_all_parallel<P>(&self, dir: P) -> Result<Vec<Extracted>, io::Error>
+	where
+		P: AsRef<Path>,
+	{
+		use rayon::prelude::*;
+
+		fs::create_dir_all(dir.as_ref())?;
+
+		let dir = to_long_path(dir.as_ref());
+		let names = to_file_names(self.iter().map(|entry| entry.name.as_str()));
+
+		let extracted = names
+			.into_par_iter()
+			.enumerate()
+			.map(|(index, name)| {
+				let result = extract_entry(self.open_shared(index), &dir.join(&name.file));
+
+				Extracted {
+					index,
+					name,
+					result,
+				}
+			})
+			.collect();
+
+		Ok(extracted)
+	}
+}
+
+pub(crate) fn extract_entry<R>(open: Option<R>, path: &Path) -> Result<u64, io::Error>
+where
+	R: Read,
+{
+	match open {
+		Some(mut open) => io::copy(&mut open, &mut File::create(path)?),
+		None => Err(io::Error::from(io::ErrorKind::NotFound)),
+	}
+}
+
+/// Packs each of the files directly within the `folder` directory using the specified writer.
+/// Files present within the specified mapping (as returned by [`Archive::extract_all`]) are packed first, in the order of the mapping and under the original names of the entries, such that extracting and packing an archive preserves the order of its entries.
+/// Any other files are then packed under their file names, in order of their names.
+/// Returns the mapping between the names of the entries and the names of the files.
+pub fn pack_dir<P, W>(folder: P, names: &[FileName], writer: &mut W) -> Result<Vec<FileName>, WriteError>
+where
+	P: AsRef<Path>,
+	W: Writer,
+{
+	let mut files = Vec::new();
+
+	for file in fs::read_dir(to_long_path(folder.as_ref()))? {
+		let file = file?;
+
+		if file.file_type()?.is_file() {
+			files.push((file.file_name().to_string_lossy().into_owned(), file.path()));
+		}
+	}
+
+	// Sort the files, as the order of directory listings is unspecified.
+
+	files.sort();
+
+	// Order the mapped files as per the mapping, followed by the unmapped files.
+
+	let mut ordered = Vec::with_capacity(files.len());
+
+	for name in names {
+		if let Some(position) = files.iter().position(|(file, _)| *file == name.file) {
+			ordered.push((name.name.clone(), files.remove(position)));
+		}
+	}
+
+	ordered.extend(files.into_iter().map(|(file, path)| (file.clone(), (file, path))));
+
+	let mut packed = Vec::with_capacity(ordered.len());
+
+	for (name, (file, path)) in ordered {
+		writer.write(&name, &mut File::open(path)?)?;
+
+		packed.push(FileName {
+			name,
+			file,
+		});
+	}
+
+	Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{fs, io::Cursor};
+
+	use crate::{
+		read::{Archive, Entry, Reader, V2Reader},
+		write::V2Writer,
+	};
+
+	use super::{pack_dir, to_file_name, to_file_names, FileName};
+
+	#[test]
+	fn test_to_file_name() {
+		assert_eq!(to_file_name("VIRGO.DFF"), "VIRGO.DFF");
+		assert_eq!(to_file_name("CON"), "_CON");
+		assert_eq!(to_file_name("aux.txd"), "_aux.txd");
+		assert_eq!(to_file_name("console.txd"), "console.txd");
+		assert_eq!(to_file_name("VIRGO. "), "VIRGO__");
+		assert_eq!(to_file_name("a:b?c"), "a_b_c");
+		assert_eq!(to_file_name(""), "_");
+	}
+
+	#[test]
+	fn test_to_file_names() {
+		let names = to_file_names(["virgo.dff", "VIRGO.DFF", "Virgo.dff", "landstal"]);
+		let files: Vec<&str> = names.iter().map(|name| name.file.as_str()).collect();
+
+		assert_eq!(files, ["virgo.dff", "VIRGO~1.DFF", "Virgo~2.dff", "landstal"]);
+	}
+
+	#[test]
+	fn test_extract_all() {
+		let mut data = Cursor::new(vec![1; 6144]);
+		let mut archive = Archive::from_entries(
+			&mut data,
+			vec![
+				Entry::new("../../VIRGO.DFF", 0, 1).expect("failed to create entry"),
+				Entry::new("..", 1, 1).expect("failed to create entry"),
+				Entry::new("LANDSTAL.DFF", 2, 1).expect("failed to create entry"),
+			],
+		);
+
+		let folder = std::env::temp_dir().join(format!("gta-img-portable-all-{}", std::process::id())).join("nested");
+		let extracted = archive.extract_all(&folder).expect("failed to extract archive");
+
+		let files: Vec<&str> = extracted.iter().map(|extracted| extracted.name.file.as_str()).collect();
+
+		assert_eq!(files, [".._.._VIRGO.DFF", "__", "LANDSTAL.DFF"]);
+		assert!(extracted.iter().all(|extracted| extracted.result.as_ref().is_ok_and(|&len| len == 2048)));
+
+		assert_eq!(fs::read_dir(&folder).expect("failed to list folder").count(), 3);
+
+		fs::remove_dir_all(folder.parent().expect("expected parent")).expect("failed to remove folder");
+	}
+
+	#[test]
+	#[cfg(feature = "parallel")]
+	fn test_extract_all_parallel() {
+		let mut data = Cursor::new((0..8).flat_map(|sector| [sector; 2048]).collect::<Vec<u8>>());
+		let archive = Archive::from_entries(
+			&mut data,
+			(0..8).map(|index| Entry::new(&format!("{}.DFF", index), index, 1).expect("failed to create entry")).collect(),
+		);
+
+		let folder = std::env::temp_dir().join(format!("gta-img-portable-parallel-{}", std::process::id()));
+		let extracted = archive.extract_all_parallel(&folder).expect("failed to extract archive");
+
+		for (index, extracted) in extracted.iter().enumerate() {
+			assert_eq!(extracted.index, index);
+			assert_eq!(fs::read(folder.join(&extracted.name.file)).expect("failed to read file"), [index as u8; 2048]);
+		}
+
+		fs::remove_dir_all(&folder).expect("failed to remove folder");
+	}
+
+	#[test]
+	fn test_extract_pack() {
+		let mut data = Cursor::new(vec![1; 6144]);
+		let mut archive = Archive::from_entries(
+			&mut data,
+			vec![
+				Entry::new("CON.DFF", 0, 1).expect("failed to create entry"),
+				Entry::new("a.txd", 1, 1).expect("failed to create entry"),
+				Entry::new("A.TXD", 2, 1).expect("failed to create entry"),
+			],
+		);
+
+		let folder = std::env::temp_dir().join(format!("gta-img-portable-{}", std::process::id()));
+
+		fs::create_dir_all(&folder).expect("failed to create folder");
+
+		// Extract the entries, then pack them again under their original names.
+
+		let names: Vec<FileName> =
+			archive.extract_all(&folder).expect("failed to extract archive").into_iter().map(|extracted| extracted.name).collect();
+
+		assert_eq!(names[0].file, "_CON.DFF");
+
+		fs::write(folder.join("EXTRA.COL"), [2; 2048]).expect("failed to write file");
+
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 4).expect("failed to create writer");
+
+		let packed = pack_dir(&folder, &names, &mut writer).expect("failed to pack folder");
+
+		writer.finish().expect("failed to finish archive");
+		fs::remove_dir_all(&folder).expect("failed to remove folder");
+
+		assert_eq!(packed.len(), 4);
+
+		img.set_position(0);
+
+		let repacked: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let names: Vec<&str> = repacked.iter().map(|entry| entry.name.as_str()).collect();
+
+		assert_eq!(names, ["CON.DFF", "a.txd", "A.TXD", "EXTRA.COL"]);
+	}
+}
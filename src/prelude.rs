@@ -0,0 +1,5 @@
+pub use crate::{
+	error::{ReadError, WriteError},
+	read::{Archive, Entry, OpenEntry, OwnedArchive, ReadSeek, Reader, V1Reader, V2Reader},
+	write::{V1Writer, V2Writer, Writer},
+};
@@ -1,35 +1,240 @@
 use std::{
 	cmp,
+	collections::HashMap,
+	fmt,
 	hash::{self, Hash},
-	io::{self, Read, Seek},
+	mem,
+	ops::{Deref, DerefMut, Range},
+	io::{self, Read, Seek, SeekFrom},
+	path::PathBuf,
+	slice, vec,
 };
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
-use crate::{error::ReadError, NAME_SIZE, NULL_TERMINATOR, SECTOR_SIZE, VERSION_2_HEADER};
+use crate::{
+	error::ReadError,
+	event::{Event, Handle, Observer},
+	game::{GameProfile, Version, Violation},
+	limit::{Exceeded, Limit},
+	collision::COL_FOURCCS,
+	renderware::{RwVersion, CHUNK_CLUMP, CHUNK_TEXTURE_DICTIONARY},
+	validate::{find_gaps, read_data},
+	ENTRY_SIZE, NAME_SIZE, NULL_TERMINATOR, RESERVED_CHARACTERS, SECTOR_SIZE, VERSION_2_HEADER, VERSION_2_HEADER_ENTRY_OFFSET,
+};
 
 /// Represents the length of the name of an entry with the null terminator.
 const NAME_SIZE_NULL_TERMINATOR: usize = NAME_SIZE + 1;
 
+/// Represents the maximum number of entries allocated for up-front, regardless of the number declared by the archive.
+const PREALLOCATED_ENTRIES: usize = 4096;
+
+/// Represents the maximum number of bytes allocated for up-front when reading an entry, regardless of the length declared by the entry.
+const PREALLOCATED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Represents the character substituted for characters outside of printable ASCII under [`NamePolicy::Replace`].
+const NAME_REPLACEMENT: char = '?';
+
+/// Represents the characters of the bytes from `0x80` to `0x9F` in Windows-1252, with undefined bytes interpreted as the equivalent Unicode code point.
+const WINDOWS_1252: [char; 32] = [
+	'\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}', '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+	'\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+/// Represents the number of leading bytes of an entry inspected when detecting its kind.
+const KIND_MAGIC_SIZE: u64 = 4;
+
+/// Represents the magic numbers of each version of animation packages.
+const IFP_MAGICS: [&[u8; 4]; 2] = [b"ANPK", b"ANP3"];
+
+/// Represents the magic number of binary item placement files.
+const IPL_MAGIC: &[u8; 4] = b"bnry";
+
 /// Represents an archive.
-#[derive(Debug)]
 pub struct Archive<'a, R> {
 	inner: &'a mut R,
 
 	entries: Vec<Entry>,
+	names: Option<HashMap<String, usize>>,
+
+	declared: Option<usize>,
+	trailing: Option<u64>,
+
+	unclaimed: Vec<Unclaimed>,
+
+	warnings: Vec<Warning>,
+
+	observer: Option<Handle>,
+}
+
+/// Represents an archive which owns its reader, rather than borrowing it, such that it can be stored or returned freely.
+/// The full functionality of [`Archive`] is available by borrowing it via [`OwnedArchive::archive`].
+/// Where the reader supports positioned reads (e.g. [`std::fs::File`]), the archive is `Send + Sync` and its entries may be read from several threads at once via [`OwnedArchive::open_shared`].
+pub struct OwnedArchive<R> {
+	inner: R,
+
+	parts: Parts,
+}
+
+/// Represents the directory of an archive detached from its reader, such that it can be stored (e.g. cached by a mod manager) without holding the reader open.
+/// Indices are cheap to clone and compare, and can be bound to a reader once more via [`ArchiveIndex::bind`]. Warnings and unclaimed regions are not retained.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct ArchiveIndex {
+	entries: Vec<Entry>,
+
+	declared: Option<usize>,
+	trailing: Option<u64>,
+}
+
+/// Represents an owned archive borrowed as an [`Archive`], which returns any changes to the owned archive when dropped.
+pub struct ArchiveMut<'a, R> {
+	parts: &'a mut Parts,
+
+	archive: Archive<'a, R>,
+}
+
+/// Represents an archive mapped into memory, which serves the data of entries as slices of the map rather than reading them.
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct MmapArchive {
+	archive: OwnedArchive<io::Cursor<memmap2::Mmap>>,
+}
+
+/// Represents the state of an archive, other than its reader.
+#[derive(Clone, Default)]
+struct Parts {
+	entries: Vec<Entry>,
+	names: Option<HashMap<String, usize>>,
+
+	declared: Option<usize>,
+	trailing: Option<u64>,
+
+	unclaimed: Vec<Unclaimed>,
+
+	warnings: Vec<Warning>,
+
+	observer: Option<Handle>,
 }
 
 /// Represents an entry.
-#[derive(Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+/// Entries are ordered by their offset, then by their name, and finally by their length (and any other properties), such that sorting entries arranges them as they appear within the archive.
+/// Entries are compared, hashed and ordered only by their decoded properties, such that neither their [`Entry::raw_name`] nor their [`Entry::attributes`] affect their identity.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Entry {
 	/// The name of the entry, up to 23 characters.
 	pub name: String,
 
-	/// The offset, in sectors, of the entry.
+	/// The offset, in sectors, of the entry (see also [`Entry::offset_bytes`]).
 	pub offset: u64,
 
-	/// The length, in sectors, of the entry.
+	/// The length, in sectors, of the entry (see also [`Entry::len_bytes`]).
 	pub length: u64,
+
+	/// The length, in sectors, of the entry as recorded within the second length field of entries in the header of a V2-styled archive, if non-zero.
+	/// The field is unused by the games themselves, but is populated by some tools, and is preserved when the entry is rewritten by [`crate::write::Writer::write_entry`].
+	pub stream_len: Option<u64>,
+
+	/// The original bytes of the name of the entry as stored within the directory (including the null terminator and any bytes following it), if read from an archive.
+	/// Unlike [`Entry::name`], these are unaffected by the name policy of the reader, and are written in place of the name by [`crate::write::Writer::write_entry`] such that the name is preserved exactly.
+	/// They are cleared by [`Entry::set_name`], and should likewise be cleared should the name be assigned directly.
+	pub raw_name: Option<[u8; NAME_SIZE_NULL_TERMINATOR]>,
+
+	/// The additional metadata attached to the entry, which is not stored within the archive itself.
+	pub attributes: Attributes,
+}
+
+/// Represents additional metadata attached to an entry by the various analysis features.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub struct Attributes {
+	/// The kind of the data of the entry, if detected (see [`Archive::kind`]).
+	pub kind: Option<Kind>,
+
+	/// The CRC32 checksum of the data of the entry (including any padding), if calculated.
+	pub crc32: Option<u32>,
+
+	/// The path of the `img` file the entry was read from, if known (see [`crate::workspace::Workspace::entries`]).
+	pub source: Option<PathBuf>,
+
+	/// The version of the RenderWare library used to produce the data of the entry, if a model or texture dictionary and read.
+	pub rw_version: Option<RwVersion>,
+}
+
+/// Represents what was learned about the container of an archive whilst reading it, for diagnostics.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Metadata {
+	/// The version of the archive.
+	pub version: Version,
+
+	/// The number of entries declared within the header, for V2-styled archives.
+	pub declared: Option<usize>,
+
+	/// The number of entries read from the directory.
+	pub entries: usize,
+
+	/// The size of the directory (i.e. the header of V2-styled archives, or the `dir` file of V1-styled archives), in whole sectors.
+	pub directory_sectors: u64,
+
+	/// The first sector containing the data of any entry, if any entry contains data.
+	pub first_data_sector: Option<u64>,
+
+	/// The offset, in bytes, within the directory of any unexpected data following the entries.
+	pub trailing: Option<u64>,
+}
+
+/// Represents statistics summarising the entries of an archive, such as their total size and the space wasted between them.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Stats {
+	/// The number of entries.
+	pub entries: usize,
+
+	/// The total size of the entries, in bytes.
+	pub bytes: u64,
+
+	/// The total size of the entries, in sectors.
+	pub sectors: u64,
+
+	/// The number of sectors between the directory and the end of the furthest entry which are not occupied by any entry.
+	pub wasted_sectors: u64,
+
+	/// The index of the largest entry, if any (the first, if several are equally large).
+	pub largest: Option<usize>,
+
+	/// The index of the smallest entry, if any (the first, if several are equally small).
+	pub smallest: Option<usize>,
+}
+
+/// Represents a region of an archive not claimed by any entry which contains data (other than zeroes), such as data hidden between entries.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Unclaimed {
+	/// The offset, in bytes, of the region.
+	pub offset: u64,
+
+	/// The data within the region.
+	pub data: Vec<u8>,
+}
+
+/// Represents the kind of the data of an entry, as detected from its leading bytes.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Kind {
+	/// Indicates a RenderWare model (`dff`).
+	Dff,
+
+	/// Indicates a RenderWare texture dictionary (`txd`).
+	Txd,
+
+	/// Indicates a collision file (`col`), of any version.
+	Col,
+
+	/// Indicates an animation package (`ifp`), of any version.
+	Ifp,
+
+	/// Indicates a binary item placement file (`ipl`).
+	Ipl,
+
+	/// Indicates data of an unknown kind.
+	Unknown,
 }
 
 /// Represents an entry opened for reading.
@@ -40,21 +245,57 @@ where
 {
 	inner: &'a mut R,
 
+	index: usize,
+
+	offset: u64,
+	length: u64,
+
+	position: u64,
+
+	observer: Option<Handle>,
+}
+
+/// Represents an entry opened for reading from a source supporting positioned reads, any number of which may be open at once.
+#[derive(Debug)]
+pub struct SharedEntry<'a, R>
+where
+	R: ReadAt + ?Sized,
+{
+	inner: &'a R,
+
+	index: usize,
+
 	offset: u64,
 	length: u64,
 
 	position: u64,
+
+	observer: Option<Handle>,
+}
+
+/// Represents a source supporting positioned reads presented as a stream, such that archives can be read from any source (e.g. a remote file).
+/// The position of the stream is tracked by the stream itself, and the source is only ever read from using positioned reads.
+#[derive(Debug)]
+pub struct Positioned<R> {
+	inner: R,
+
+	length: u64,
+	position: u64,
 }
 
 /// Represents a reader of V1-styled archives, from both an `img` file and a `dir` file.
 #[derive(Debug)]
 pub struct V1Reader<'a, 'b, D, I>
 where
-	D: Read,
+	D: Read + ?Sized,
 	I: Read + Seek,
 {
 	dir: &'b mut D,
 	img: &'a mut I,
+
+	options: ReadOptions,
+
+	observer: Option<Handle>,
 }
 
 /// Represents a reader of V2-styled archives, from a single `img` file.
@@ -64,6 +305,230 @@ where
 	I: Read + Seek,
 {
 	img: &'a mut I,
+
+	options: ReadOptions,
+
+	observer: Option<Handle>,
+}
+
+/// Represents an iterator over the entries of a V1-styled archive, reading each from the `dir` file on demand.
+/// Yields an error (after which iteration stops) should an entry fail to be read.
+#[derive(Debug)]
+pub struct V1Entries<'b, D>
+where
+	D: Read + ?Sized,
+{
+	dir: &'b mut D,
+
+	options: ReadOptions,
+
+	index: usize,
+	trailing: Option<u64>,
+
+	warnings: Vec<Warning>,
+
+	done: bool,
+}
+
+/// Represents an iterator over the entries of a V2-styled archive, reading each from the header on demand.
+/// Yields an error (after which iteration stops) should an entry fail to be read.
+#[derive(Debug)]
+pub struct V2Entries<'a, I>
+where
+	I: Read + Seek,
+{
+	img: &'a mut I,
+
+	options: ReadOptions,
+
+	index: usize,
+	count: usize,
+
+	warnings: Vec<Warning>,
+
+	done: bool,
+}
+
+/// Represents the options for reading archives.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct ReadOptions {
+	/// The policy for characters within the names of entries outside of printable ASCII.
+	pub name_policy: NamePolicy,
+
+	/// The encoding with which the names of entries are decoded, which is applied before the name policy.
+	pub name_encoding: NameEncoding,
+
+	/// The maximum number of entries permitted in the archive, if limited.
+	pub max_entries: Option<usize>,
+
+	/// The maximum size, in bytes, of the directory (either the `dir` file or the header of a V2-styled archive) permitted, if limited.
+	pub max_directory_size: Option<u64>,
+
+	/// The profile of the game the archive is intended for, against which any violations are raised as warnings, if any.
+	pub profile: Option<GameProfile>,
+
+	/// Whether to read any regions not claimed by any entry, retaining those which contain data (other than zeroes) such that they can be carried through rebuilds.
+	/// Regions are read a sector at a time, with only the spans of sectors containing data being retained.
+	pub unclaimed: bool,
+
+	/// The maximum total size, in bytes, of the unclaimed data retained, if limited.
+	pub max_unclaimed: Option<u64>,
+
+	/// Whether to skip any malformed entries within the directory (e.g. names rejected by the name policy), raising each as a warning rather than failing the read.
+	/// Any error reading the directory itself (e.g. a truncated header) also stops reading with a warning, retaining the entries read before it.
+	pub lenient: bool,
+
+	/// Whether to fail the read should the directory end part-way through an entry (with [`ReadError::TruncatedDirectory`]), rather than ending the directory before it.
+	/// Names are also read in full, rather than tolerating any short reads.
+	pub strict: bool,
+
+	/// The policy for entries extending beyond the end of the `img` file, which are checked once the directory has been read.
+	pub bounds: BoundsPolicy,
+}
+
+/// Represents the policy for entries extending beyond the end of the `img` file, a common symptom of a mismatched `dir` and `img` file.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq)]
+pub enum BoundsPolicy {
+	/// Does not check the entries against the length of the `img` file.
+	#[default]
+	Ignore,
+
+	/// Raises each entry extending beyond the end as a warning.
+	Warn,
+
+	/// Fails the read with [`ReadError::OutOfBounds`] upon the first entry extending beyond the end.
+	Reject,
+}
+
+/// Represents the policy for characters within the names of entries outside of printable ASCII.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq)]
+pub enum NamePolicy {
+	/// Rejects names containing such characters, failing the read.
+	Reject,
+
+	/// Replaces such characters with `?`.
+	Replace,
+
+	/// Passes such characters through unchanged, with each byte interpreted as the equivalent Unicode code point.
+	#[default]
+	PassThrough,
+}
+
+/// Represents the encoding of the names of entries, which only differ in their interpretation of bytes outside of ASCII.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq)]
+pub enum NameEncoding {
+	/// Interprets each byte as the equivalent Unicode code point (i.e. ISO-8859-1), such that names can be written back unchanged.
+	#[default]
+	Latin1,
+
+	/// Interprets only ASCII, replacing any other bytes with `?`.
+	Ascii,
+
+	/// Interprets the bytes as Windows-1252, as used by localised versions of the games and by many tools.
+	Windows1252,
+
+	/// Interprets the bytes as UTF-8, replacing any invalid sequences with `U+FFFD`.
+	Utf8Lossy,
+}
+
+/// Represents a non-fatal warning raised whilst reading an archive.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+	/// Indicates that the name of an entry was not null-terminated, and was truncated to the maximum length.
+	UnterminatedName {
+		/// The index of the entry.
+		index: usize,
+
+		/// The (truncated) name of the entry.
+		name: String,
+	},
+
+	/// Indicates that the name of an entry contains a control character or a reserved character.
+	InvalidCharacter {
+		/// The index of the entry.
+		index: usize,
+
+		/// The offending character.
+		character: char,
+	},
+
+	/// Indicates that an entry has a length of zero.
+	EmptyEntry {
+		/// The index of the entry.
+		index: usize,
+	},
+
+	/// Indicates that the directory contains unexpected data following the entries (e.g. an incomplete or undeclared entry).
+	TrailingData {
+		/// The offset, in bytes, of the data within the directory.
+		offset: u64,
+	},
+
+	/// Indicates that a region not claimed by any entry contains data (other than zeroes).
+	UnclaimedData {
+		/// The offset, in bytes, of the region.
+		offset: u64,
+
+		/// The length, in bytes, of the region.
+		length: u64,
+	},
+
+	/// Indicates that the archive violates the game profile of the read options.
+	ProfileViolation {
+		/// The violation.
+		violation: Violation,
+	},
+
+	/// Indicates that a malformed entry was skipped (or that reading stopped at it), as the read options are lenient.
+	SkippedEntry {
+		/// The index of the entry.
+		index: usize,
+
+		/// The reason the entry was skipped.
+		reason: String,
+	},
+
+	/// Indicates that the data of an entry extends beyond the end of the `img` file.
+	OutOfBounds {
+		/// The index of the entry.
+		index: usize,
+
+		/// The offset, in bytes, of the end of the entry.
+		end: u64,
+
+		/// The length, in bytes, of the `img` file.
+		len: u64,
+	},
+}
+
+/// Represents a source which can be both read and seeked, such that sources chosen at runtime can be named as a single trait object (e.g. `Box<dyn ReadSeek>` or `&mut dyn ReadSeek`).
+/// Implemented for every type implementing both [`Read`] and [`Seek`].
+pub trait ReadSeek: Read + Seek {}
+
+impl<T> ReadSeek for T where T: Read + Seek + ?Sized {}
+
+/// Represents a source supporting positioned reads, which read from an offset without requiring exclusive access to the source.
+pub trait ReadAt {
+	/// Reads from the source at the specified offset into `buf`, returning the number of bytes read (zero at the end of the source).
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+	/// Reads exactly enough bytes from the source at the specified offset to fill `buf`, failing if the end of the source is reached first.
+	fn read_exact_at(&self, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+		while !buf.is_empty() {
+			match self.read_at(buf, offset) {
+				Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+				Ok(read) => {
+					buf = &mut buf[read..];
+					offset += read as u64;
+				}
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+				Err(e) => return Err(e),
+			}
+		}
+
+		Ok(())
+	}
 }
 
 /// Represents a generic archive reader that can produce archives.
@@ -72,9 +537,131 @@ pub trait Reader<'a, R> {
 	fn read(self) -> Result<Archive<'a, R>, ReadError>;
 }
 
+impl ReadOptions {
+	/// Checks that an entry at the specified index, with the specified offset within the directory, would fall within any limits.
+	fn check_limits(&self, index: usize, record: u64) -> Result<(), ReadError> {
+		if let Some(max) = self.max_entries.filter(|&max| index >= max) {
+			return Err(ReadError::TooManyEntries {
+				count: index + 1,
+				max,
+			});
+		}
+
+		if let Some(max) = self.max_directory_size.filter(|&max| record + ENTRY_SIZE > max) {
+			return Err(ReadError::DirectoryTooLarge {
+				size: record + ENTRY_SIZE,
+				max,
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Creates an error for the entry at the specified index, with the specified offset within the directory, attributing any unexpected end of the directory to it being truncated when strict.
+	fn entry_error(&self, index: usize, offset: u64, error: io::Error) -> ReadError {
+		match self.strict && error.kind() == io::ErrorKind::UnexpectedEof {
+			true => ReadError::TruncatedDirectory {
+				index,
+				offset,
+			},
+			false => ReadError::entry(index, offset, error),
+		}
+	}
+
+	/// Checks the entries of an archive of the specified version against any game profile, raising any violations as warnings.
+	fn check_profile(&self, version: Version, entries: &[Entry], warnings: &mut Vec<Warning>) {
+		if let Some(profile) = &self.profile {
+			warnings.extend(profile.violations(version, entries.iter()).into_iter().map(|violation| Warning::ProfileViolation {
+				violation,
+			}));
+		}
+	}
+
+	/// Checks each of the entries against the length of the `img` file as per the bounds policy, either raising a warning or failing for any extending beyond it.
+	fn check_bounds<I>(&self, img: &mut I, entries: &[Entry], warnings: &mut Vec<Warning>) -> Result<(), ReadError>
+	where
+		I: Read + Seek,
+	{
+		if self.bounds == BoundsPolicy::Ignore {
+			return Ok(());
+		}
+
+		let len = img.seek(SeekFrom::End(0))?;
+
+		for (index, entry) in entries.iter().enumerate().filter(|(_, entry)| entry.end_bytes() > len) {
+			let end = entry.end_bytes();
+
+			if self.bounds == BoundsPolicy::Reject {
+				return Err(ReadError::OutOfBounds {
+					index,
+					name: entry.name.clone(),
+					end,
+					len,
+				});
+			}
+
+			warnings.push(Warning::OutOfBounds {
+				index,
+				end,
+				len,
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Reads the spans of any unclaimed regions of the archive containing data, if enabled, raising each as a warning.
+	/// Fails with [`ReadError::UnclaimedTooLarge`] should the data retained exceed the maximum permitted.
+	fn read_unclaimed<I>(&self, img: &mut I, entries: &[Entry], declared: Option<usize>, warnings: &mut Vec<Warning>) -> Result<Vec<Unclaimed>, ReadError>
+	where
+		I: Read + Seek,
+	{
+		let mut unclaimed: Vec<Unclaimed> = Vec::new();
+
+		if !self.unclaimed {
+			return Ok(unclaimed);
+		}
+
+		let len = img.seek(SeekFrom::End(0))?;
+		let mut size = 0;
+
+		for (offset, end) in find_gaps(entries, declared, len) {
+			read_data(img, offset, end, |position, data| {
+				size += data.len() as u64;
+
+				if let Some(max) = self.max_unclaimed.filter(|&max| size > max) {
+					return Err(ReadError::UnclaimedTooLarge {
+						size,
+						max,
+					});
+				}
+
+				// Join the data onto the previous span should it follow directly on from it.
+
+				match unclaimed.last_mut().filter(|span| span.offset + span.data.len() as u64 == position) {
+					Some(span) => span.data.extend_from_slice(data),
+					None => unclaimed.push(Unclaimed {
+						offset: position,
+						data: data.to_vec(),
+					}),
+				}
+
+				Ok(())
+			})?;
+		}
+
+		warnings.extend(unclaimed.iter().map(|span| Warning::UnclaimedData {
+			offset: span.offset,
+			length: span.data.len() as u64,
+		}));
+
+		Ok(unclaimed)
+	}
+}
+
 impl<'a, 'b, D, I> V1Reader<'a, 'b, D, I>
 where
-	D: Read,
+	D: Read + ?Sized,
 	I: Read + Seek,
 {
 	/// Creates a new V1-styled reader with the specified `dir` source and specified `img` source.
@@ -82,8 +669,30 @@ where
 		Self {
 			dir,
 			img,
+			options: ReadOptions::default(),
+			observer: None,
 		}
 	}
+
+	/// Sets the options for reading the archive.
+	pub fn with_options(mut self, options: ReadOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Sets the observer to be notified of any warnings raised whilst reading the archive, which is then retained by the archive itself.
+	pub fn with_observer<O>(mut self, observer: O) -> Self
+	where
+		O: Observer + 'static,
+	{
+		self.observer = Some(Handle::new(observer));
+		self
+	}
+
+	/// Consumes the reader, returning an iterator which reads each of the entries on demand, rather than reading them all up front.
+	pub fn entries(self) -> V1Entries<'b, D> {
+		V1Entries::new(self.dir, self.options)
+	}
 }
 
 impl<'a, I> V2Reader<'a, I>
@@ -94,62 +703,161 @@ where
 	pub fn new(img: &'a mut I) -> Self {
 		Self {
 			img,
+			options: ReadOptions::default(),
+			observer: None,
 		}
 	}
+
+	/// Sets the options for reading the archive.
+	pub fn with_options(mut self, options: ReadOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Sets the observer to be notified of any warnings raised whilst reading the archive, which is then retained by the archive itself.
+	pub fn with_observer<O>(mut self, observer: O) -> Self
+	where
+		O: Observer + 'static,
+	{
+		self.observer = Some(Handle::new(observer));
+		self
+	}
+
+	/// Consumes the reader, returning an iterator which reads each of the entries on demand, rather than reading them all up front.
+	/// Reads and checks the header immediately.
+	pub fn entries(self) -> Result<V2Entries<'a, I>, ReadError> {
+		V2Entries::new(self.img, self.options)
+	}
 }
 
-impl<'a, D, I> Reader<'a, I> for V1Reader<'a, '_, D, I>
+impl<'b, D> V1Entries<'b, D>
 where
-	D: Read,
-	I: Read + Seek,
+	D: Read + ?Sized,
 {
-	fn read(self) -> Result<Archive<'a, I>, ReadError> {
-		let mut entries: Vec<Entry> = Vec::new();
+	/// Creates a new iterator over the entries within the specified `dir` source.
+	pub fn new(dir: &'b mut D, options: ReadOptions) -> Self {
+		Self {
+			dir,
+			options,
+			index: 0,
+			trailing: None,
+			warnings: Vec::new(),
+			done: false,
+		}
+	}
 
-		loop {
-			// Attempt to read the offset for the next entry, however graciously handle an EOF.
-			// Return any other kind of errors as normal.
-
-			let offset = match self.dir.read_u32::<LittleEndian>() {
-				Ok(offset) => offset as u64,
-				Err(error) => match error.kind() {
-					io::ErrorKind::UnexpectedEof => break,
-					_ => return Err(error.into()),
-				},
-			};
+	/// Returns each of the non-fatal warnings raised whilst reading the entries so far.
+	pub fn warnings(&self) -> &[Warning] {
+		&self.warnings
+	}
 
-			// Read the properties of the entry.
+	fn read_entry(&mut self) -> Result<Option<Entry>, ReadError> {
+		let index = self.index;
+		let record = index as u64 * ENTRY_SIZE;
+
+		// Attempt to read the entirety of the next entry, however graciously handle an EOF.
+		// Stop at (and make note of) any incomplete entry, rather than parsing only half of one.
+
+		let mut buf = [0; ENTRY_SIZE as usize];
+
+		match read_fully(self.dir, &mut buf).map_err(|error| ReadError::entry(index, record, error))? {
+			0 => return Ok(None),
+			len if len < buf.len() && self.options.strict => {
+				return Err(ReadError::TruncatedDirectory {
+					index,
+					offset: record + len as u64,
+				});
+			}
+			len if len < buf.len() => {
+				self.trailing = Some(record);
+				self.warnings.push(Warning::TrailingData {
+					offset: record,
+				});
+
+				return Ok(None);
+			}
+			_ => (),
+		}
 
-			let length = self.dir.read_u32::<LittleEndian>()? as u64;
+		self.options.check_limits(index, record)?;
 
-			// Read the name as a null-terminated string.
+		let mut src = &buf[..];
 
-			let name = read_null_terminated(self.dir)?;
+		// Read the properties of the entry.
 
-			entries.push(Entry {
-				name,
-				offset,
-				length,
-			})
+		let offset = src.read_u32::<LittleEndian>().map_err(|error| ReadError::entry(index, record, error))? as u64;
+		let length = src.read_u32::<LittleEndian>().map_err(|error| ReadError::entry(index, record + 4, error))? as u64;
+
+		// Read the name as a null-terminated string.
+
+		let (name, raw_name) = read_null_terminated(&mut src, &self.options).map_err(|error| ReadError::entry(index, record + 8, error))?;
+		let name = apply_name_policy(name, self.options.name_policy).map_err(|name| ReadError::InvalidName {
+			index,
+			name,
+			offset: record + 8,
+		})?;
+
+		let entry = Entry {
+			name,
+			offset,
+			length,
+			stream_len: None,
+			raw_name: Some(raw_name),
+			attributes: Attributes::default(),
+		};
+
+		Limit::Offset.check(offset + length).map_err(|exceeded| ReadError::LimitExceeded {
+			index,
+			exceeded,
+		})?;
+
+		check_entry(index, &entry, &mut self.warnings);
+
+		Ok(Some(entry))
+	}
+}
+
+impl<D> Iterator for V1Entries<'_, D>
+where
+	D: Read + ?Sized,
+{
+	type Item = Result<Entry, ReadError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while !self.done {
+			// Stop at the end of the entries, or at the first error (unless lenient, skipping any malformed entries instead).
+
+			let entry = self.read_entry().transpose();
+
+			match entry {
+				Some(Ok(_)) => self.index += 1,
+				Some(Err(error)) if self.options.lenient => {
+					self.done = !skip_entry(self.index, error, &mut self.warnings);
+					self.index += 1;
+
+					continue;
+				}
+				_ => self.done = true,
+			}
+
+			return entry;
 		}
 
-		Ok(Archive {
-			inner: self.img,
-			entries,
-		})
+		None
 	}
 }
 
-impl<'a, I> Reader<'a, I> for V2Reader<'a, I>
+impl<'a, I> V2Entries<'a, I>
 where
 	I: Read + Seek,
 {
-	fn read(self) -> Result<Archive<'a, I>, ReadError> {
+	/// Creates a new iterator over the entries within the header of the specified `img` source, reading and checking the header itself immediately.
+	pub fn new(img: &'a mut I, options: ReadOptions) -> Result<Self, ReadError> {
 		// Read and check the header of the archive is in the expected format.
 
 		let mut header = [0; VERSION_2_HEADER.len()];
 
-		self.img.read_exact(&mut header)?;
+		img.read_exact(&mut header)?;
 
 		if header != VERSION_2_HEADER {
 			return Err(ReadError::InvalidHeader);
@@ -157,264 +865,2892 @@ where
 
 		// Read the (expected) number of entries in the archive.
 
-		let count = self.img.read_u32::<LittleEndian>()? as usize;
+		let count = img.read_u32::<LittleEndian>()? as usize;
 
-		let mut entries: Vec<Entry> = Vec::with_capacity(count);
+		// Check the number of entries against any limits before reading them.
 
-		for _ in 0..count {
-			// Read the properties of the entry.
-
-			let offset = self.img.read_u32::<LittleEndian>()? as u64;
-			let length = self.img.read_u16::<LittleEndian>()? as u64;
-			let _ = self.img.read_u16::<LittleEndian>()?; // Unused (always 0)
-
-			// Read the name as a null-terminated string.
-
-			let name = read_null_terminated(self.img)?;
-
-			entries.push(Entry {
-				name,
-				offset,
-				length,
-			})
+		if count > 0 {
+			options.check_limits(count - 1, VERSION_2_HEADER_ENTRY_OFFSET + (count as u64 - 1) * ENTRY_SIZE)?;
 		}
 
-		Ok(Archive {
-			inner: self.img,
-			entries,
+		Ok(Self {
+			img,
+			options,
+			index: 0,
+			count,
+			warnings: Vec::new(),
+			done: false,
 		})
 	}
-}
 
-impl<I> Archive<'_, I> {
-	/// Returns the number of entries in the archive.
-	pub fn len(&self) -> usize {
-		self.entries.len()
+	/// Returns the number of entries declared within the header.
+	pub fn declared(&self) -> usize {
+		self.count
 	}
 
-	/// Returns if the archive is void of any entries.
-	pub fn is_empty(&self) -> bool {
-		self.entries.is_empty()
+	/// Returns each of the non-fatal warnings raised whilst reading the entries so far.
+	pub fn warnings(&self) -> &[Warning] {
+		&self.warnings
 	}
 
-	/// Returns the entry at the specified index, if it exists.
-	pub fn get(&self, index: usize) -> Option<&Entry> {
-		self.entries.get(index)
-	}
+	fn read_entry(&mut self) -> Result<Entry, ReadError> {
+		let index = self.index;
+		let record = VERSION_2_HEADER_ENTRY_OFFSET + index as u64 * ENTRY_SIZE;
 
-	/// Returns an iterator over each of the entries in the archive.
-	pub fn iter(&self) -> impl Iterator<Item = &Entry> {
-		self.entries.iter()
-	}
-}
+		// Read the properties of the entry.
 
-impl<I> Archive<'_, I>
-where
-	I: Read + Seek,
-{
+		let offset = self.img.read_u32::<LittleEndian>().map_err(|error| self.options.entry_error(index, record, error))? as u64;
+		let length = self.img.read_u16::<LittleEndian>().map_err(|error| self.options.entry_error(index, record + 4, error))? as u64;
+		let stream_len = self.img.read_u16::<LittleEndian>().map_err(|error| self.options.entry_error(index, record + 6, error))?; // Length in archive (usually 0)
+
+		// Read the name as a null-terminated string.
+
+		let (name, raw_name) = read_null_terminated(self.img, &self.options).map_err(|error| self.options.entry_error(index, record + 8, error))?;
+		let name = apply_name_policy(name, self.options.name_policy).map_err(|name| ReadError::InvalidName {
+			index,
+			name,
+			offset: record + 8,
+		})?;
+
+		let entry = Entry {
+			name,
+			offset,
+			length,
+			stream_len: (stream_len != 0).then_some(stream_len as u64),
+			raw_name: Some(raw_name),
+			attributes: Attributes::default(),
+		};
+
+		Limit::Offset.check(offset + length).map_err(|exceeded| ReadError::LimitExceeded {
+			index,
+			exceeded,
+		})?;
+
+		check_entry(index, &entry, &mut self.warnings);
+
+		Ok(entry)
+	}
+}
+
+impl<I> Iterator for V2Entries<'_, I>
+where
+	I: Read + Seek,
+{
+	type Item = Result<Entry, ReadError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while !self.done && self.index < self.count {
+			// Stop at the end of the entries, or at the first error (unless lenient, skipping any malformed entries instead).
+
+			let entry = self.read_entry();
+
+			match entry {
+				Ok(_) => self.index += 1,
+				Err(error) if self.options.lenient => {
+					self.done = !skip_entry(self.index, error, &mut self.warnings);
+					self.index += 1;
+
+					continue;
+				}
+				Err(_) => self.done = true,
+			}
+
+			return Some(entry);
+		}
+
+		None
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self.done {
+			true => (0, Some(0)),
+			false => (0, Some(self.count - self.index)),
+		}
+	}
+}
+
+impl<'a, D, I> Reader<'a, I> for V1Reader<'a, '_, D, I>
+where
+	D: Read + ?Sized,
+	I: Read + Seek,
+{
+	fn read(self) -> Result<Archive<'a, I>, ReadError> {
+		let mut iter = V1Entries::new(self.dir, self.options.clone());
+		let entries = iter.by_ref().collect::<Result<Vec<Entry>, ReadError>>()?;
+
+		let trailing = iter.trailing;
+		let mut warnings = iter.warnings;
+
+		self.options.check_bounds(self.img, &entries, &mut warnings)?;
+		self.options.check_profile(Version::V1, &entries, &mut warnings);
+
+		let unclaimed = self.options.read_unclaimed(self.img, &entries, None, &mut warnings)?;
+
+		notify_warnings(&self.observer, &warnings);
+
+		Ok(Archive {
+			inner: self.img,
+			entries,
+			names: None,
+			declared: None,
+			trailing,
+			unclaimed,
+			warnings,
+			observer: self.observer,
+		})
+	}
+}
+
+impl<'a, I> Reader<'a, I> for V2Reader<'a, I>
+where
+	I: Read + Seek,
+{
+	fn read(self) -> Result<Archive<'a, I>, ReadError> {
+		let mut iter = V2Entries::new(&mut *self.img, self.options.clone())?;
+		let count = iter.count;
+
+		// Avoid trusting the declared number outright, however, as a malformed archive could otherwise exhaust memory.
+
+		let mut entries: Vec<Entry> = Vec::with_capacity(count.min(PREALLOCATED_ENTRIES));
+
+		for entry in iter.by_ref() {
+			entries.push(entry?);
+		}
+
+		let mut warnings = iter.warnings;
+
+		// Check if there appear to be further (undeclared) entries between the end of the header and the start of the data.
+
+		let end = VERSION_2_HEADER_ENTRY_OFFSET + count as u64 * ENTRY_SIZE;
+		let first = entries.iter().filter(|entry| entry.length > 0).map(|entry| entry.offset_bytes()).min();
+
+		let mut trailing = None;
+
+		if first.is_some_and(|first| end + ENTRY_SIZE <= first) {
+			let mut buf = [0; ENTRY_SIZE as usize];
+			let len = read_fully(self.img, &mut buf)?;
+
+			if buf[0..len].iter().any(|&b| b != 0) {
+				trailing = Some(end);
+				warnings.push(Warning::TrailingData {
+					offset: end,
+				});
+			}
+		}
+
+		self.options.check_bounds(self.img, &entries, &mut warnings)?;
+		self.options.check_profile(Version::V2, &entries, &mut warnings);
+
+		let unclaimed = self.options.read_unclaimed(self.img, &entries, Some(count), &mut warnings)?;
+
+		notify_warnings(&self.observer, &warnings);
+
+		Ok(Archive {
+			inner: self.img,
+			entries,
+			names: None,
+			declared: Some(count),
+			trailing,
+			unclaimed,
+			warnings,
+			observer: self.observer,
+		})
+	}
+}
+
+impl<'a, I> Archive<'a, I> {
+	/// Creates a new archive from the specified entries, with the data of each located within `inner`.
+	/// Primarily intended for implementations of [`Reader`] for other formats.
+	pub fn from_entries(inner: &'a mut I, entries: Vec<Entry>) -> Self {
+		Self {
+			inner,
+			entries,
+			names: None,
+			declared: None,
+			trailing: None,
+			unclaimed: Vec::new(),
+			warnings: Vec::new(),
+			observer: None,
+		}
+	}
+
+	/// Returns the number of entries in the archive.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns if the archive is void of any entries.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns the entry at the specified index, if it exists.
+	pub fn get(&self, index: usize) -> Option<&Entry> {
+		self.entries.get(index)
+	}
+
+	/// Returns the first entry with the specified name (case-insensitively), if it exists.
+	pub fn get_by_name(&self, name: &str) -> Option<&Entry> {
+		self.entries.get(self.position_of(name)?)
+	}
+
+	/// Returns the index of the first entry with the specified name (case-insensitively), if it exists.
+	/// Uses the index of names if one has been built via [`Archive::build_index`], or otherwise searches each of the entries in turn.
+	pub fn position_of(&self, name: &str) -> Option<usize> {
+		match &self.names {
+			Some(names) => names.get(&fold_name(name)).copied(),
+			None => self.entries.iter().position(|entry| entry.name.eq_ignore_ascii_case(name)),
+		}
+	}
+
+	/// Returns whether the archive contains an entry with the specified name (case-insensitively).
+	pub fn contains(&self, name: &str) -> bool {
+		self.position_of(name).is_some()
+	}
+
+	/// Builds an index of the names of the entries (case-insensitively), such that looking up entries by name takes constant rather than linear time.
+	/// Worthwhile for archives with many entries which are looked up repeatedly. The index is discarded whenever the entries are modified.
+	pub fn build_index(&mut self) {
+		self.names = Some(group_by_name(&self.entries).into_iter().map(|(name, indices)| (name, indices[0])).collect());
+	}
+
+	/// Returns whether an index of the names of the entries has been built.
+	pub fn is_indexed(&self) -> bool {
+		self.names.is_some()
+	}
+
+	/// Returns the indices of each group of entries sharing the same name (case-insensitively), of which the games only load one.
+	/// Groups are ordered by their first entry, with indices ordered as they appear within the directory.
+	pub fn duplicates(&self) -> Vec<Vec<usize>> {
+		group_by_name(&self.entries).into_iter().map(|(_, indices)| indices).filter(|indices| indices.len() > 1).collect()
+	}
+
+	/// Returns the indices of each of the entries with names matching the specified glob pattern (case-insensitively), in the order they appear within the directory.
+	/// Within the pattern, `*` matches any number of characters and `?` matches any single character (e.g. `*.txd` or `landsta?.*`).
+	pub fn find(&self, pattern: &str) -> Vec<usize> {
+		self.entries.iter().enumerate().filter(|(_, entry)| glob(pattern, &entry.name)).map(|(index, _)| index).collect()
+	}
+
+	/// Returns each of the entries in the archive, in the order they appear within the directory.
+	pub fn entries(&self) -> &[Entry] {
+		&self.entries
+	}
+
+	/// Returns an iterator over each of the entries in the archive.
+	pub fn iter(&self) -> slice::Iter<'_, Entry> {
+		self.entries.iter()
+	}
+
+	/// Returns an iterator over each of the entries in the archive, ordered by the specified comparator.
+	/// The sort is stable, such that entries which compare equal retain their order within the directory.
+	pub fn iter_sorted<F>(&self, compare: F) -> vec::IntoIter<&Entry>
+	where
+		F: FnMut(&&Entry, &&Entry) -> cmp::Ordering,
+	{
+		let mut entries: Vec<&Entry> = self.entries.iter().collect();
+
+		entries.sort_by(compare);
+		entries.into_iter()
+	}
+
+	/// Returns an iterator over each of the entries in the archive, ordered by their names (case-insensitively).
+	pub fn iter_sorted_by_name(&self) -> vec::IntoIter<&Entry> {
+		self.iter_sorted(|a, b| a.name.bytes().map(|byte| byte.to_ascii_lowercase()).cmp(b.name.bytes().map(|byte| byte.to_ascii_lowercase())))
+	}
+
+	/// Returns an iterator over each of the entries in the archive, ordered by their offsets (i.e. as they are laid out within the archive).
+	pub fn iter_sorted_by_offset(&self) -> vec::IntoIter<&Entry> {
+		self.iter_sorted(|a, b| a.offset.cmp(&b.offset))
+	}
+
+	/// Returns what was learned about the container of the archive whilst reading it (e.g. the version and the size of the directory).
+	pub fn metadata(&self) -> Metadata {
+		let directory = match self.declared {
+			Some(declared) => VERSION_2_HEADER_ENTRY_OFFSET + declared as u64 * ENTRY_SIZE,
+			None => self.entries.len() as u64 * ENTRY_SIZE,
+		};
+
+		Metadata {
+			version: Version::from_declared(self.declared),
+			declared: self.declared,
+			entries: self.entries.len(),
+			directory_sectors: directory.div_ceil(SECTOR_SIZE),
+			first_data_sector: self.entries.iter().filter(|entry| entry.length > 0).map(|entry| entry.offset).min(),
+			trailing: self.trailing,
+		}
+	}
+
+	/// Returns statistics summarising the entries of the archive, calculated from the directory alone.
+	pub fn stats(&self) -> Stats {
+		let end = self.entries.iter().map(Entry::end_bytes).max().unwrap_or_default();
+		let wasted = find_gaps(&self.entries, self.declared, end).into_iter().map(|(start, end)| (end - start) / SECTOR_SIZE).sum();
+
+		// Find the first of the largest and smallest entries (reversed, as `max_by_key` and `min_by_key` differ in which of several equal elements they return).
+
+		let largest = self.entries.iter().enumerate().rev().max_by_key(|(_, entry)| entry.length).map(|(index, _)| index);
+		let smallest = self.entries.iter().enumerate().min_by_key(|(_, entry)| entry.length).map(|(index, _)| index);
+
+		Stats {
+			entries: self.entries.len(),
+			bytes: self.size(),
+			sectors: self.entries.iter().map(|entry| entry.length).sum(),
+			wasted_sectors: wasted,
+			largest,
+			smallest,
+		}
+	}
+
+	/// Returns any unclaimed regions of the archive containing data, which are only read if enabled within the read options.
+	pub fn unclaimed(&self) -> &[Unclaimed] {
+		&self.unclaimed
+	}
+
+	/// Returns each of the non-fatal warnings raised whilst reading the archive.
+	pub fn warnings(&self) -> &[Warning] {
+		&self.warnings
+	}
+
+	/// Sets the observer to be notified of events raised when reading entries from the archive.
+	pub fn with_observer<O>(mut self, observer: O) -> Self
+	where
+		O: Observer + 'static,
+	{
+		self.observer = Some(Handle::new(observer));
+		self
+	}
+
+	/// Returns whether the archive has entries with the same names (case-insensitively) as another archive, in the same order.
+	pub fn order_eq<J>(&self, other: &Archive<J>) -> bool {
+		self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a.name.eq_ignore_ascii_case(&b.name))
+	}
+
+	/// Consumes the archive, returning each of the entries.
+	pub fn into_entries(self) -> Vec<Entry> {
+		self.entries
+	}
+
+	/// Consumes the archive, returning the underlying reader.
+	pub fn into_inner(self) -> &'a mut I {
+		self.inner
+	}
+
+	/// Returns the entries of the archive, for attaching attributes.
+	pub(crate) fn entries_mut(&mut self) -> &mut [Entry] {
+		// Discard the index of names, as any of the names may be changed.
+
+		self.names = None;
+
+		&mut self.entries
+	}
+
+	/// Returns the number of entries declared by the archive itself, if the version declares one.
+	pub(crate) fn declared(&self) -> Option<usize> {
+		self.declared
+	}
+
+	/// Returns the offset, in bytes, within the directory of any unexpected data following the entries, if any.
+	pub(crate) fn trailing(&self) -> Option<u64> {
+		self.trailing
+	}
+
+	/// Notifies the observer of the archive, if any, of the specified event.
+	pub(crate) fn notify(&self, event: &Event) {
+		if let Some(observer) = &self.observer {
+			observer.notify(event);
+		}
+	}
+}
+
+impl<I> Archive<'_, I>
+where
+	I: Read + Seek,
+{
 	/// Opens and returns the entry at the specified index for reading, if it exists.
-	pub fn open(&mut self, index: usize) -> Option<OpenEntry<I>> {
+	pub fn open(&mut self, index: usize) -> Option<OpenEntry<'_, I>> {
+		let entry = self.entries.get(index)?;
+
+		self.notify(&Event::EntryReadStarted {
+			index,
+			entry,
+		});
+
+		Some(OpenEntry {
+			inner: self.inner,
+			index,
+			offset: entry.offset_bytes(),
+			length: entry.len_bytes(),
+			position: 0,
+			observer: self.observer.clone(),
+		})
+	}
+
+	/// Opens and returns the first entry with the specified name (case-insensitively) for reading, if it exists.
+	pub fn open_by_name(&mut self, name: &str) -> Option<OpenEntry<'_, I>> {
+		let index = self.position_of(name)?;
+
+		self.open(index)
+	}
+
+	/// Reads the entirety of the entry at the specified index into a buffer allocated for its length, if it exists.
+	/// Entries extending beyond the end of the underlying source are read only up to its end.
+	pub fn read_entry(&mut self, index: usize) -> Result<Option<Vec<u8>>, io::Error> {
+		let Some(capacity) = self.entries.get(index).map(|entry| entry.len_bytes().min(PREALLOCATED_BYTES)) else {
+			return Ok(None);
+		};
+
+		let mut data = Vec::with_capacity(capacity as usize);
+
+		if let Some(mut open) = self.open(index) {
+			open.read_to_end(&mut data)?;
+		}
+
+		Ok(Some(data))
+	}
+
+	/// Reads the entirety of the first entry with the specified name (case-insensitively) into a buffer, as per [`Archive::read_entry`], if it exists.
+	pub fn read_entry_by_name(&mut self, name: &str) -> Result<Option<Vec<u8>>, io::Error> {
+		match self.position_of(name) {
+			Some(index) => self.read_entry(index),
+			None => Ok(None),
+		}
+	}
+
+	/// Detects the kind of the entry at the specified index from its leading bytes (regardless of its name), if it exists, attaching it to the attributes of the entry.
+	pub fn kind(&mut self, index: usize) -> Result<Option<Kind>, io::Error> {
+		let Some(open) = self.open(index) else {
+			return Ok(None);
+		};
+
+		let mut magic = Vec::new();
+
+		open.take(KIND_MAGIC_SIZE).read_to_end(&mut magic)?;
+
+		let kind = Kind::detect(&magic);
+
+		self.entries[index].attributes.kind = Some(kind);
+
+		Ok(Some(kind))
+	}
+
+	/// Opens and returns the entry at the specified index for reading, checking that it exists and that it lies entirely within the underlying source.
+	pub fn open_checked(&mut self, index: usize) -> Result<OpenEntry<'_, I>, ReadError> {
+		let entry = self.entries.get(index).ok_or(ReadError::InvalidIndex(index))?;
+
+		// Check the end of the entry against the length of the source.
+
+		let end = entry.end_bytes();
+		let len = self.stream_len()?;
+
+		if end > len {
+			return Err(ReadError::OutOfBounds {
+				index,
+				name: self.entries[index].name.clone(),
+				end,
+				len,
+			});
+		}
+
+		self.open(index).ok_or(ReadError::InvalidIndex(index))
+	}
+
+	/// Returns whether the archive has the same entries as another archive, with the same names (case-insensitively) and content, in the same order.
+	/// Unlike comparing archives for equality, which only compares their entries, this reads and compares the data of each entry.
+	pub fn content_eq<J>(&mut self, other: &mut Archive<J>) -> Result<bool, io::Error>
+	where
+		J: Read + Seek,
+	{
+		if self.len() != other.len() {
+			return Ok(false);
+		}
+
+		for index in 0..self.len() {
+			if !self.entries[index].name.eq_ignore_ascii_case(&other.entries[index].name) || !self.entry_eq(index, other, index)? {
+				return Ok(false);
+			}
+		}
+
+		Ok(true)
+	}
+
+	/// Returns whether the entry at the specified index has the same content as the entry at the specified index within another archive, or `false` if either does not exist.
+	/// Entries with different lengths are not read.
+	pub fn entry_eq<J>(&mut self, index: usize, other: &mut Archive<J>, other_index: usize) -> Result<bool, io::Error>
+	where
+		J: Read + Seek,
+	{
+		match (self.entries.get(index), other.entries.get(other_index)) {
+			(Some(a), Some(b)) if a.length == b.length => (),
+			_ => return Ok(false),
+		}
+
+		let (Some(mut a), Some(mut b)) = (self.open(index), other.open(other_index)) else {
+			return Ok(false);
+		};
+
+		// Compare the entries a sector at a time, stopping at the first difference.
+
+		let mut buf_a = [0; SECTOR_SIZE as usize];
+		let mut buf_b = [0; SECTOR_SIZE as usize];
+
+		loop {
+			let read_a = read_fully(&mut a, &mut buf_a)?;
+			let read_b = read_fully(&mut b, &mut buf_b)?;
+
+			if buf_a[..read_a] != buf_b[..read_b] {
+				return Ok(false);
+			}
+
+			if read_a == 0 {
+				return Ok(true);
+			}
+		}
+	}
+
+	/// Returns the length, in bytes, of the underlying source.
+	pub(crate) fn stream_len(&mut self) -> Result<u64, io::Error> {
+		self.inner.seek(io::SeekFrom::End(0))
+	}
+
+	/// Returns the underlying stream of the archive.
+	pub(crate) fn inner_mut(&mut self) -> &mut I {
+		self.inner
+	}
+}
+
+impl<I> Archive<'_, I>
+where
+	I: ReadAt,
+{
+	/// Opens and returns the entry at the specified index for reading using positioned reads, if it exists.
+	/// Unlike [`Archive::open`], this only borrows the archive immutably, such that several entries may be open at once.
+	pub fn open_shared(&self, index: usize) -> Option<SharedEntry<'_, I>> {
 		let entry = self.entries.get(index)?;
 
-		Some(OpenEntry {
-			inner: self.inner,
-			offset: entry.offset * SECTOR_SIZE,
-			length: entry.length * SECTOR_SIZE,
-			position: 0,
-		})
+		self.notify(&Event::EntryReadStarted {
+			index,
+			entry,
+		});
+
+		Some(SharedEntry {
+			inner: self.inner,
+			index,
+			offset: entry.offset_bytes(),
+			length: entry.len_bytes(),
+			position: 0,
+			observer: self.observer.clone(),
+		})
+	}
+
+	/// Creates a copy of the archive backed by the specified reader (e.g. another handle to the same file), copying the entries, warnings and observer.
+	/// As the archive mutably borrows its reader, it cannot implement `Clone` itself (unlike [`OwnedArchive`], where the reader can be cloned).
+	pub fn fork<'b>(&self, inner: &'b mut I) -> Archive<'b, I> {
+		Archive {
+			inner,
+			entries: self.entries.clone(),
+			names: self.names.clone(),
+			declared: self.declared,
+			trailing: self.trailing,
+			unclaimed: self.unclaimed.clone(),
+			warnings: self.warnings.clone(),
+			observer: self.observer.clone(),
+		}
+	}
+}
+
+#[cfg(feature = "bytes")]
+impl Archive<'_, io::Cursor<bytes::Bytes>> {
+	/// Returns the data of the entry at the specified index as a slice of the underlying buffer, if it exists.
+	/// The returned `Bytes` shares the buffer rather than copying it, and is clamped to the end of the buffer.
+	/// Memory-mapped files may be used by wrapping the map with `Bytes::from_owner`.
+	pub fn entry_bytes(&self, index: usize) -> Option<bytes::Bytes> {
+		let buf = self.inner.get_ref();
+
+		Some(buf.slice(entry_range(self.entries.get(index)?, buf.len())))
+	}
+}
+
+impl<T> Archive<'_, io::Cursor<T>>
+where
+	T: AsRef<[u8]>,
+{
+	/// Returns the data of the entry at the specified index as a slice of the underlying buffer (e.g. a `Vec<u8>` or a memory map), if it exists.
+	/// The slice is clamped to the end of the buffer.
+	pub fn entry_slice(&self, index: usize) -> Option<&[u8]> {
+		let buf = self.inner.get_ref().as_ref();
+
+		Some(&buf[entry_range(self.entries.get(index)?, buf.len())])
+	}
+}
+
+#[cfg(feature = "mmap")]
+impl MmapArchive {
+	/// Maps the specified `img` file into memory, reading the archive using the reader produced by `reader` (e.g. `|img| V2Reader::new(img).read()`).
+	/// As with any memory-mapped file, the file must not be modified (whether by this or any other process) whilst mapped.
+	pub fn map<F>(img: &std::fs::File, reader: F) -> Result<Self, ReadError>
+	where
+		F: for<'b> FnOnce(&'b mut io::Cursor<memmap2::Mmap>) -> Result<Archive<'b, io::Cursor<memmap2::Mmap>>, ReadError>,
+	{
+		// SAFETY: the map is read-only, and the caller is required not to modify the file whilst mapped.
+
+		let map = unsafe { memmap2::Mmap::map(img)? };
+
+		Ok(Self {
+			archive: OwnedArchive::read(io::Cursor::new(map), reader)?,
+		})
+	}
+
+	/// Returns the data of the entry at the specified index as a slice of the map, if it exists.
+	/// The slice is clamped to the end of the map.
+	pub fn data(&self, index: usize) -> Option<&[u8]> {
+		let map = self.archive.inner.get_ref();
+
+		Some(&map[entry_range(self.archive.get(index)?, map.len())])
+	}
+
+	/// Borrows the mapped archive as an [`Archive`].
+	pub fn archive(&mut self) -> ArchiveMut<'_, io::Cursor<memmap2::Mmap>> {
+		self.archive.archive()
+	}
+
+	/// Returns the number of entries in the archive.
+	pub fn len(&self) -> usize {
+		self.archive.len()
+	}
+
+	/// Returns if the archive is void of any entries.
+	pub fn is_empty(&self) -> bool {
+		self.archive.is_empty()
+	}
+
+	/// Returns the entry at the specified index, if it exists.
+	pub fn get(&self, index: usize) -> Option<&Entry> {
+		self.archive.get(index)
+	}
+
+	/// Returns each of the entries in the archive, in the order they appear within the directory.
+	pub fn entries(&self) -> &[Entry] {
+		self.archive.entries()
+	}
+
+	/// Returns an iterator over each of the entries in the archive.
+	pub fn iter(&self) -> slice::Iter<'_, Entry> {
+		self.archive.iter()
+	}
+
+	/// Returns each of the non-fatal warnings raised whilst reading the archive.
+	pub fn warnings(&self) -> &[Warning] {
+		self.archive.warnings()
+	}
+
+	/// Consumes the archive, returning each of the entries.
+	pub fn into_entries(self) -> Vec<Entry> {
+		self.archive.into_entries()
+	}
+}
+
+impl<R> Read for OpenEntry<'_, R>
+where
+	R: Read + Seek,
+{
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		// Check if we have already reached the end of the entry.
+
+		if self.position >= self.length {
+			self.finish();
+
+			return Ok(0);
+		}
+
+		// Seek to the start of the entry including any currently read bytes.
+
+		self.inner.seek(io::SeekFrom::Start(self.offset + self.position))?;
+
+		// Calculate the maximum possible number of bytes to read for the entry, to forbid reading beyond it.
+		// Includes the number of bytes already read, honouring the length of the entry and the length of the buffer.
+
+		let length = (self.length - self.position.min(self.length)).min(buf.len() as u64) as usize;
+		let offset = self.inner.read(&mut buf[0..length])?;
+
+		self.position += offset as u64;
+
+		// Check if we have now reached the end of the entry (or the end of the source).
+
+		if offset == 0 || self.position >= self.length {
+			self.finish();
+		}
+
+		Ok(offset)
+	}
+}
+
+impl<R> Seek for OpenEntry<'_, R>
+where
+	R: Read + Seek,
+{
+	/// Seeks within the entry, with positions relative to the start of the entry and `SeekFrom::End` relative to the end of the entry.
+	/// Seeking beyond the end of the entry is permitted, however no further bytes may be read from there.
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.position = seek_within(self.position, self.length, pos)?;
+
+		Ok(self.position)
+	}
+}
+
+impl<R> Read for SharedEntry<'_, R>
+where
+	R: ReadAt + ?Sized,
+{
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if self.position >= self.length {
+			self.finish();
+
+			return Ok(0);
+		}
+
+		// Read from the current position within the entry, forbidding reading beyond it.
+
+		let length = (self.length - self.position).min(buf.len() as u64) as usize;
+		let offset = self.inner.read_at(&mut buf[0..length], self.offset + self.position)?;
+
+		self.position += offset as u64;
+
+		if offset == 0 || self.position >= self.length {
+			self.finish();
+		}
+
+		Ok(offset)
+	}
+}
+
+impl<R> Seek for SharedEntry<'_, R>
+where
+	R: ReadAt + ?Sized,
+{
+	/// Seeks within the entry, as per [`OpenEntry`].
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.position = seek_within(self.position, self.length, pos)?;
+
+		Ok(self.position)
+	}
+}
+
+impl<R> SharedEntry<'_, R>
+where
+	R: ReadAt + ?Sized,
+{
+	fn finish(&mut self) {
+		// Notify the observer only once, upon first reaching the end of the entry.
+
+		if let Some(observer) = self.observer.take() {
+			observer.notify(&Event::EntryReadFinished {
+				index: self.index,
+				bytes: self.position,
+			});
+		}
+	}
+}
+
+impl<R> Positioned<R>
+where
+	R: ReadAt,
+{
+	/// Creates a new stream over the specified source, which is `length` bytes long.
+	pub fn new(inner: R, length: u64) -> Self {
+		Self {
+			inner,
+			length,
+			position: 0,
+		}
+	}
+
+	/// Returns the length of the source.
+	pub fn len(&self) -> u64 {
+		self.length
+	}
+
+	/// Returns whether the source is empty.
+	pub fn is_empty(&self) -> bool {
+		self.length == 0
+	}
+
+	/// Returns the underlying source.
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	/// Consumes the stream, returning the underlying source.
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R> Read for Positioned<R>
+where
+	R: ReadAt,
+{
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if self.position >= self.length {
+			return Ok(0);
+		}
+
+		// Read from the current position, forbidding reading beyond the end of the source.
+
+		let length = (self.length - self.position).min(buf.len() as u64) as usize;
+		let read = self.inner.read_at(&mut buf[0..length], self.position)?;
+
+		self.position += read as u64;
+
+		Ok(read)
+	}
+}
+
+impl<R> Seek for Positioned<R>
+where
+	R: ReadAt,
+{
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		self.position = seek_within(self.position, self.length, pos)?;
+
+		Ok(self.position)
+	}
+}
+
+impl<R> ReadAt for Positioned<R>
+where
+	R: ReadAt,
+{
+	/// Reads from the underlying source, ignoring (and leaving unchanged) the position of the stream.
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		self.inner.read_at(buf, offset)
+	}
+}
+
+impl ReadAt for [u8] {
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		let start = offset.min(self.len() as u64) as usize;
+		let length = (self.len() - start).min(buf.len());
+
+		buf[..length].copy_from_slice(&self[start..start + length]);
+
+		Ok(length)
+	}
+}
+
+impl<T> ReadAt for io::Cursor<T>
+where
+	T: AsRef<[u8]>,
+{
+	/// Reads from the underlying buffer, ignoring (and leaving unchanged) the position of the cursor.
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		self.get_ref().as_ref().read_at(buf, offset)
+	}
+}
+
+impl<T> ReadAt for &T
+where
+	T: ReadAt + ?Sized,
+{
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		(**self).read_at(buf, offset)
+	}
+}
+
+impl<T> ReadAt for Box<T>
+where
+	T: ReadAt + ?Sized,
+{
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		(**self).read_at(buf, offset)
+	}
+}
+
+impl<T> ReadAt for std::sync::Arc<T>
+where
+	T: ReadAt + ?Sized,
+{
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		(**self).read_at(buf, offset)
+	}
+}
+
+impl ReadAt for std::fs::File {
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		#[cfg(unix)]
+		return std::os::unix::fs::FileExt::read_at(self, buf, offset);
+
+		// Note that the position of the file is changed on Windows, which is unimportant as every read is positioned.
+
+		#[cfg(windows)]
+		return std::os::windows::fs::FileExt::seek_read(self, buf, offset);
+
+		#[cfg(not(any(unix, windows)))]
+		return Err(io::Error::from(io::ErrorKind::Unsupported));
+	}
+}
+
+impl<R> OpenEntry<'_, R>
+where
+	R: Read + Seek,
+{
+	fn finish(&mut self) {
+		// Notify the observer only once, upon first reaching the end of the entry.
+
+		if let Some(observer) = self.observer.take() {
+			observer.notify(&Event::EntryReadFinished {
+				index: self.index,
+				bytes: self.position,
+			});
+		}
+	}
+}
+
+impl<I> Archive<'_, I> {
+	fn size(&self) -> u64 {
+		self.entries.iter().map(Entry::len_bytes).sum()
+	}
+}
+
+impl<I> OwnedArchive<I> {
+	/// Reads an archive from the specified reader, taking ownership of it, using the reader produced by `reader` (e.g. `|img| V2Reader::new(img).read()`).
+	pub fn read<F>(mut inner: I, reader: F) -> Result<Self, ReadError>
+	where
+		F: for<'b> FnOnce(&'b mut I) -> Result<Archive<'b, I>, ReadError>,
+	{
+		let archive = reader(&mut inner)?;
+
+		let parts = Parts {
+			entries: archive.entries,
+			names: archive.names,
+			declared: archive.declared,
+			trailing: archive.trailing,
+			unclaimed: archive.unclaimed,
+			warnings: archive.warnings,
+			observer: archive.observer,
+		};
+
+		Ok(Self {
+			inner,
+			parts,
+		})
+	}
+
+	/// Borrows the owned archive as an [`Archive`].
+	pub fn archive(&mut self) -> ArchiveMut<'_, I> {
+		let archive = Archive {
+			inner: &mut self.inner,
+			entries: mem::take(&mut self.parts.entries),
+			names: self.parts.names.take(),
+			declared: self.parts.declared,
+			trailing: self.parts.trailing,
+			unclaimed: mem::take(&mut self.parts.unclaimed),
+			warnings: mem::take(&mut self.parts.warnings),
+			observer: self.parts.observer.take(),
+		};
+
+		ArchiveMut {
+			parts: &mut self.parts,
+			archive,
+		}
+	}
+
+	/// Returns the number of entries in the archive.
+	pub fn len(&self) -> usize {
+		self.parts.entries.len()
+	}
+
+	/// Returns if the archive is void of any entries.
+	pub fn is_empty(&self) -> bool {
+		self.parts.entries.is_empty()
+	}
+
+	/// Returns the entry at the specified index, if it exists.
+	pub fn get(&self, index: usize) -> Option<&Entry> {
+		self.parts.entries.get(index)
+	}
+
+	/// Returns each of the entries in the archive, in the order they appear within the directory.
+	pub fn entries(&self) -> &[Entry] {
+		&self.parts.entries
+	}
+
+	/// Returns an iterator over each of the entries in the archive.
+	pub fn iter(&self) -> slice::Iter<'_, Entry> {
+		self.parts.entries.iter()
+	}
+
+	/// Returns each of the non-fatal warnings raised whilst reading the archive.
+	pub fn warnings(&self) -> &[Warning] {
+		&self.parts.warnings
+	}
+
+	/// Consumes the archive, returning each of the entries.
+	pub fn into_entries(self) -> Vec<Entry> {
+		self.parts.entries
+	}
+
+	/// Consumes the archive, returning the underlying reader.
+	pub fn into_inner(self) -> I {
+		self.inner
+	}
+}
+
+impl<I> OwnedArchive<I>
+where
+	I: ReadAt,
+{
+	/// Opens and returns the entry at the specified index for reading using positioned reads, if it exists.
+	/// As this only borrows the archive immutably, the archive may be shared between threads (e.g. via [`std::sync::Arc`]) to read entries concurrently.
+	pub fn open_shared(&self, index: usize) -> Option<SharedEntry<'_, I>> {
+		let entry = self.parts.entries.get(index)?;
+
+		if let Some(observer) = &self.parts.observer {
+			observer.notify(&Event::EntryReadStarted {
+				index,
+				entry,
+			});
+		}
+
+		Some(SharedEntry {
+			inner: &self.inner,
+			index,
+			offset: entry.offset_bytes(),
+			length: entry.len_bytes(),
+			position: 0,
+			observer: self.parts.observer.clone(),
+		})
+	}
+}
+
+impl<'a, I> Deref for ArchiveMut<'a, I> {
+	type Target = Archive<'a, I>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.archive
+	}
+}
+
+impl<I> DerefMut for ArchiveMut<'_, I> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.archive
+	}
+}
+
+impl<I> Drop for ArchiveMut<'_, I> {
+	fn drop(&mut self) {
+		// Return the state of the archive (which may have changed) to the owned archive.
+
+		self.parts.entries = mem::take(&mut self.archive.entries);
+		self.parts.names = self.archive.names.take();
+		self.parts.declared = self.archive.declared;
+		self.parts.trailing = self.archive.trailing;
+		self.parts.unclaimed = mem::take(&mut self.archive.unclaimed);
+		self.parts.warnings = mem::take(&mut self.archive.warnings);
+		self.parts.observer = self.archive.observer.take();
+	}
+}
+
+impl<I> Archive<'_, I> {
+	/// Returns a copy of the directory of the archive, detached from its reader.
+	pub fn to_index(&self) -> ArchiveIndex {
+		ArchiveIndex {
+			entries: self.entries.clone(),
+			declared: self.declared,
+			trailing: self.trailing,
+		}
+	}
+}
+
+impl ArchiveIndex {
+	/// Binds the index to the specified reader (e.g. the reopened `img` file), producing an archive with the same entries.
+	/// The reader is assumed to be the same archive the index was taken from, which is not checked.
+	pub fn bind<I>(self, inner: &mut I) -> Archive<'_, I> {
+		Archive {
+			inner,
+			entries: self.entries,
+			names: None,
+			declared: self.declared,
+			trailing: self.trailing,
+			unclaimed: Vec::new(),
+			warnings: Vec::new(),
+			observer: None,
+		}
+	}
+
+	/// Returns the version of the archive the index was taken from.
+	pub fn version(&self) -> Version {
+		Version::from_declared(self.declared)
+	}
+
+	/// Returns the number of entries declared within the header, for V2-styled archives.
+	pub fn declared(&self) -> Option<usize> {
+		self.declared
+	}
+
+	/// Returns each of the entries, in the order they appear within the directory.
+	pub fn entries(&self) -> &[Entry] {
+		&self.entries
+	}
+
+	/// Returns the number of entries.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns whether the index is void of any entries.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Consumes the index, returning each of the entries.
+	pub fn into_entries(self) -> Vec<Entry> {
+		self.entries
+	}
+}
+
+impl<I> Clone for OwnedArchive<I>
+where
+	I: Clone,
+{
+	/// Clones the archive along with its reader (e.g. a `Cursor<Vec<u8>>`), such that each may be read from independently.
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+			parts: self.parts.clone(),
+		}
+	}
+}
+
+impl<I> fmt::Debug for OwnedArchive<I> {
+	/// Summarises the archive, as per [`Archive`].
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("OwnedArchive").field("entries", &self.parts.entries.len()).field("warnings", &self.parts.warnings.len()).finish_non_exhaustive()
+	}
+}
+
+impl<I> fmt::Debug for Archive<'_, I> {
+	/// Summarises the archive, rather than listing each of the entries.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Archive")
+			.field("version", &Version::from_declared(self.declared))
+			.field("entries", &self.entries.len())
+			.field("size", &self.size())
+			.field("unclaimed", &self.unclaimed.len())
+			.field("warnings", &self.warnings.len())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<I> fmt::Display for Archive<'_, I> {
+	/// Formats the archive as its version, followed by its number of entries and their total size in human-readable units (e.g. `V2 archive (3 entries, 20.0 KiB)`).
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} archive ({} entries, ", Version::from_declared(self.declared), self.entries.len())?;
+		write_size(f, self.size())?;
+		write!(f, ")")
+	}
+}
+
+impl fmt::Display for Stats {
+	/// Formats the statistics as the number of entries, followed by their total size in human-readable units and in sectors (e.g. `3 entries, 20.0 KiB in 10 sectors (0 wasted)`).
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} entries, ", self.entries)?;
+		write_size(f, self.bytes)?;
+		write!(f, " in {} sectors ({} wasted)", self.sectors, self.wasted_sectors)
+	}
+}
+
+impl fmt::Display for Entry {
+	/// Formats the entry as its name, followed by its offset in bytes and its size in human-readable units (e.g. `VIRGO.DFF (offset 2048, 2.0 KiB)`).
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} (offset {}, ", self.name, self.offset_bytes())?;
+		write_size(f, self.len_bytes())?;
+		write!(f, ")")
+	}
+}
+
+impl fmt::Display for Kind {
+	/// Formats the kind as its conventional extension (e.g. `dff`), or `unknown`.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Dff => write!(f, "dff"),
+			Self::Txd => write!(f, "txd"),
+			Self::Col => write!(f, "col"),
+			Self::Ifp => write!(f, "ifp"),
+			Self::Ipl => write!(f, "ipl"),
+			Self::Unknown => write!(f, "unknown"),
+		}
+	}
+}
+
+impl fmt::Display for Warning {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::UnterminatedName {
+				index,
+				name,
+			} => write!(f, "unterminated name of entry [#{} {}]", index, name),
+			Self::InvalidCharacter {
+				index,
+				character,
+			} => write!(f, "invalid character {:?} in name of entry [#{}]", character, index),
+			Self::EmptyEntry {
+				index,
+			} => write!(f, "empty entry [#{}]", index),
+			Self::TrailingData {
+				offset,
+			} => write!(f, "trailing data in directory at offset {}", offset),
+			Self::UnclaimedData {
+				offset,
+				length,
+			} => write!(f, "unclaimed data at offset {} ({} bytes)", offset, length),
+			Self::ProfileViolation {
+				violation,
+			} => write!(f, "profile violation ({})", violation),
+			Self::SkippedEntry {
+				index,
+				reason,
+			} => write!(f, "skipped malformed entry [#{}] ({})", index, reason),
+			Self::OutOfBounds {
+				index,
+				end,
+				len,
+			} => write!(f, "entry [#{}] ends at offset {} beyond the end of the archive ({} bytes)", index, end, len),
+		}
+	}
+}
+
+impl<'e, I> IntoIterator for &'e Archive<'_, I> {
+	type Item = &'e Entry;
+	type IntoIter = slice::Iter<'e, Entry>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.entries.iter()
+	}
+}
+
+impl<I> IntoIterator for Archive<'_, I> {
+	type Item = Entry;
+	type IntoIter = vec::IntoIter<Entry>;
+
+	/// Consumes the archive, returning an iterator over each of the entries (see also [`Archive::into_entries`]).
+	fn into_iter(self) -> Self::IntoIter {
+		self.entries.into_iter()
+	}
+}
+
+impl<'e, I> IntoIterator for &'e OwnedArchive<I> {
+	type Item = &'e Entry;
+	type IntoIter = slice::Iter<'e, Entry>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.parts.entries.iter()
+	}
+}
+
+impl<I> Hash for Archive<'_, I> {
+	fn hash<H: hash::Hasher>(&self, state: &mut H) {
+		self.entries.hash(state);
+	}
+}
+
+impl<I> PartialEq for Archive<'_, I> {
+	fn eq(&self, other: &Self) -> bool {
+		self.entries == other.entries
+	}
+}
+
+impl Kind {
+	/// Detects the kind of data from its leading bytes, returning [`Kind::Unknown`] should they not be recognised (or be too few).
+	pub fn detect(data: &[u8]) -> Self {
+		let Some(magic) = data.first_chunk::<4>() else {
+			return Self::Unknown;
+		};
+
+		// Check for the magic numbers of the various formats, before the types of the first chunk of RenderWare streams.
+
+		if COL_FOURCCS.contains(&magic) {
+			return Self::Col;
+		}
+
+		if IFP_MAGICS.contains(&magic) {
+			return Self::Ifp;
+		}
+
+		if magic == IPL_MAGIC {
+			return Self::Ipl;
+		}
+
+		match u32::from_le_bytes(*magic) {
+			CHUNK_CLUMP => Self::Dff,
+			CHUNK_TEXTURE_DICTIONARY => Self::Txd,
+			_ => Self::Unknown,
+		}
+	}
+}
+
+impl Entry {
+	/// Creates a new entry with the specified name, offset and length (both in sectors).
+	/// Returns an error if the name is longer than 23 characters, or if the entry would end beyond the maximum offset.
+	pub fn new(name: &str, offset: u64, length: u64) -> Result<Self, Exceeded> {
+		Limit::NameLength.check(name.chars().count() as u64)?;
+		Limit::Offset.check(offset.saturating_add(length))?;
+
+		Ok(Self {
+			name: name.to_owned(),
+			offset,
+			length,
+			stream_len: None,
+			raw_name: None,
+			attributes: Attributes::default(),
+		})
+	}
+
+	/// Returns the offset, in sectors, of the entry.
+	pub fn offset_sectors(&self) -> u64 {
+		self.offset
+	}
+
+	/// Returns the offset, in bytes, of the entry.
+	pub fn offset_bytes(&self) -> u64 {
+		self.offset * SECTOR_SIZE
+	}
+
+	/// Returns the length, in sectors, of the entry.
+	pub fn len_sectors(&self) -> u64 {
+		self.length
+	}
+
+	/// Returns the length, in bytes, of the entry (including any padding up to the end of the final sector).
+	pub fn len_bytes(&self) -> u64 {
+		self.length * SECTOR_SIZE
+	}
+
+	/// Returns the end (exclusive), in sectors, of the entry.
+	pub fn end_sectors(&self) -> u64 {
+		self.offset + self.length
+	}
+
+	/// Returns the end (exclusive), in bytes, of the entry.
+	pub fn end_bytes(&self) -> u64 {
+		self.end_sectors() * SECTOR_SIZE
+	}
+
+	/// Returns the range, in bytes, occupied by the entry within the archive.
+	pub fn range_bytes(&self) -> Range<u64> {
+		self.offset_bytes()..self.end_bytes()
+	}
+
+	/// Returns the extension of the name of the entry (i.e. following the last dot, e.g. `DFF` for `VIRGO.DFF`), if it has one.
+	pub fn extension(&self) -> Option<&str> {
+		self.name.rsplit_once('.').map(|(_, extension)| extension)
+	}
+
+	/// Returns the stem of the name of the entry (i.e. preceding the last dot, e.g. `VIRGO` for `VIRGO.DFF`), or the entire name if it has no extension.
+	pub fn stem(&self) -> &str {
+		self.name.rsplit_once('.').map_or(self.name.as_str(), |(stem, _)| stem)
+	}
+
+	/// Returns whether the name of the entry has the specified extension (case-insensitively, and without the dot, e.g. `col`).
+	pub fn has_extension(&self, extension: &str) -> bool {
+		self.extension().is_some_and(|own| own.eq_ignore_ascii_case(extension))
+	}
+
+	/// Sets the name of the entry, clearing its [`Entry::raw_name`].
+	/// Returns an error, leaving the entry unchanged, if the name is longer than 23 characters.
+	pub fn set_name(&mut self, name: &str) -> Result<(), Exceeded> {
+		Limit::NameLength.check(name.chars().count() as u64)?;
+
+		self.name = name.to_owned();
+		self.raw_name = None;
+
+		Ok(())
+	}
+
+	/// Sets the offset and length (both in sectors) of the entry.
+	/// Returns an error, leaving the entry unchanged, if the entry would end beyond the maximum offset.
+	pub fn set_range(&mut self, offset: u64, length: u64) -> Result<(), Exceeded> {
+		Limit::Offset.check(offset.saturating_add(length))?;
+
+		self.offset = offset;
+		self.length = length;
+
+		Ok(())
+	}
+
+	/// Returns the properties of the entry by which it is compared, hashed and ordered.
+	fn key(&self) -> (u64, &str, u64, Option<u64>) {
+		(self.offset, &self.name, self.length, self.stream_len)
+	}
+}
+
+impl PartialEq for Entry {
+	fn eq(&self, other: &Self) -> bool {
+		self.key() == other.key()
+	}
+}
+
+impl Eq for Entry {}
+
+impl Hash for Entry {
+	fn hash<H: hash::Hasher>(&self, state: &mut H) {
+		self.key().hash(state);
+	}
+}
+
+impl Ord for Entry {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		self.key().cmp(&other.key())
+	}
+}
+
+impl PartialOrd for Entry {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<I> PartialOrd for Archive<'_, I> {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		self.entries.partial_cmp(&other.entries)
+	}
+}
+
+fn read_null_terminated<T>(inner: &mut T, options: &ReadOptions) -> Result<(String, [u8; NAME_SIZE_NULL_TERMINATOR]), io::Error>
+where
+	T: Read,
+{
+	// Read the bytes for the string, in full if strict.
+
+	let mut buf = [0; NAME_SIZE_NULL_TERMINATOR];
+
+	match options.strict {
+		true => inner.read_exact(&mut buf)?,
+		#[allow(clippy::unused_io_amount)]
+		false => {
+			inner.read(&mut buf)?;
+		}
+	}
+
+	// Determine the position of the null terminator and build a string from it.
+
+	let pos = buf.iter().position(|&b| b == NULL_TERMINATOR).unwrap_or(buf.len()).min(NAME_SIZE_NULL_TERMINATOR);
+	let str = decode_name(&buf[..pos], options.name_encoding);
+
+	Ok((str, buf))
+}
+
+fn decode_name(bytes: &[u8], encoding: NameEncoding) -> String {
+	match encoding {
+		NameEncoding::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+		NameEncoding::Ascii => bytes.iter().map(|&b| if b.is_ascii() { char::from(b) } else { NAME_REPLACEMENT }).collect(),
+		NameEncoding::Windows1252 => bytes.iter().map(|&b| if (0x80..0xA0).contains(&b) { WINDOWS_1252[(b - 0x80) as usize] } else { char::from(b) }).collect(),
+		NameEncoding::Utf8Lossy => String::from_utf8_lossy(bytes).into_owned(),
+	}
+}
+
+fn write_size(f: &mut fmt::Formatter, bytes: u64) -> fmt::Result {
+	const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+	if bytes < 1024 {
+		return write!(f, "{} B", bytes);
+	}
+
+	// Scale down to the largest unit for which the size is at least one.
+
+	let mut size = bytes as f64 / 1024.0;
+	let mut unit = 0;
+
+	while size >= 1024.0 && unit < UNITS.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+
+	write!(f, "{:.1} {}", size, UNITS[unit])
+}
+
+/// Raises a warning for the entry at the specified index skipped due to the specified error, returning whether reading may continue with the next entry.
+/// Only malformed entries are skipped, with any other error (e.g. failing to read the directory itself) stopping reading.
+fn skip_entry(index: usize, error: ReadError, warnings: &mut Vec<Warning>) -> bool {
+	let recoverable = matches!(error, ReadError::InvalidName { .. } | ReadError::LimitExceeded { .. });
+
+	warnings.push(Warning::SkippedEntry {
+		index,
+		reason: error.to_string(),
+	});
+
+	recoverable
+}
+
+fn check_entry(index: usize, entry: &Entry, warnings: &mut Vec<Warning>) {
+	if entry.name.chars().count() > NAME_SIZE {
+		warnings.push(Warning::UnterminatedName {
+			index,
+			name: entry.name.clone(),
+		});
+	}
+
+	if let Some(character) = entry.name.chars().find(|&c| c.is_ascii_control() || RESERVED_CHARACTERS.contains(&c)) {
+		warnings.push(Warning::InvalidCharacter {
+			index,
+			character,
+		});
+	}
+
+	if entry.length == 0 {
+		warnings.push(Warning::EmptyEntry {
+			index,
+		});
+	}
+}
+
+/// Calculates the range of the entry within a buffer of the specified length, honouring the length of the buffer.
+fn entry_range(entry: &Entry, len: usize) -> Range<usize> {
+	let start = entry.offset_bytes().min(len as u64) as usize;
+	let end = entry.end_bytes().min(len as u64) as usize;
+
+	start..end
+}
+
+fn notify_warnings(observer: &Option<Handle>, warnings: &[Warning]) {
+	if let Some(observer) = observer {
+		for warning in warnings {
+			observer.notify(&Event::Warning(warning));
+		}
+	}
+}
+
+pub(crate) fn read_fully<T>(inner: &mut T, buf: &mut [u8]) -> Result<usize, io::Error>
+where
+	T: Read + ?Sized,
+{
+	// Read repeatedly until either the buffer is full or the source is exhausted.
+
+	let mut len = 0;
+
+	while len < buf.len() {
+		match inner.read(&mut buf[len..]) {
+			Ok(0) => break,
+			Ok(num) => len += num,
+			Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+			Err(error) => return Err(error),
+		}
+	}
+
+	Ok(len)
+}
+
+/// Resolves the position sought to within something of the specified length, from the specified current position.
+/// Positions beyond the end are permitted (as with files), whereas negative or overflowing positions are not.
+fn seek_within(position: u64, length: u64, pos: io::SeekFrom) -> io::Result<u64> {
+	let position = match pos {
+		io::SeekFrom::Start(offset) => Some(offset),
+		io::SeekFrom::Current(offset) => position.checked_add_signed(offset),
+		io::SeekFrom::End(offset) => length.checked_add_signed(offset),
+	};
+
+	position.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position"))
+}
+
+/// Folds the specified name such that names differing only by case are equal, in line with how the games resolve names.
+pub(crate) fn fold_name(name: &str) -> String {
+	name.to_ascii_lowercase()
+}
+
+/// Groups the indices of the entries by their folded names (see [`fold_name`]).
+/// Groups are ordered by their first entry, with indices ordered as they appear within the directory.
+pub(crate) fn group_by_name(entries: &[Entry]) -> Vec<(String, Vec<usize>)> {
+	let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+	let mut names: HashMap<String, usize> = HashMap::with_capacity(entries.len());
+
+	for (index, entry) in entries.iter().enumerate() {
+		let name = fold_name(&entry.name);
+
+		let group = *names.entry(name.clone()).or_insert_with(|| {
+			groups.push((name, Vec::new()));
+			groups.len() - 1
+		});
+
+		groups[group].1.push(index);
+	}
+
+	groups
+}
+
+/// Matches the specified name against the specified glob pattern, case-insensitively.
+pub fn glob(pattern: &str, name: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+	let name: Vec<char> = name.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+	let (mut p, mut n) = (0, 0);
+	let mut backtrack = None;
+
+	while n < name.len() {
+		match pattern.get(p) {
+			Some('*') => {
+				// Remember the position of the wildcard, initially matching nothing with it.
+
+				backtrack = Some((p, n));
+				p += 1;
+			}
+			Some(&c) if c == '?' || c == name[n] => {
+				p += 1;
+				n += 1;
+			}
+			_ => match backtrack {
+				Some((star, matched)) => {
+					// Extend the match of the last wildcard by a single character, and try again.
+
+					backtrack = Some((star, matched + 1));
+					p = star + 1;
+					n = matched + 1;
+				}
+				None => return false,
+			},
+		}
+	}
+
+	pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn apply_name_policy(name: String, policy: NamePolicy) -> Result<String, String> {
+	let printable = |c: char| c.is_ascii_graphic() || c == ' ';
+
+	if policy == NamePolicy::PassThrough || name.chars().all(printable) {
+		return Ok(name);
+	}
+
+	// Replace any non-printable characters, returning the replaced name as an error should the policy reject them.
+
+	let replaced = name.chars().map(|c| if printable(c) { c } else { NAME_REPLACEMENT }).collect();
+
+	match policy {
+		NamePolicy::Replace => Ok(replaced),
+		_ => Err(replaced),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		cmp,
+		io::{self, Cursor, Read, Seek, SeekFrom},
+		sync::{Arc, Mutex},
+	};
+
+	use crate::{
+		error::ReadError,
+		event::Event,
+		game::Version,
+		limit::{Exceeded, Limit},
+		read::{Reader, V1Reader, V2Reader},
+		write::{V2Writer, Writer},
+	};
+
+	use super::{apply_name_policy, decode_name, glob, read_null_terminated, seek_within, Archive, BoundsPolicy, Entry, Kind, Metadata, NameEncoding, NAME_SIZE_NULL_TERMINATOR, NamePolicy, OwnedArchive, Positioned, ReadAt, ReadOptions, ReadSeek, SharedEntry, Stats, Warning};
+
+	#[test]
+	fn test_to_name() {
+		let mut cursor = Cursor::new(vec![b'V', b'I', b'R', b'G', b'O', b'.', b'D', b'F', b'F', 0]); // VIRGO.DFF
+		let (string, raw) = read_null_terminated(&mut cursor, &ReadOptions::default()).expect("failed to read string");
+
+		assert_eq!(string, "VIRGO.DFF");
+		assert_eq!(raw[..10], *b"VIRGO.DFF\0");
+	}
+
+	#[test]
+	fn test_to_name_truncate() {
+		let mut cursor =
+			Cursor::new(vec![b'S', b'o', b'm', b'e', b'b', b'o', b'd', b'y', b'O', b'n', b'c', b'e', b'T', b'o', b'l', b'd', b'M', b'e', b'W', b'o', b'r', b'l', b'd', b'G', b'o', b'n', b'n', b'a', b'R', b'o', b'l', b'l', b'M', b'e', 0]); // SomebodyOnceToldMeWorldGonnaRollMe
+
+		let (string, _) = read_null_terminated(&mut cursor, &ReadOptions::default()).expect("failed to read string");
+
+		assert_eq!(string, "SomebodyOnceToldMeWorldG");
+	}
+
+	#[test]
+	fn test_decode_name() {
+		let bytes = [b'V', 0xCD, b'R', 0x80, 0xC3, 0xA9];
+
+		assert_eq!(decode_name(&bytes, NameEncoding::Latin1), "V\u{CD}R\u{80}\u{C3}\u{A9}");
+		assert_eq!(decode_name(&bytes, NameEncoding::Ascii), "V?R???");
+		assert_eq!(decode_name(&bytes, NameEncoding::Windows1252), "VÍR€Ã©");
+		assert_eq!(decode_name(&bytes, NameEncoding::Utf8Lossy), "V\u{FFFD}R\u{FFFD}é");
+	}
+
+	#[test]
+	fn test_name_policy() {
+		let name = String::from("VIRG\u{FF}.DFF");
+
+		assert_eq!(apply_name_policy(name.clone(), NamePolicy::PassThrough), Ok(name.clone()));
+		assert_eq!(apply_name_policy(name.clone(), NamePolicy::Replace), Ok(String::from("VIRG?.DFF")));
+		assert_eq!(apply_name_policy(name.clone(), NamePolicy::Reject), Err(String::from("VIRG?.DFF")));
+		assert_eq!(apply_name_policy(String::from("VIRGO.DFF"), NamePolicy::Reject), Ok(String::from("VIRGO.DFF")));
+	}
+
+	#[test]
+	fn test_read_v2_name_policy() {
+		let mut bytes = include_bytes!("../test/v2.img").to_vec();
+
+		bytes[16] = 0xFF;
+
+		let mut img = Cursor::new(&bytes);
+		let archive: Archive<_> = V2Reader::new(&mut img)
+			.with_options(ReadOptions {
+				name_policy: NamePolicy::Replace,
+				..Default::default()
+			})
+			.read()
+			.expect("failed to read archive");
+
+		assert_eq!(archive.get(0).expect("expected first entry").name, "?IRGO.DFF");
+		assert_eq!(archive.get(0).expect("expected first entry").raw_name.map(|raw| raw[..4] == *b"\xFFIRG"), Some(true));
+		assert_eq!(archive.get(0).expect("expected first entry").raw_name.map(|raw| raw[9..] == bytes[25..40]), Some(true));
+
+		let mut img = Cursor::new(&bytes);
+		let result = V2Reader::new(&mut img)
+			.with_options(ReadOptions {
+				name_policy: NamePolicy::Reject,
+				..Default::default()
+			})
+			.read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::InvalidName {
+				index: 0,
+				offset: 16,
+				..
+			})
+		));
+	}
+
+	#[test]
+	fn test_read_lenient() {
+		let mut bytes = include_bytes!("../test/v2.img").to_vec();
+
+		bytes[16] = 0xFF;
+
+		let options = ReadOptions {
+			name_policy: NamePolicy::Reject,
+			lenient: true,
+			..Default::default()
+		};
+
+		let mut img = Cursor::new(&bytes);
+		let archive: Archive<_> = V2Reader::new(&mut img).with_options(options.clone()).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 2);
+		assert_eq!(archive.get(0).map(|entry| entry.name.as_str()), Some("LANDSTAL.DFF"));
+		assert!(matches!(archive.warnings()[0], Warning::SkippedEntry { index: 0, .. }));
+
+		// Reading stops at a truncated directory, retaining the entries before it.
+
+		let mut img = Cursor::new(&include_bytes!("../test/v2.img")[0..44]);
+		let archive: Archive<_> = V2Reader::new(&mut img).with_options(options).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 1);
+		assert_eq!(archive.get(0).map(|entry| entry.name.as_str()), Some("VIRGO.DFF"));
+		assert!(matches!(archive.warnings().last(), Some(Warning::SkippedEntry { index: 1, .. })));
+	}
+
+	#[test]
+	fn test_read_strict() {
+		let options = ReadOptions {
+			strict: true,
+			..Default::default()
+		};
+
+		let mut dir = Cursor::new(&include_bytes!("../test/v1.dir")[0..40]);
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let result = V1Reader::new(&mut dir, &mut img).with_options(options.clone()).read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::TruncatedDirectory {
+				index: 1,
+				offset: 40,
+			})
+		));
+
+		let mut img = Cursor::new(&include_bytes!("../test/v2.img")[0..50]);
+		let result = V2Reader::new(&mut img).with_options(options).read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::TruncatedDirectory {
+				index: 1,
+				offset: 48,
+			})
+		));
+
+		// Without strict reading, the truncated name is read as far as it goes.
+
+		let mut img = Cursor::new(&include_bytes!("../test/v2.img")[0..50]);
+		let entry = V2Reader::new(&mut img).entries().expect("failed to read header").nth(1).expect("expected entry").expect("failed to read entry");
+
+		assert_eq!(entry.name, "LA");
+	}
+
+	#[test]
+	fn test_read_bounds() {
+		let mut options = ReadOptions {
+			bounds: BoundsPolicy::Warn,
+			..Default::default()
+		};
+
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).with_options(options.clone()).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 3);
+		assert_eq!(
+			archive.warnings().last(),
+			Some(&Warning::OutOfBounds {
+				index: 2,
+				end: 22528,
+				len: 6144,
+			})
+		);
+
+		options.bounds = BoundsPolicy::Reject;
+
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+		let result = V1Reader::new(&mut dir, &mut img).with_options(options).read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::OutOfBounds {
+				index: 1,
+				..
+			})
+		));
+
+		// Entries are not checked by default.
+
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert!(!archive.warnings().iter().any(|warning| matches!(warning, Warning::OutOfBounds { .. })));
+	}
+
+	#[test]
+	fn test_read_v1() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 3);
+
+		let virgo = archive.get(0).expect("expected first entry");
+		let landstal = archive.get(1).expect("expected second entry");
+		let test = archive.get(2).expect("expected third entry");
+
+		assert_eq!(virgo.name, "VIRGO.DFF");
+		assert_eq!(virgo.offset, 0);
+		assert_eq!(virgo.length, 1);
+
+		assert_eq!(landstal.name, "LANDSTAL.DFF");
+		assert_eq!(landstal.offset, 1);
+		assert_eq!(landstal.length, 2);
+
+		assert_eq!(test.name, "abcdefghijklmnopqrstuvwx");
+		assert_eq!(test.offset, 3);
+		assert_eq!(test.length, 8);
+	}
+
+	#[test]
+	fn test_read_v1_entry() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+		let mut virgo = archive.open(0).expect("expected first entry");
+
+		let mut buf = Vec::new();
+		let len = virgo.read_to_end(&mut buf).expect("failed to read entry");
+
+		assert_eq!(buf[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'1']); // Virgo-v1
+		assert_eq!(len, 2048);
+	}
+
+	#[test]
+	fn test_read_v2() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 3);
+
+		let virgo = archive.get(0).expect("expected first entry");
+		let landstal = archive.get(1).expect("expected second entry");
+		let longer = archive.get(2).expect("expected third entry");
+
+		assert_eq!(virgo.name, "VIRGO.DFF");
+		assert_eq!(virgo.offset, 1);
+		assert_eq!(virgo.length, 1);
+
+		assert_eq!(landstal.name, "LANDSTAL.DFF");
+		assert_eq!(landstal.offset, 2);
+		assert_eq!(landstal.length, 1);
+
+		assert_eq!(longer.name, "abcdefghijklmnopqrstuvwx");
+		assert_eq!(longer.offset, 3);
+		assert_eq!(longer.length, 8);
+	}
+
+	#[test]
+	fn test_read_v2_entry() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let mut virgo = archive.open(0).expect("expected first entry");
+
+		let mut buf = Vec::new();
+		let len = virgo.read_to_end(&mut buf).expect("failed to read entry");
+
+		assert_eq!(buf[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'2']); // Virgo-v2
+		assert_eq!(len, 2048);
+	}
+
+	#[test]
+	fn test_open_checked() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert!(archive.open_checked(0).is_ok());
+		assert!(matches!(archive.open_checked(3), Err(ReadError::InvalidIndex(3))));
+		assert!(matches!(
+			archive.open_checked(2),
+			Err(ReadError::OutOfBounds {
+				index: 2,
+				end: 22528,
+				len: 6144,
+				..
+			})
+		));
+	}
+
+	#[test]
+	fn test_read_entry_partial() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+		let mut entry = archive.open(0).expect("expected first entry");
+
+		let mut buf = [0; 1024];
+		let num = entry.read(&mut buf).expect("failed to read entry first time");
+
+		assert_eq!(buf[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'1']); // Virgo-v1
+		assert_eq!(num, 1024);
+
+		let num = entry.read(&mut buf).expect("failed to read entry second time");
+
+		assert_eq!(num, 1024);
+
+		let num = entry.read(&mut buf);
+
+		assert!(matches!(num, Ok(0)));
+	}
+
+	#[test]
+	fn test_read_v1_trailing() {
+		let mut dir = Cursor::new(&include_bytes!("../test/v1.dir")[0..72]);
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 2);
+		assert_eq!(archive.trailing(), Some(64));
+		assert_eq!(
+			archive.metadata(),
+			Metadata {
+				version: Version::V1,
+				declared: None,
+				entries: 2,
+				directory_sectors: 1,
+				first_data_sector: Some(0),
+				trailing: Some(64),
+			}
+		);
+	}
+
+	#[test]
+	fn test_archive_index() {
+		let index = {
+			let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+			let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+			archive.to_index()
+		};
+
+		assert_eq!(index.version(), Version::V2);
+		assert_eq!(index.declared(), Some(3));
+		assert_eq!(index.len(), 3);
+		assert_eq!(index.clone(), index);
+
+		// Bind the index to the archive once more, reading an entry.
+
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive = index.clone().bind(&mut img);
+		let mut buf = [0; 8];
+
+		archive.open(0).expect("expected entry").read_exact(&mut buf).expect("failed to read entry");
+
+		assert_eq!(&buf, b"Virgo-v2");
+		assert_eq!(archive.metadata().version, Version::V2);
+		assert_eq!(archive.to_index(), index);
+	}
+
+	#[test]
+	fn test_stats() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let stats = archive.stats();
+
+		assert_eq!(
+			stats,
+			Stats {
+				entries: 3,
+				bytes: 20480,
+				sectors: 10,
+				wasted_sectors: 0,
+				largest: Some(2),
+				smallest: Some(0),
+			}
+		);
+		assert_eq!(stats.to_string(), "3 entries, 20.0 KiB in 10 sectors (0 wasted)");
+
+		// Leave a gap of two sectors between the entries.
+
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let archive = Archive::from_entries(
+			&mut img,
+			vec![Entry::new("VIRGO.DFF", 0, 1).expect("failed to create entry"), Entry::new("LANDSTAL.DFF", 3, 1).expect("failed to create entry")],
+		);
+
+		assert_eq!(archive.stats().wasted_sectors, 2);
+		assert_eq!(archive.stats().largest, Some(0));
+	}
+
+	#[test]
+	fn test_metadata() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(
+			archive.metadata(),
+			Metadata {
+				version: Version::V2,
+				declared: Some(3),
+				entries: 3,
+				directory_sectors: 1,
+				first_data_sector: Some(1),
+				trailing: None,
+			}
+		);
+	}
+
+	#[test]
+	fn test_read_limits() {
+		let mut bytes = include_bytes!("../test/v2.img").to_vec();
+
+		bytes[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+
+		let mut img = Cursor::new(&bytes);
+		let result = V2Reader::new(&mut img)
+			.with_options(ReadOptions {
+				max_entries: Some(16384),
+				..Default::default()
+			})
+			.read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::TooManyEntries {
+				max: 16384,
+				..
+			})
+		));
+
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let result = V1Reader::new(&mut dir, &mut img)
+			.with_options(ReadOptions {
+				max_directory_size: Some(64),
+				..Default::default()
+			})
+			.read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::DirectoryTooLarge {
+				size: 96,
+				max: 64,
+			})
+		));
+	}
+
+	#[test]
+	fn test_read_unclaimed() {
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 1).expect("failed to create writer");
+
+		writer.write("VIRGO.DFF", &mut [1; 2048].as_slice()).expect("failed to write entry");
+		writer.finish().expect("failed to finish archive");
+
+		// Hide some data after the entry, separated by a sector of padding.
+
+		let mut bytes = img.into_inner();
+
+		bytes.extend([0xEE; 2048]);
+		bytes.extend([0; 2048]);
+		bytes.extend([0xEE; 16]);
+
+		let options = ReadOptions {
+			unclaimed: true,
+			..Default::default()
+		};
+
+		let mut img = Cursor::new(&bytes);
+		let archive: Archive<_> = V2Reader::new(&mut img).with_options(options.clone()).read().expect("failed to read archive");
+
+		assert_eq!(archive.unclaimed().len(), 2);
+		assert_eq!((archive.unclaimed()[0].offset, archive.unclaimed()[0].data.len()), (4096, 2048));
+		assert_eq!((archive.unclaimed()[1].offset, archive.unclaimed()[1].data.len()), (8192, 16));
+
+		// Reading fails should the data exceed the limit.
+
+		let mut img = Cursor::new(&bytes);
+		let result = V2Reader::new(&mut img)
+			.with_options(ReadOptions {
+				max_unclaimed: Some(2048),
+				..options
+			})
+			.read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::UnclaimedTooLarge {
+				size: 2064,
+				max: 2048,
+			})
+		));
+	}
+
+	#[test]
+	fn test_read_warnings() {
+		let mut bytes = include_bytes!("../test/v2.img").to_vec();
+
+		bytes[14] = 1;
+
+		let mut img = Cursor::new(&bytes);
+
+		let warnings = Arc::new(Mutex::new(Vec::new()));
+		let observed = Arc::clone(&warnings);
+
+		let archive: Archive<_> = V2Reader::new(&mut img)
+			.with_observer(move |event: &Event| {
+				if let Event::Warning(warning) = event {
+					observed.lock().expect("failed to lock warnings").push((*warning).clone());
+				}
+			})
+			.read()
+			.expect("failed to read archive");
+
+		// The second length field is recorded as the stream length, rather than being warned about.
+
+		assert_eq!(archive.get(0).and_then(|entry| entry.stream_len), Some(1));
+
+		let expected = vec![
+			Warning::UnterminatedName {
+				index: 2,
+				name: String::from("abcdefghijklmnopqrstuvwx"),
+			},
+		];
+
+		assert_eq!(archive.warnings(), expected);
+		assert_eq!(*warnings.lock().expect("failed to lock warnings"), expected);
+	}
+
+	#[test]
+	fn test_read_limit_exceeded() {
+		let mut dir = include_bytes!("../test/v1.dir").to_vec();
+
+		dir[32..36].copy_from_slice(&u32::MAX.to_le_bytes());
+
+		let mut dir = Cursor::new(dir);
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let result: Result<Archive<_>, _> = V1Reader::new(&mut dir, &mut img).read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::LimitExceeded {
+				index: 1,
+				exceeded: Exceeded {
+					limit: Limit::Offset,
+					..
+				},
+			})
+		));
+	}
+
+	#[test]
+	fn test_by_name() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.get_by_name("landstal.dff").map(|entry| entry.offset), Some(2));
+		assert!(archive.get_by_name("kuruma.dff").is_none());
+		assert_eq!(archive.position_of("LANDSTAL.dff"), Some(1));
+		assert!(archive.contains("virgo.DFF"));
+		assert!(!archive.contains("kuruma.dff"));
+
+		let mut data = Vec::new();
+
+		archive.open_by_name("Virgo.dff").expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert_eq!(data[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'2']);
+		assert!(archive.open_by_name("kuruma.dff").is_none());
+	}
+
+	#[test]
+	fn test_kind() {
+		assert_eq!(Kind::detect(&[0x10, 0, 0, 0, 0, 0, 0, 0]), Kind::Dff);
+		assert_eq!(Kind::detect(&[0x16, 0, 0, 0]), Kind::Txd);
+		assert_eq!(Kind::detect(b"COL3"), Kind::Col);
+		assert_eq!(Kind::detect(b"ANP3"), Kind::Ifp);
+		assert_eq!(Kind::detect(b"bnry"), Kind::Ipl);
+		assert_eq!(Kind::detect(b"COL"), Kind::Unknown);
+
+		let mut bytes = include_bytes!("../test/v2.img").to_vec();
+
+		bytes[4096..4100].copy_from_slice(b"COL2");
+
+		let mut img = Cursor::new(bytes);
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.kind(0).expect("failed to detect kind"), Some(Kind::Unknown));
+		assert_eq!(archive.kind(1).expect("failed to detect kind"), Some(Kind::Col));
+		assert_eq!(archive.kind(3).expect("failed to detect kind"), None);
+
+		assert_eq!(archive.get(1).and_then(|entry| entry.attributes.kind), Some(Kind::Col));
+		assert_eq!(archive.get(2).and_then(|entry| entry.attributes.kind), None);
+	}
+
+	#[test]
+	fn test_trait_objects() {
+		let mut dir: Box<dyn Read> = Box::new(Cursor::new(include_bytes!("../test/v1.dir")));
+		let mut img: Box<dyn ReadSeek> = Box::new(Cursor::new(include_bytes!("../test/v1.img")));
+
+		// Read from both boxed sources and borrowed trait objects.
+
+		let mut archive: Archive<_> = V1Reader::new(dir.as_mut(), &mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.len(), 3);
+		assert!(archive.read_entry(0).expect("failed to read entry").is_some());
+
+		let mut cursor = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut img: &mut dyn ReadSeek = &mut cursor;
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert!(archive.read_entry(0).expect("failed to read entry").is_some_and(|data| data.starts_with(b"Virgo-v2")));
+	}
+
+	#[test]
+	fn test_read_entry() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let data = archive.read_entry(0).expect("failed to read entry").expect("expected entry");
+
+		assert_eq!(data.len(), 2048);
+		assert!(data.starts_with(b"Virgo-v2"));
+
+		assert_eq!(archive.read_entry_by_name("landstal.dff").expect("failed to read entry").map(|data| data.len()), Some(2048));
+		assert!(archive.read_entry_by_name("kuruma.dff").expect("failed to read entry").is_none());
+		assert!(archive.read_entry(3).expect("failed to read entry").is_none());
+	}
+
+	#[test]
+	fn test_iter() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut iter = archive.iter();
+
+		assert_eq!(iter.len(), 3);
+		assert_eq!(iter.next_back().map(|entry| entry.offset), Some(3));
+		assert_eq!(iter.len(), 2);
+
+		let names: Vec<&str> = archive.iter().rev().map(|entry| entry.name.as_str()).collect();
+
+		assert_eq!(names, ["abcdefghijklmnopqrstuvwx", "LANDSTAL.DFF", "VIRGO.DFF"]);
+		assert_eq!(archive.iter_sorted_by_name().len(), 3);
+	}
+
+	#[test]
+	fn test_iter_sorted() {
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let archive = Archive::from_entries(
+			&mut img,
+			vec![
+				Entry::new("b.dff", 4, 1).expect("failed to create entry"),
+				Entry::new("C.dff", 1, 1).expect("failed to create entry"),
+				Entry::new("a.dff", 1, 2).expect("failed to create entry"),
+			],
+		);
+
+		let names: Vec<&str> = archive.iter_sorted_by_name().map(|entry| entry.name.as_str()).collect();
+
+		assert_eq!(names, ["a.dff", "b.dff", "C.dff"]);
+
+		let names: Vec<&str> = archive.iter_sorted_by_offset().map(|entry| entry.name.as_str()).collect();
+
+		assert_eq!(names, ["C.dff", "a.dff", "b.dff"]);
+
+		let names: Vec<&str> = archive.iter_sorted(|a, b| b.length.cmp(&a.length)).map(|entry| entry.name.as_str()).collect();
+
+		assert_eq!(names, ["a.dff", "b.dff", "C.dff"]);
 	}
-}
 
-impl<R> Read for OpenEntry<'_, R>
-where
-	R: Read + Seek,
-{
-	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-		// Check if we have already reached the end of the entry.
+	#[test]
+	fn test_duplicates() {
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let archive = Archive::from_entries(
+			&mut img,
+			vec![
+				Entry::new("VIRGO.DFF", 1, 1).expect("failed to create entry"),
+				Entry::new("LANDSTAL.DFF", 2, 1).expect("failed to create entry"),
+				Entry::new("virgo.dff", 3, 1).expect("failed to create entry"),
+				Entry::new("Virgo.dff", 4, 1).expect("failed to create entry"),
+			],
+		);
+
+		assert_eq!(archive.duplicates(), vec![vec![0, 2, 3]]);
+	}
 
-		if self.position >= self.length {
-			return Ok(0);
-		}
+	#[test]
+	fn test_build_index() {
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut archive = Archive::from_entries(
+			&mut img,
+			vec![
+				Entry::new("VIRGO.DFF", 1, 1).expect("failed to create entry"),
+				Entry::new("LANDSTAL.DFF", 2, 1).expect("failed to create entry"),
+				Entry::new("virgo.dff", 3, 1).expect("failed to create entry"),
+			],
+		);
+
+		archive.build_index();
+
+		assert!(archive.is_indexed());
+		assert_eq!(archive.position_of("Virgo.DFF"), Some(0));
+		assert_eq!(archive.get_by_name("landstal.dff").map(|entry| entry.offset), Some(2));
+		assert_eq!(archive.position_of("MISSING.DFF"), None);
+		assert!(archive.contains("virgo.dff"));
+		assert!(!archive.contains("MISSING.DFF"));
+
+		// Modifying the entries should discard the index, such that lookups remain correct.
+
+		archive.entries_mut()[0].name = "KURUMA.DFF".to_owned();
+
+		assert!(!archive.is_indexed());
+		assert_eq!(archive.position_of("VIRGO.DFF"), Some(2));
+	}
 
-		// Seek to the start of the entry including any currently read bytes.
+	#[test]
+	fn test_find() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
 
-		self.inner.seek(io::SeekFrom::Start(self.offset + self.position))?;
+		assert_eq!(archive.find("*.dff"), [0, 1]);
+		assert_eq!(archive.find("landsta?.DFF"), [1]);
+		assert_eq!(archive.find("*"), [0, 1, 2]);
+		assert!(archive.find("*.txd").is_empty());
+	}
 
-		// Calculate the maximum possible number of bytes to read for the entry, to forbid reading beyond it.
-		// Includes the number of bytes already read, honouring the length of the entry and the length of the buffer.
+	#[test]
+	fn test_glob() {
+		assert!(glob("*", ""));
+		assert!(glob("*.txd", "LOADSC0.TXD"));
+		assert!(glob("*a*a*", "banana"));
+		assert!(glob("v?rgo.*", "VIRGO.DFF"));
+		assert!(glob("virgo.dff", "Virgo.Dff"));
+
+		assert!(!glob("*.txd", "VIRGO.DFF"));
+		assert!(!glob("?", ""));
+		assert!(!glob("*a*b", "banana"));
+		assert!(!glob("virgo", "VIRGO.DFF"));
+	}
 
-		let length = (self.length - self.position.min(self.length)).min(buf.len() as u64) as usize;
-		let offset = self.inner.read(&mut buf[0..length])?;
+	#[test]
+	fn test_into_entries() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
 
-		self.position += offset as u64;
+		let names: Vec<&str> = archive.entries().iter().map(|entry| entry.name.as_str()).collect();
 
-		Ok(offset)
+		assert_eq!(names, ["VIRGO.DFF", "LANDSTAL.DFF", "abcdefghijklmnopqrstuvwx"]);
+
+		let entries = archive.into_entries();
+
+		assert_eq!(entries.len(), 3);
+		assert_eq!(entries[0].name, "VIRGO.DFF");
 	}
-}
 
-impl<I> Hash for Archive<'_, I> {
-	fn hash<H: hash::Hasher>(&self, state: &mut H) {
-		self.entries.hash(state);
+	#[test]
+	fn test_into_iter() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut offsets = Vec::new();
+
+		for entry in &archive {
+			offsets.push(entry.offset);
+		}
+
+		assert_eq!(offsets, [1, 2, 3]);
+
+		let names: Vec<String> = archive.into_iter().map(|entry| entry.name).collect();
+
+		assert_eq!(names[1], "LANDSTAL.DFF");
 	}
-}
 
-impl<I> PartialEq for Archive<'_, I> {
-	fn eq(&self, other: &Self) -> bool {
-		self.entries == other.entries
+	#[test]
+	fn test_fork() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut inner = archive.inner_mut().clone();
+		let mut fork = archive.fork(&mut inner);
+
+		assert!(fork == archive);
+
+		// Read from both independently.
+
+		let mut first = Vec::new();
+		let mut second = Vec::new();
+
+		fork.open(0).expect("failed to open entry").read_to_end(&mut first).expect("failed to read entry");
+		archive.open(0).expect("failed to open entry").read_to_end(&mut second).expect("failed to read entry");
+
+		assert_eq!(first, second);
 	}
-}
 
-impl<I> PartialOrd for Archive<'_, I> {
-	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-		self.entries.partial_cmp(&other.entries)
+	#[test]
+	fn test_owned_archive() {
+		fn open() -> OwnedArchive<Cursor<&'static [u8]>> {
+			OwnedArchive::read(Cursor::new(include_bytes!("../test/v2.img").as_slice()), |img| V2Reader::new(img).read()).expect("failed to read archive")
+		}
+
+		let mut owned = open();
+
+		assert_eq!(owned.len(), 3);
+		assert_eq!(owned.get(0).map(|entry| entry.name.as_str()), Some("VIRGO.DFF"));
+
+		// Borrow the archive, reading an entry and modifying the entries.
+
+		{
+			let mut archive = owned.archive();
+			let mut data = Vec::new();
+
+			archive.open(1).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+			archive.entries_mut()[0].name = "KURUMA.DFF".to_owned();
+
+			assert_eq!(data.len(), 2048);
+		}
+
+		assert_eq!(owned.get(0).map(|entry| entry.name.as_str()), Some("KURUMA.DFF"));
+		assert_eq!(owned.warnings().len(), 1);
+		assert_eq!(owned.into_inner().get_ref().len(), 6144);
 	}
-}
 
-fn read_null_terminated<T>(inner: &mut T) -> Result<String, io::Error>
-where
-	T: Read,
-{
-	// Read the bytes for the string.
+	#[test]
+	fn test_owned_archive_clone() {
+		let owned = OwnedArchive::read(Cursor::new(include_bytes!("../test/v2.img").to_vec()), |img| V2Reader::new(img).read()).expect("failed to read archive");
+		let mut clone = owned.clone();
 
-	let mut buf = [0; NAME_SIZE_NULL_TERMINATOR];
+		// Modify the clone, which leaves the original unchanged.
 
-	#[allow(clippy::unused_io_amount)]
-	inner.read(&mut buf)?;
+		{
+			let mut archive = clone.archive();
+			let mut data = Vec::new();
 
-	// Determine the position of the null terminator and build a string from it.
+			archive.open(1).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+			archive.entries_mut()[0].name = "KURUMA.DFF".to_owned();
 
-	let pos = buf.iter().position(|&b| b == NULL_TERMINATOR).unwrap_or(buf.len()).min(NAME_SIZE_NULL_TERMINATOR);
-	let str = buf.iter().map(|&b| char::from(b)).take(pos).collect();
+			assert_eq!(data.len(), 2048);
+		}
 
-	Ok(str)
-}
+		assert_eq!(clone.get(0).map(|entry| entry.name.as_str()), Some("KURUMA.DFF"));
+		assert_eq!(owned.get(0).map(|entry| entry.name.as_str()), Some("VIRGO.DFF"));
+		assert_eq!(clone.warnings(), owned.warnings());
+	}
 
-#[cfg(test)]
-mod tests {
-	use std::io::{Cursor, Read};
+	#[test]
+	fn test_owned_archive_threads() {
+		fn assert_send_sync<T: Send + Sync>() {}
+
+		assert_send_sync::<Archive<'_, std::fs::File>>();
+		assert_send_sync::<OwnedArchive<std::fs::File>>();
+		assert_send_sync::<SharedEntry<'_, std::fs::File>>();
+
+		let owned = OwnedArchive::read(Cursor::new(include_bytes!("../test/v2.img").to_vec()), |img| V2Reader::new(img).read()).expect("failed to read archive");
+
+		// Read each of the entries from a separate thread.
+
+		let lengths: Vec<usize> = std::thread::scope(|scope| {
+			let handles: Vec<_> = (0..2)
+				.map(|index| {
+					let owned = &owned;
+
+					scope.spawn(move || {
+						let mut data = Vec::new();
 
-	use crate::read::{Reader, V1Reader, V2Reader};
+						owned.open_shared(index).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
 
-	use super::{read_null_terminated, Archive};
+						data.len()
+					})
+				})
+				.collect();
+
+			handles.into_iter().map(|handle| handle.join().expect("failed to join thread")).collect()
+		});
+
+		assert_eq!(lengths, [2048, 2048]);
+		assert!(owned.open_shared(3).is_none());
+	}
 
 	#[test]
-	fn test_to_name() {
-		let mut cursor = Cursor::new(vec![b'V', b'I', b'R', b'G', b'O', b'.', b'D', b'F', b'F', 0]); // VIRGO.DFF
-		let string = read_null_terminated(&mut cursor).expect("failed to read string");
+	fn test_into_inner() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
 
-		assert_eq!(string, "VIRGO.DFF");
+		let inner = archive.into_inner();
+
+		assert_eq!(inner.get_ref().len(), 6144);
 	}
 
 	#[test]
-	fn test_to_name_truncate() {
-		let mut cursor =
-			Cursor::new(vec![b'S', b'o', b'm', b'e', b'b', b'o', b'd', b'y', b'O', b'n', b'c', b'e', b'T', b'o', b'l', b'd', b'M', b'e', b'W', b'o', b'r', b'l', b'd', b'G', b'o', b'n', b'n', b'a', b'R', b'o', b'l', b'l', b'M', b'e', 0]); // SomebodyOnceToldMeWorldGonnaRollMe
+	fn test_content_eq() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img").to_vec());
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
 
-		let string = read_null_terminated(&mut cursor).expect("failed to read string");
+		let mut inner = archive.inner_mut().clone();
+		let mut fork = archive.fork(&mut inner);
 
-		assert_eq!(string, "SomebodyOnceToldMeWorldG");
+		assert!(archive.content_eq(&mut fork).expect("failed to compare archives"));
+
+		// Modify the data of the second entry, which leaves the entries themselves unchanged.
+
+		let mut modified = archive.inner_mut().clone();
+		modified.get_mut()[4096] ^= 0xFF;
+
+		let mut fork = archive.fork(&mut modified);
+
+		assert!(fork == archive);
+		assert!(!archive.content_eq(&mut fork).expect("failed to compare archives"));
+
+		assert!(archive.entry_eq(0, &mut fork, 0).expect("failed to compare entries"));
+		assert!(!archive.entry_eq(1, &mut fork, 1).expect("failed to compare entries"));
+		assert!(!archive.entry_eq(0, &mut fork, 1).expect("failed to compare entries"));
+		assert!(!archive.entry_eq(0, &mut fork, 3).expect("failed to compare entries"));
 	}
 
 	#[test]
-	fn test_read_v1() {
-		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
-		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+	fn test_entry_new() {
+		let mut entry = Entry::new("VIRGO.DFF", 1, 2).expect("failed to create entry");
 
-		let archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+		assert_eq!(entry.name, "VIRGO.DFF");
+		assert_eq!(entry.offset, 1);
+		assert_eq!(entry.length, 2);
 
-		assert_eq!(archive.len(), 3);
+		assert_eq!(entry.offset_sectors(), 1);
+		assert_eq!(entry.offset_bytes(), 2048);
+		assert_eq!(entry.len_sectors(), 2);
+		assert_eq!(entry.len_bytes(), 4096);
+		assert_eq!(entry.end_sectors(), 3);
+		assert_eq!(entry.end_bytes(), 6144);
+		assert_eq!(entry.range_bytes(), 2048..6144);
 
-		let virgo = archive.get(0).expect("expected first entry");
-		let landstal = archive.get(1).expect("expected second entry");
-		let test = archive.get(2).expect("expected third entry");
+		assert_eq!(entry.extension(), Some("DFF"));
+		assert_eq!(entry.stem(), "VIRGO");
+		assert!(entry.has_extension("dff"));
+		assert!(!entry.has_extension("txd"));
 
-		assert_eq!(virgo.name, "VIRGO.DFF");
-		assert_eq!(virgo.offset, 0);
-		assert_eq!(virgo.length, 1);
+		let bare = Entry::new("LOADSCS", 1, 2).expect("failed to create entry");
 
-		assert_eq!(landstal.name, "LANDSTAL.DFF");
-		assert_eq!(landstal.offset, 1);
-		assert_eq!(landstal.length, 2);
+		assert_eq!(bare.extension(), None);
+		assert_eq!(bare.stem(), "LOADSCS");
 
-		assert_eq!(test.name, "abcdefghijklmnopqrstuvwx");
-		assert_eq!(test.offset, 3);
-		assert_eq!(test.length, 8);
+		assert!(Entry::new("abcdefghijklmnopqrstuvwx", 1, 2).is_err());
+		assert!(Entry::new("VIRGO.DFF", u32::MAX as u64, 1).is_err());
+
+		assert!(entry.set_name("abcdefghijklmnopqrstuvwx").is_err());
+		assert_eq!(entry.name, "VIRGO.DFF");
+
+		entry.raw_name = Some([0; NAME_SIZE_NULL_TERMINATOR]);
+		entry.set_name("ZEBRA.DFF").expect("failed to set name");
+
+		assert_eq!(entry.raw_name, None);
+
+		entry.set_range(4, 8).expect("failed to set range");
+
+		assert_eq!((entry.offset, entry.length), (4, 8));
 	}
 
 	#[test]
-	fn test_read_v1_entry() {
-		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
-		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+	fn test_seek_within() {
+		assert_eq!(seek_within(4, 8, SeekFrom::Start(2)).expect("failed to seek"), 2);
+		assert_eq!(seek_within(4, 8, SeekFrom::Current(-4)).expect("failed to seek"), 0);
+		assert_eq!(seek_within(4, 8, SeekFrom::End(4)).expect("failed to seek"), 12);
 
-		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
-		let mut virgo = archive.open(0).expect("expected first entry");
+		assert!(seek_within(4, 8, SeekFrom::Current(-5)).is_err());
+		assert!(seek_within(4, u64::MAX, SeekFrom::End(1)).is_err());
+	}
 
-		let mut buf = Vec::new();
-		let len = virgo.read_to_end(&mut buf).expect("failed to read entry");
+	#[test]
+	fn test_entry_ord() {
+		let entry = |name: &str, offset, length| Entry::new(name, offset, length).expect("failed to create entry");
 
-		assert_eq!(buf[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'1']); // Virgo-v1
-		assert_eq!(len, 2048);
+		let mut entries = vec![entry("VIRGO.DFF", 2, 1), entry("LANDSTAL.DFF", 2, 1), entry("ZEBRA.DFF", 1, 4), entry("LANDSTAL.DFF", 2, 0)];
+
+		entries.sort();
+
+		assert_eq!(entries, vec![entry("ZEBRA.DFF", 1, 4), entry("LANDSTAL.DFF", 2, 0), entry("LANDSTAL.DFF", 2, 1), entry("VIRGO.DFF", 2, 1)]);
+
+		// Entries differing only by their raw names or attributes should be neither unequal nor ordered differently.
+
+		let mut annotated = entry("VIRGO.DFF", 2, 1);
+
+		annotated.raw_name = Some([0; NAME_SIZE_NULL_TERMINATOR]);
+		annotated.attributes.crc32 = Some(1);
+		annotated.attributes.kind = Some(Kind::Dff);
+
+		assert_eq!(annotated, entry("VIRGO.DFF", 2, 1));
+		assert_eq!(annotated.cmp(&entry("VIRGO.DFF", 2, 1)), cmp::Ordering::Equal);
 	}
 
 	#[test]
-	fn test_read_v2() {
+	fn test_archive_display() {
 		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.to_string(), "V2 archive (3 entries, 20.0 KiB)");
+		assert_eq!(format!("{:?}", archive), "Archive { version: V2, entries: 3, size: 20480, unclaimed: 0, warnings: 1, .. }");
+	}
+
+	#[test]
+	fn test_entry_display() {
+		let entry = |length| Entry::new("VIRGO.DFF", 1, length).expect("failed to create entry");
 
+		assert_eq!(entry(0).to_string(), "VIRGO.DFF (offset 2048, 0 B)");
+		assert_eq!(entry(1).to_string(), "VIRGO.DFF (offset 2048, 2.0 KiB)");
+		assert_eq!(entry(768).to_string(), "VIRGO.DFF (offset 2048, 1.5 MiB)");
+		assert_eq!(entry(1048576).to_string(), "VIRGO.DFF (offset 2048, 2.0 GiB)");
+	}
+
+	#[test]
+	fn test_read_invalid_character() {
+		let mut bytes = include_bytes!("../test/v2.img").to_vec();
+
+		bytes[48] = b'/';
+
+		let mut img = Cursor::new(&bytes);
 		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
 
-		assert_eq!(archive.len(), 3);
+		assert!(archive.warnings().contains(&Warning::InvalidCharacter {
+			index: 1,
+			character: '/',
+		}));
+	}
 
-		let virgo = archive.get(0).expect("expected first entry");
-		let landstal = archive.get(1).expect("expected second entry");
-		let longer = archive.get(2).expect("expected third entry");
+	#[test]
+	fn test_read_v2_excessive_count() {
+		let mut img = Cursor::new([0x56, 0x45, 0x52, 0x32, 0xFF, 0xFF, 0xFF, 0xFF]);
 
-		assert_eq!(virgo.name, "VIRGO.DFF");
-		assert_eq!(virgo.offset, 1);
-		assert_eq!(virgo.length, 1);
+		let result: Result<Archive<_>, _> = V2Reader::new(&mut img).read();
 
-		assert_eq!(landstal.name, "LANDSTAL.DFF");
-		assert_eq!(landstal.offset, 2);
-		assert_eq!(landstal.length, 1);
+		assert!(matches!(
+			result,
+			Err(ReadError::EntryError {
+				index: 0,
+				..
+			})
+		));
+	}
 
-		assert_eq!(longer.name, "abcdefghijklmnopqrstuvwx");
-		assert_eq!(longer.offset, 3);
-		assert_eq!(longer.length, 8);
+	#[test]
+	fn test_read_v2_truncated() {
+		let mut img = Cursor::new(&include_bytes!("../test/v2.img")[0..44]);
+
+		let result = V2Reader::new(&mut img).read();
+
+		assert!(matches!(
+			result,
+			Err(ReadError::EntryError {
+				index: 1,
+				name: None,
+				offset: 44,
+				..
+			})
+		));
 	}
 
 	#[test]
-	fn test_read_v2_entry() {
+	fn test_open_entry_seek() {
 		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
-
 		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
-		let mut virgo = archive.open(0).expect("expected first entry");
 
-		let mut buf = Vec::new();
-		let len = virgo.read_to_end(&mut buf).expect("failed to read entry");
+		let mut open = archive.open(0).expect("expected entry");
+		let mut buf = [0; 2];
 
-		assert_eq!(buf[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'2']); // Virgo-v2
-		assert_eq!(len, 2048);
+		// Seek within the entry, relative to each of the start, the current position and the end.
+
+		assert_eq!(open.seek(SeekFrom::Start(5)).expect("failed to seek"), 5);
+
+		open.read_exact(&mut buf).expect("failed to read entry");
+
+		assert_eq!(buf, [b'-', b'v']);
+		assert_eq!(open.seek(SeekFrom::Current(-7)).expect("failed to seek"), 0);
+		assert_eq!(open.seek(SeekFrom::End(-1)).expect("failed to seek"), 2047);
+		assert_eq!(open.seek(SeekFrom::End(1)).expect("failed to seek"), 2049);
+		assert_eq!(open.read(&mut buf).expect("failed to read entry"), 0);
+
+		assert!(open.seek(SeekFrom::Current(-2050)).is_err());
 	}
 
 	#[test]
-	fn test_read_entry_partial() {
+	fn test_open_shared() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		// Open several entries at once, reading from each in turn.
+
+		let mut virgo = archive.open_shared(0).expect("expected entry");
+		let mut landstal = archive.open_shared(1).expect("expected entry");
+
+		let mut first = [0; 5];
+		let mut second = [0; 8];
+
+		virgo.read_exact(&mut first).expect("failed to read entry");
+		landstal.read_exact(&mut second).expect("failed to read entry");
+
+		assert_eq!(&first, b"Virgo");
+		assert_eq!(second, include_bytes!("../test/v2.img")[4096..4104]);
+
+		let mut rest = Vec::new();
+
+		assert_eq!(virgo.read_to_end(&mut rest).expect("failed to read entry"), 2043);
+		assert!(archive.open_shared(3).is_none());
+	}
+
+	#[test]
+	fn test_read_exact_at() {
+		let data: Arc<[u8]> = include_bytes!("../test/v2.img").as_slice().into();
+		let mut buf = [0; 8];
+
+		data.read_exact_at(&mut buf, 2048).expect("failed to read");
+
+		assert_eq!(&buf, b"Virgo-v2");
+		assert_eq!(data.read_exact_at(&mut buf, 6140).map_err(|e| e.kind()), Err(io::ErrorKind::UnexpectedEof));
+		assert_eq!(data.read_at(&mut buf, 6144).expect("failed to read"), 0);
+	}
+
+	#[test]
+	fn test_positioned() {
+		let data: Arc<[u8]> = include_bytes!("../test/v2.img").as_slice().into();
+		let mut img = Positioned::new(data, 6144);
+
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let mut buf = [0; 8];
+
+		assert_eq!(archive.len(), 3);
+
+		archive.open_shared(0).expect("expected entry").read_exact(&mut buf).expect("failed to read entry");
+
+		assert_eq!(&buf, b"Virgo-v2");
+
+		assert_eq!(img.seek(SeekFrom::End(-4)).expect("failed to seek"), 6140);
+		assert_eq!(img.read(&mut buf).expect("failed to read"), 4);
+		assert_eq!(img.read(&mut buf).expect("failed to read"), 0);
+	}
+
+	#[test]
+	fn test_read_entries() {
 		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
 		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
 
-		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
-		let mut entry = archive.open(0).expect("expected first entry");
+		let mut entries = V1Reader::new(&mut dir, &mut img).entries();
 
-		let mut buf = [0; 1024];
-		let num = entry.read(&mut buf).expect("failed to read entry first time");
+		assert_eq!(entries.next().map(|entry| entry.expect("failed to read entry").name), Some("VIRGO.DFF".to_owned()));
+		assert_eq!(entries.count(), 2);
 
-		assert_eq!(buf[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'1']); // Virgo-v1
-		assert_eq!(num, 1024);
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let entries = V2Reader::new(&mut img).entries().expect("failed to read header");
 
-		let num = entry.read(&mut buf).expect("failed to read entry second time");
+		assert_eq!(entries.declared(), 3);
+		assert_eq!(entries.map(|entry| entry.expect("failed to read entry").offset).collect::<Vec<_>>(), [1, 2, 3]);
 
-		assert_eq!(num, 1024);
+		// Check that iteration stops after the first error.
 
-		let num = entry.read(&mut buf);
+		let mut img = Cursor::new(&include_bytes!("../test/v2.img")[0..44]);
+		let mut entries = V2Reader::new(&mut img).entries().expect("failed to read header");
 
-		assert!(matches!(num, Ok(0)));
+		assert!(entries.next().is_some_and(|entry| entry.is_ok()));
+		assert!(entries.next().is_some_and(|entry| entry.is_err()));
+		assert!(entries.next().is_none());
+	}
+
+	#[test]
+	fn test_entry_slice() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img").to_vec());
+
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let virgo = archive.entry_slice(0).expect("expected first entry");
+
+		assert_eq!(virgo[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'2']); // Virgo-v2
+		assert_eq!(virgo.len(), 2048);
+
+		assert!(archive.entry_slice(2).is_some_and(|data| data.is_empty()));
+		assert!(archive.entry_slice(3).is_none());
+	}
+
+	#[test]
+	#[cfg(feature = "mmap")]
+	fn test_mmap_archive() {
+		let img = std::fs::File::open("test/v2.img").expect("failed to open archive");
+		let archive = super::MmapArchive::map(&img, |img| V2Reader::new(img).read()).expect("failed to map archive");
+
+		assert_eq!(archive.len(), 3);
+
+		let virgo = archive.data(0).expect("expected first entry");
+
+		assert_eq!(virgo[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'2']); // Virgo-v2
+		assert_eq!(virgo.len(), 2048);
+
+		assert!(archive.data(2).is_some_and(|data| data.is_empty()));
+		assert!(archive.data(3).is_none());
+	}
+
+	#[test]
+	#[cfg(feature = "bytes")]
+	fn test_entry_bytes() {
+		let mut img = Cursor::new(bytes::Bytes::from_static(include_bytes!("../test/v2.img")));
+
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let virgo = archive.entry_bytes(0).expect("expected first entry");
+
+		assert_eq!(virgo[0..8], [b'V', b'i', b'r', b'g', b'o', b'-', b'v', b'2']); // Virgo-v2
+		assert_eq!(virgo.len(), 2048);
+
+		assert!(archive.entry_bytes(3).is_none());
 	}
 }
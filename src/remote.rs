@@ -0,0 +1,275 @@
+use std::{
+	collections::VecDeque,
+	fmt,
+	io::{self, Read},
+	sync::{Arc, Mutex},
+};
+
+use crate::read::{Positioned, ReadAt};
+
+/// Represents the size, in bytes, of each block requested from the server, such that many small reads (e.g. of the directory) are served by a single request.
+const BLOCK_SIZE: u64 = 64 * 1024;
+
+/// Represents the maximum number of blocks retained in the cache, beyond which the least recently used block is discarded.
+const CACHED_BLOCKS: usize = 32;
+
+/// Represents a file hosted on a web server, read using HTTP range requests rather than by downloading it in full.
+/// Presented as a stream via [`HttpSource::into_stream`], archives can be read from the file as from any other source, with only the directory and the entries opened being downloaded.
+/// The file is requested in blocks, which are cached, such that reading the directory of an archive takes only a handful of requests.
+pub struct HttpSource {
+	agent: ureq::Agent,
+
+	url: String,
+	length: u64,
+
+	blocks: Mutex<VecDeque<(u64, Arc<[u8]>)>>,
+}
+
+impl HttpSource {
+	/// Opens the file at the specified URL, requesting its length from the server.
+	pub fn open(url: &str) -> Result<Self, io::Error> {
+		let agent = ureq::Agent::new();
+		let response = agent.head(url).call().map_err(to_io_error)?;
+
+		let length = response
+			.header("Content-Length")
+			.and_then(|length| length.parse().ok())
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "server did not report the length of the file"))?;
+
+		Ok(Self {
+			agent,
+			url: url.to_owned(),
+			length,
+			blocks: Mutex::new(VecDeque::with_capacity(CACHED_BLOCKS)),
+		})
+	}
+
+	/// Returns the URL of the file.
+	pub fn url(&self) -> &str {
+		&self.url
+	}
+
+	/// Returns the length of the file.
+	pub fn len(&self) -> u64 {
+		self.length
+	}
+
+	/// Returns whether the file is empty.
+	pub fn is_empty(&self) -> bool {
+		self.length == 0
+	}
+
+	/// Consumes the source, presenting it as a stream from which archives can be read.
+	pub fn into_stream(self) -> Positioned<Self> {
+		let length = self.length;
+
+		Positioned::new(self, length)
+	}
+
+	/// Returns the block starting at the specified offset, from the cache if present or otherwise by requesting it from the server.
+	fn block(&self, start: u64) -> Result<Arc<[u8]>, io::Error> {
+		if let Some(block) = self.cached(start) {
+			return Ok(block);
+		}
+
+		let block: Arc<[u8]> = self.fetch(start, (start + BLOCK_SIZE).min(self.length))?.into();
+		let mut blocks = self.blocks.lock().unwrap_or_else(|e| e.into_inner());
+
+		if blocks.len() >= CACHED_BLOCKS {
+			blocks.pop_front();
+		}
+
+		blocks.push_back((start, block.clone()));
+
+		Ok(block)
+	}
+
+	/// Returns the block starting at the specified offset from the cache, if present.
+	/// The cache is ordered from the least to the most recently used block, such that the block is moved to the back.
+	fn cached(&self, start: u64) -> Option<Arc<[u8]>> {
+		let mut blocks = self.blocks.lock().unwrap_or_else(|e| e.into_inner());
+
+		let index = blocks.iter().position(|(offset, _)| *offset == start)?;
+		let cached = blocks.remove(index)?;
+		let block = cached.1.clone();
+
+		blocks.push_back(cached);
+
+		Some(block)
+	}
+
+	/// Requests the range of bytes from `start` to `end` (exclusive) from the server, failing if the server does not support range requests.
+	fn fetch(&self, start: u64, end: u64) -> Result<Vec<u8>, io::Error> {
+		let response = self.agent.get(&self.url).set("Range", &format!("bytes={}-{}", start, end - 1)).call().map_err(to_io_error)?;
+
+		// Servers ignoring the range would respond with the entire file, which is never what is wanted.
+
+		if response.status() != 206 {
+			return Err(io::Error::new(io::ErrorKind::Unsupported, "server does not support range requests"));
+		}
+
+		let mut data = Vec::with_capacity((end - start) as usize);
+
+		response.into_reader().take(end - start).read_to_end(&mut data)?;
+
+		Ok(data)
+	}
+}
+
+impl ReadAt for HttpSource {
+	/// Reads from the file via the block containing the offset, reading no further than the end of the block.
+	fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+		if buf.is_empty() || offset >= self.length {
+			return Ok(0);
+		}
+
+		let start = offset - offset % BLOCK_SIZE;
+		let block = self.block(start)?;
+
+		let position = (offset - start) as usize;
+		let length = block.len().saturating_sub(position).min(buf.len());
+
+		buf[..length].copy_from_slice(&block[position..position + length]);
+
+		Ok(length)
+	}
+}
+
+impl fmt::Debug for HttpSource {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("HttpSource").field("url", &self.url).field("length", &self.length).finish_non_exhaustive()
+	}
+}
+
+fn to_io_error(e: ureq::Error) -> io::Error {
+	match e {
+		ureq::Error::Status(416, _) => io::Error::from(io::ErrorKind::UnexpectedEof),
+		ureq::Error::Status(404, _) => io::Error::from(io::ErrorKind::NotFound),
+		e => io::Error::other(e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		io::{BufRead, BufReader, Read, Write},
+		net::{TcpListener, TcpStream},
+		sync::{
+			atomic::{AtomicUsize, Ordering},
+			Arc,
+		},
+		thread,
+	};
+
+	use crate::read::{Archive, Reader, V2Reader};
+
+	use super::{HttpSource, BLOCK_SIZE, CACHED_BLOCKS};
+
+	const DATA: &[u8] = include_bytes!("../test/v2.img");
+
+	fn serve(stream: TcpStream, ranges: bool, requests: &AtomicUsize) {
+		let mut reader = BufReader::new(&stream);
+		let mut method = String::new();
+		let mut range = None;
+
+		reader.read_line(&mut method).expect("failed to read request");
+		requests.fetch_add(1, Ordering::SeqCst);
+
+		// Read each of the headers, noting any requested range.
+
+		loop {
+			let mut line = String::new();
+
+			if reader.read_line(&mut line).expect("failed to read header") <= 2 {
+				break;
+			}
+
+			if let Some(value) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+				let (start, end) = value.trim().split_once('-').expect("expected range");
+
+				range = Some((start.parse::<usize>().expect("expected start"), end.parse::<usize>().expect("expected end")));
+			}
+		}
+
+		let (status, body) = match range.filter(|_| ranges) {
+			Some((start, end)) => ("206 Partial Content", &DATA[start..=end]),
+			None => ("200 OK", DATA),
+		};
+
+		let mut stream = &stream;
+
+		write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, body.len()).expect("failed to write response");
+
+		if !method.starts_with("HEAD") {
+			stream.write_all(body).expect("failed to write response");
+		}
+	}
+
+	fn server(ranges: bool) -> (String, Arc<AtomicUsize>) {
+		let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind listener");
+		let url = format!("http://{}/v2.img", listener.local_addr().expect("expected address"));
+		let requests = Arc::new(AtomicUsize::new(0));
+		let counter = requests.clone();
+
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				serve(stream.expect("failed to accept connection"), ranges, &counter);
+			}
+		});
+
+		(url, requests)
+	}
+
+	#[test]
+	fn test_http_source() {
+		let (url, requests) = server(true);
+		let source = HttpSource::open(&url).expect("failed to open source");
+
+		assert_eq!(source.len(), 6144);
+
+		let mut img = source.into_stream();
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let mut data = Vec::new();
+
+		assert_eq!(archive.len(), 3);
+		assert_eq!(archive.get(0).map(|entry| entry.name.as_str()), Some("VIRGO.DFF"));
+
+		archive.open(1).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert_eq!(data, DATA[4096..6144]);
+
+		// The length should have been requested, followed by a single block serving both the directory and the entry.
+
+		assert_eq!(requests.load(Ordering::SeqCst), 2);
+	}
+
+	#[test]
+	fn test_http_source_cache() {
+		let (url, requests) = server(true);
+		let source = HttpSource::open(&url).expect("failed to open source");
+
+		// Fill the cache, then request the least recently used block once more.
+
+		source.blocks.lock().expect("failed to lock blocks").extend((0..CACHED_BLOCKS as u64).map(|index| (index * BLOCK_SIZE, Arc::from([0; 1]))));
+		source.block(0).expect("failed to read block");
+
+		// The block should have been served from the cache, and moved to the back such that it is discarded last.
+
+		let blocks = source.blocks.lock().expect("failed to lock blocks");
+
+		assert_eq!(requests.load(Ordering::SeqCst), 1);
+		assert_eq!(blocks.front().map(|(offset, _)| *offset), Some(BLOCK_SIZE));
+		assert_eq!(blocks.back().map(|(offset, _)| *offset), Some(0));
+	}
+
+	#[test]
+	fn test_http_source_without_ranges() {
+		let (url, _) = server(false);
+		let source = HttpSource::open(&url).expect("failed to open source");
+
+		let mut img = source.into_stream();
+		let error = V2Reader::new(&mut img).read().expect_err("expected error");
+
+		assert!(error.to_string().contains("range requests"), "{}", error);
+	}
+}
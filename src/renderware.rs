@@ -0,0 +1,438 @@
+use std::{
+	fmt,
+	io::{self, Read, Seek},
+};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::{game::Game, read::Archive};
+
+/// Represents the extensions of entries containing RenderWare streams (models and texture dictionaries).
+const RW_EXTENSIONS: [&str; 2] = ["dff", "txd"];
+
+/// Represents the extension of texture dictionary entries.
+const TXD_EXTENSION: &str = "txd";
+
+/// Represents the type of a struct chunk.
+const CHUNK_STRUCT: u32 = 0x01;
+
+/// Represents the type of a texture native chunk.
+const CHUNK_TEXTURE_NATIVE: u32 = 0x15;
+
+/// Represents the type of a clump chunk, with which models begin.
+pub(crate) const CHUNK_CLUMP: u32 = 0x10;
+
+/// Represents the type of a texture dictionary chunk, with which texture dictionaries begin.
+pub(crate) const CHUNK_TEXTURE_DICTIONARY: u32 = 0x16;
+
+/// Represents the size of the header of a chunk.
+const CHUNK_HEADER_SIZE: usize = 12;
+
+/// Represents the length of the name and mask of a texture, including the null terminator.
+const TEXTURE_NAME_SIZE: usize = 32;
+
+/// Represents the platform identifier of Direct3D 8 textures, which indicate their compression separately.
+const PLATFORM_D3D8: u32 = 8;
+
+/// Represents the platform identifier of Direct3D 9 textures, which indicate their compression as a four-character code.
+const PLATFORM_D3D9: u32 = 9;
+
+/// Represents the mask of the pixel format of a raster.
+const RASTER_FORMAT_MASK: u32 = 0x0F00;
+
+/// Represents the flag for rasters with 8-bit palettes.
+const RASTER_PAL8: u32 = 0x2000;
+
+/// Represents the flag for rasters with 4-bit palettes.
+const RASTER_PAL4: u32 = 0x4000;
+
+/// Represents the version of the RenderWare library used to produce a RenderWare stream (e.g. `3.6.0.3`).
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct RwVersion {
+	/// The major version.
+	pub major: u8,
+
+	/// The minor version.
+	pub minor: u8,
+
+	/// The revision.
+	pub revision: u8,
+
+	/// The patch.
+	pub patch: u8,
+}
+
+impl RwVersion {
+	/// Creates a new version from the library identifier stamp within the header of a RenderWare chunk.
+	/// Both the packed stamps of later versions and the plain versions of earlier versions are supported.
+	pub fn from_library_id(id: u32) -> Self {
+		let version = match id & 0xFFFF0000 {
+			0 => id << 8,
+			_ => (((id >> 14) & 0x3FF00) + 0x30000) | ((id >> 16) & 0x3F),
+		};
+
+		Self {
+			major: ((version >> 16) & 0xF) as u8,
+			minor: ((version >> 12) & 0xF) as u8,
+			revision: ((version >> 8) & 0xF) as u8,
+			patch: (version & 0xFF) as u8,
+		}
+	}
+
+	/// Returns the game whose era the version belongs to, such that mixtures of assets from different games can be identified.
+	/// Grand Theft Auto: III used versions prior to 3.3, Vice City used versions prior to 3.6, and San Andreas used 3.6 onwards.
+	pub fn game(&self) -> Game {
+		match (self.major, self.minor) {
+			(..=2, _) | (3, ..=2) => Game::III,
+			(3, 3..=5) => Game::ViceCity,
+			_ => Game::SanAndreas,
+		}
+	}
+}
+
+/// Represents a texture within a texture dictionary.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Texture {
+	/// The name of the texture.
+	pub name: String,
+
+	/// The name of the alpha mask of the texture, if any (otherwise empty).
+	pub mask: String,
+
+	/// The width, in pixels, of the texture.
+	pub width: u16,
+
+	/// The height, in pixels, of the texture.
+	pub height: u16,
+
+	/// The depth, in bits per pixel, of the texture.
+	pub depth: u8,
+
+	/// The format of the raster of the texture (e.g. `DXT1`, `8888` or `8888 PAL8`).
+	pub format: String,
+}
+
+/// Reads the RenderWare version of the entry at the specified index from the header of its first chunk, if the entry exists and is a model or texture dictionary.
+/// Returns `None` for entries too short to contain a chunk header.
+pub fn entry_rw_version<I>(archive: &mut Archive<I>, index: usize) -> Result<Option<RwVersion>, io::Error>
+where
+	I: Read + Seek,
+{
+	let Some(entry) = archive.get(index) else {
+		return Ok(None);
+	};
+
+	let is_rw = RW_EXTENSIONS.iter().any(|rw| entry.has_extension(rw));
+
+	if !is_rw {
+		return Ok(None);
+	}
+
+	let Some(mut open) = archive.open(index) else {
+		return Ok(None);
+	};
+
+	// Read the header of the chunk, made up of the type, the size and the library identifier stamp.
+
+	let mut header = [0; CHUNK_HEADER_SIZE];
+
+	match open.read_exact(&mut header) {
+		Ok(()) => (),
+		Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(error) => return Err(error),
+	}
+
+	let id = (&header[8..]).read_u32::<LittleEndian>()?;
+
+	Ok(Some(RwVersion::from_library_id(id)))
+}
+
+/// Lists the textures within the texture dictionary at the specified index, if the entry exists and is a texture dictionary.
+/// Only the headers of the textures are parsed, with the pixel data itself being skipped.
+pub fn entry_textures<I>(archive: &mut Archive<I>, index: usize) -> Result<Option<Vec<Texture>>, io::Error>
+where
+	I: Read + Seek,
+{
+	let is_txd = archive.get(index).is_some_and(|entry| entry.has_extension(TXD_EXTENSION));
+
+	if !is_txd {
+		return Ok(None);
+	}
+
+	match archive.open(index) {
+		Some(mut open) => read_textures(&mut open),
+		None => Ok(None),
+	}
+}
+
+/// Reads the RenderWare version of each of the models and texture dictionaries in the archive, attaching it to the attributes of each entry.
+pub fn annotate_rw_version<I>(archive: &mut Archive<I>) -> Result<(), io::Error>
+where
+	I: Read + Seek,
+{
+	for index in 0..archive.len() {
+		let version = entry_rw_version(archive, index)?;
+
+		archive.entries_mut()[index].attributes.rw_version = version;
+	}
+
+	Ok(())
+}
+
+impl fmt::Display for RwVersion {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}.{}.{}.{}", self.major, self.minor, self.revision, self.patch)
+	}
+}
+
+impl fmt::Display for Texture {
+	/// Formats the texture as its name, followed by its dimensions and format (e.g. `wheel (64x64, DXT1)`).
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} ({}x{}, {})", self.name, self.width, self.height, self.format)
+	}
+}
+
+fn read_textures<R>(src: &mut R) -> Result<Option<Vec<Texture>>, io::Error>
+where
+	R: Read,
+{
+	// Check that the stream begins with a texture dictionary, rather than any other chunk.
+
+	let (kind, _) = read_chunk_header(src)?;
+
+	if kind != CHUNK_TEXTURE_DICTIONARY {
+		return Ok(None);
+	}
+
+	// Read the number of textures from the struct of the dictionary.
+
+	let size = expect_chunk(src, CHUNK_STRUCT)?;
+	let count = src.read_u16::<LittleEndian>()?;
+
+	skip(src, size.saturating_sub(2))?;
+
+	let mut textures = Vec::new();
+
+	for _ in 0..count {
+		let size = expect_chunk(src, CHUNK_TEXTURE_NATIVE)?;
+		let mut native = src.take(size as u64);
+
+		textures.push(read_texture(&mut native)?);
+
+		// Skip the remainder of the texture, including its pixel data and any extensions.
+
+		io::copy(&mut native, &mut io::sink())?;
+	}
+
+	Ok(Some(textures))
+}
+
+fn read_texture<R>(src: &mut R) -> Result<Texture, io::Error>
+where
+	R: Read,
+{
+	expect_chunk(src, CHUNK_STRUCT)?;
+
+	let platform = src.read_u32::<LittleEndian>()?;
+	let _filter = src.read_u32::<LittleEndian>()?;
+
+	let name = read_name(src)?;
+	let mask = read_name(src)?;
+
+	let raster = src.read_u32::<LittleEndian>()?;
+	let fourcc = src.read_u32::<LittleEndian>()?;
+
+	let width = src.read_u16::<LittleEndian>()?;
+	let height = src.read_u16::<LittleEndian>()?;
+	let depth = src.read_u8()?;
+	let _levels = src.read_u8()?;
+	let _kind = src.read_u8()?;
+	let compression = src.read_u8()?;
+
+	// Determine the format from the compression if compressed, otherwise from the raster itself.
+
+	let fourcc = fourcc.to_le_bytes();
+
+	let format = match platform {
+		PLATFORM_D3D8 if (1..=5).contains(&compression) => format!("DXT{}", compression),
+		PLATFORM_D3D9 if fourcc.starts_with(b"DXT") => String::from_utf8_lossy(&fourcc).into_owned(),
+		_ => raster_format(raster),
+	};
+
+	Ok(Texture {
+		name,
+		mask,
+		width,
+		height,
+		depth,
+		format,
+	})
+}
+
+fn raster_format(raster: u32) -> String {
+	let format = match raster & RASTER_FORMAT_MASK {
+		0x0100 => "1555",
+		0x0200 => "565",
+		0x0300 => "4444",
+		0x0400 => "LUM8",
+		0x0500 => "8888",
+		0x0600 => "888",
+		0x0A00 => "555",
+		_ => "unknown",
+	};
+
+	match raster {
+		raster if raster & RASTER_PAL8 != 0 => format!("{} PAL8", format),
+		raster if raster & RASTER_PAL4 != 0 => format!("{} PAL4", format),
+		_ => format.to_owned(),
+	}
+}
+
+fn read_chunk_header<R>(src: &mut R) -> Result<(u32, u32), io::Error>
+where
+	R: Read,
+{
+	let kind = src.read_u32::<LittleEndian>()?;
+	let size = src.read_u32::<LittleEndian>()?;
+	let _id = src.read_u32::<LittleEndian>()?;
+
+	Ok((kind, size))
+}
+
+fn expect_chunk<R>(src: &mut R, expected: u32) -> Result<u32, io::Error>
+where
+	R: Read,
+{
+	match read_chunk_header(src)? {
+		(kind, size) if kind == expected => Ok(size),
+		(kind, _) => {
+			Err(io::Error::new(io::ErrorKind::InvalidData, format!("unexpected chunk of type {:#x} where type {:#x} expected", kind, expected)))
+		}
+	}
+}
+
+fn read_name<R>(src: &mut R) -> Result<String, io::Error>
+where
+	R: Read,
+{
+	let mut buf = [0; TEXTURE_NAME_SIZE];
+
+	src.read_exact(&mut buf)?;
+
+	Ok(buf.iter().take_while(|&&b| b != 0).map(|&b| b as char).collect())
+}
+
+fn skip<R>(src: &mut R, len: u32) -> Result<(), io::Error>
+where
+	R: Read,
+{
+	io::copy(&mut src.take(len as u64), &mut io::sink())?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		game::Game,
+		read::{Archive, Entry},
+	};
+
+	use super::{annotate_rw_version, entry_textures, RwVersion, Texture};
+
+	fn chunk(kind: u32, data: &[u8]) -> Vec<u8> {
+		let mut chunk = Vec::new();
+
+		chunk.extend_from_slice(&kind.to_le_bytes());
+		chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		chunk.extend_from_slice(&0x1803FFFFu32.to_le_bytes());
+		chunk.extend_from_slice(data);
+		chunk
+	}
+
+	fn texture(platform: u32, name: &str, raster: u32, fourcc: &[u8; 4], compression: u8) -> Vec<u8> {
+		let mut data = Vec::new();
+
+		data.extend_from_slice(&platform.to_le_bytes());
+		data.extend_from_slice(&0u32.to_le_bytes());
+		data.extend(name.bytes().chain(std::iter::repeat(0)).take(32));
+		data.extend([0; 32]);
+		data.extend_from_slice(&raster.to_le_bytes());
+		data.extend_from_slice(fourcc);
+		data.extend_from_slice(&64u16.to_le_bytes());
+		data.extend_from_slice(&32u16.to_le_bytes());
+		data.extend([16, 1, 4, compression]);
+
+		// Include some pixel data, to be skipped.
+
+		let mut native = chunk(0x01, &data);
+
+		native.extend(chunk(0x03, &[0; 8]));
+
+		chunk(0x15, &native)
+	}
+
+	#[test]
+	fn test_from_library_id() {
+		assert_eq!(RwVersion::from_library_id(0x1803FFFF).to_string(), "3.6.0.3");
+		assert_eq!(RwVersion::from_library_id(0x0C02FFFF).to_string(), "3.3.0.2");
+		assert_eq!(RwVersion::from_library_id(0x00000310).to_string(), "3.1.0.0");
+
+		assert_eq!(RwVersion::from_library_id(0x00000310).game(), Game::III);
+		assert_eq!(RwVersion::from_library_id(0x0C02FFFF).game(), Game::ViceCity);
+		assert_eq!(RwVersion::from_library_id(0x1803FFFF).game(), Game::SanAndreas);
+	}
+
+	#[test]
+	fn test_annotate_rw_version() {
+		let mut data = vec![0; 3 * 2048];
+
+		data[..12].copy_from_slice(&[0x10, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF, 0x03, 0x18]);
+		data[2048..2060].copy_from_slice(&[0x16, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF, 0x02, 0x0C]);
+
+		let mut img = Cursor::new(data);
+		let mut archive = Archive::from_entries(
+			&mut img,
+			vec![
+				Entry::new("VIRGO.DFF", 0, 1).expect("failed to create entry"),
+				Entry::new("VIRGO.TXD", 1, 1).expect("failed to create entry"),
+				Entry::new("VIRGO.COL", 2, 1).expect("failed to create entry"),
+			],
+		);
+
+		annotate_rw_version(&mut archive).expect("failed to annotate archive");
+
+		let versions: Vec<_> = archive.iter().map(|entry| entry.attributes.rw_version.map(|version| version.to_string())).collect();
+
+		assert_eq!(versions, [Some("3.6.0.3".to_owned()), Some("3.3.0.2".to_owned()), None]);
+	}
+
+	#[test]
+	fn test_entry_textures() {
+		let mut dictionary = chunk(0x01, &[3, 0, 0, 0]);
+
+		dictionary.extend(texture(9, "wheel", 0x0200, b"DXT1", 0));
+		dictionary.extend(texture(8, "body", 0x0500, &[0; 4], 3));
+		dictionary.extend(texture(8, "glass", 0x2500, &[0; 4], 0));
+
+		let mut data = chunk(0x16, &dictionary);
+
+		data.resize(2048, 0);
+
+		let mut img = Cursor::new(data);
+		let mut archive = Archive::from_entries(
+			&mut img,
+			vec![Entry::new("VIRGO.TXD", 0, 1).expect("failed to create entry"), Entry::new("VIRGO.DFF", 0, 1).expect("failed to create entry")],
+		);
+
+		let textures = entry_textures(&mut archive, 0).expect("failed to list textures").expect("expected textures");
+
+		assert_eq!(
+			textures.iter().map(Texture::to_string).collect::<Vec<_>>(),
+			["wheel (64x32, DXT1)", "body (64x32, DXT3)", "glass (64x32, 8888 PAL8)"]
+		);
+		assert_eq!(entry_textures(&mut archive, 1).expect("failed to list textures"), None);
+	}
+}
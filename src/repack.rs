@@ -0,0 +1,203 @@
+use std::io::{Read, Seek};
+
+use crate::{error::WriteError, read::Archive, write::Writer};
+
+/// Represents the options for repacking archives.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq)]
+pub struct RepackOptions {
+	/// The order in which the entries are written.
+	pub order: Order,
+
+	/// Whether to carry any unclaimed regions containing data (as read from the archive) through verbatim, each written directly after the entry preceding it within the original archive.
+	pub unclaimed: bool,
+}
+
+/// Represents the order in which the entries of an archive are written when repacked.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq)]
+pub enum Order {
+	/// Indicates that entries are written in their original order within the directory.
+	#[default]
+	Original,
+
+	/// Indicates that entries are written ordered by their names (case-insensitively).
+	Name,
+
+	/// Indicates that entries are written ordered by their offsets within the archive.
+	Offset,
+}
+
+/// Repacks the archive using the specified writer, which may be of either version so as to convert the archive, returning the number of entries written.
+/// Entries are written in the specified order, such that the original order of the directory is only changed when explicitly requested.
+pub fn repack<I, W>(archive: &mut Archive<I>, writer: &mut W, options: RepackOptions) -> Result<usize, WriteError>
+where
+	I: Read + Seek,
+	W: Writer,
+{
+	let mut indices: Vec<usize> = (0..archive.len()).collect();
+
+	// Sort the indices stably, such that entries with equal keys retain their original order.
+
+	match options.order {
+		Order::Original => (),
+		Order::Name => indices.sort_by_cached_key(|&index| archive.get(index).map(|entry| entry.name.to_ascii_lowercase())),
+		Order::Offset => indices.sort_by_key(|&index| archive.get(index).map(|entry| entry.offset)),
+	}
+
+	// Determine which entry each unclaimed region follows, being the entry ending closest before it (if any).
+
+	let unclaimed: Vec<(Option<usize>, Vec<u8>)> = match options.unclaimed {
+		true => archive
+			.unclaimed()
+			.iter()
+			.map(|region| {
+				let preceding = (0..archive.len())
+					.filter_map(|index| archive.get(index).map(|entry| (index, entry.offset_bytes() + entry.len_bytes())))
+					.filter(|&(_, end)| end <= region.offset)
+					.max_by_key(|&(_, end)| end)
+					.map(|(index, _)| index);
+
+				(preceding, region.data.clone())
+			})
+			.collect(),
+		false => Vec::new(),
+	};
+
+	write_unclaimed(writer, &unclaimed, None)?;
+
+	for &index in &indices {
+		let entry = archive.get(index).cloned();
+
+		if let (Some(entry), Some(mut open)) = (entry, archive.open(index)) {
+			writer.write_entry(&entry, &mut open)?;
+		}
+
+		write_unclaimed(writer, &unclaimed, Some(index))?;
+	}
+
+	Ok(indices.len())
+}
+
+fn write_unclaimed<W>(writer: &mut W, unclaimed: &[(Option<usize>, Vec<u8>)], preceding: Option<usize>) -> Result<(), WriteError>
+where
+	W: Writer,
+{
+	for (_, data) in unclaimed.iter().filter(|(index, _)| *index == preceding) {
+		writer.write_unclaimed(&mut data.as_slice())?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		read::{Archive, Entry, ReadOptions, Reader, V2Reader, Warning},
+		write::{V2Writer, Writer},
+	};
+
+	use super::{repack, Order, RepackOptions};
+
+	fn data() -> Cursor<Vec<u8>> {
+		Cursor::new([vec![1; 2048], vec![2; 2048], vec![3; 2048]].concat())
+	}
+
+	fn entries() -> Vec<Entry> {
+		vec![
+			Entry::new("VIRGO.DFF", 2, 1).expect("failed to create entry"),
+			Entry::new("LANDSTAL.DFF", 0, 1).expect("failed to create entry"),
+			Entry::new("ADMIRAL.DFF", 1, 1).expect("failed to create entry"),
+		]
+	}
+
+	#[test]
+	fn test_repack_original() {
+		let mut data = data();
+		let mut archive = Archive::from_entries(&mut data, entries());
+
+		let mut repacked = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut repacked, archive.len()).expect("failed to create writer");
+
+		assert_eq!(repack(&mut archive, &mut writer, RepackOptions::default()).expect("failed to repack archive"), 3);
+
+		writer.finish().expect("failed to finish archive");
+		repacked.set_position(0);
+
+		let mut repacked: Archive<_> = V2Reader::new(&mut repacked).read().expect("failed to read archive");
+
+		assert!(repacked.order_eq(&archive));
+		assert!(repacked.content_eq(&mut archive).expect("failed to compare archives"));
+	}
+
+	#[test]
+	fn test_repack_name() {
+		let mut data = data();
+		let mut archive = Archive::from_entries(&mut data, entries());
+
+		let mut repacked = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut repacked, archive.len()).expect("failed to create writer");
+
+		let options = RepackOptions {
+			order: Order::Name,
+			..Default::default()
+		};
+
+		repack(&mut archive, &mut writer, options).expect("failed to repack archive");
+
+		writer.finish().expect("failed to finish archive");
+		repacked.set_position(0);
+
+		let sorted: Archive<_> = V2Reader::new(&mut repacked).read().expect("failed to read archive");
+
+		let names: Vec<&str> = sorted.iter().map(|entry| entry.name.as_str()).collect();
+
+		assert!(!sorted.order_eq(&archive));
+		assert_eq!(names, ["ADMIRAL.DFF", "LANDSTAL.DFF", "VIRGO.DFF"]);
+	}
+
+	#[test]
+	fn test_repack_unclaimed() {
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 2).expect("failed to create writer");
+
+		// Hide some data between the entries.
+
+		writer.write("VIRGO.DFF", &mut [1; 2048].as_slice()).expect("failed to write entry");
+		writer.write_unclaimed(&mut [0xEE; 16].as_slice()).expect("failed to write unclaimed data");
+		writer.write("LANDSTAL.DFF", &mut [2; 2048].as_slice()).expect("failed to write entry");
+		writer.finish().expect("failed to finish archive");
+
+		img.set_position(0);
+
+		let options = ReadOptions {
+			unclaimed: true,
+			..Default::default()
+		};
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).with_options(options.clone()).read().expect("failed to read archive");
+
+		assert_eq!(archive.unclaimed().len(), 1);
+		assert_eq!(archive.unclaimed()[0].offset, 4096);
+		assert!(archive.warnings().contains(&Warning::UnclaimedData {
+			offset: 4096,
+			length: 2048
+		}));
+
+		// Repack the archive, carrying the unclaimed data through.
+
+		let mut repacked = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut repacked, 2).expect("failed to create writer");
+
+		let options = RepackOptions {
+			unclaimed: true,
+			..Default::default()
+		};
+
+		repack(&mut archive, &mut writer, options).expect("failed to repack archive");
+
+		writer.finish().expect("failed to finish archive");
+
+		assert_eq!(repacked.get_ref(), archive.into_inner().get_ref());
+	}
+}
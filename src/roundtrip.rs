@@ -0,0 +1,244 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::{
+	game::Version,
+	read::{Archive, Entry},
+	write::to_null_terminated,
+	ENTRY_SIZE, NULL_TERMINATOR, VERSION_2_HEADER_ENTRY_OFFSET,
+};
+
+/// Represents a field of an entry within a directory.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Field {
+	/// Indicates the offset of the entry.
+	Offset,
+
+	/// Indicates the length of the entry.
+	Length,
+
+	/// Indicates the second length field of an entry within a V2-styled archive (see also [`Entry::stream_len`]), which is unused by the games themselves.
+	Unused,
+
+	/// Indicates the name of the entry, up to and including the null terminator.
+	Name,
+
+	/// Indicates the bytes following the null terminator of the name of the entry.
+	Padding,
+
+	/// Indicates data following the entries, which does not form a complete entry.
+	Trailing,
+}
+
+/// Represents a difference between the original directory of an archive and the directory as it would be rewritten.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Difference {
+	/// The index of the entry concerned.
+	pub index: usize,
+
+	/// The field of the entry which would not be preserved.
+	pub field: Field,
+
+	/// The offset, in bytes, of the first differing byte within the directory.
+	pub offset: u64,
+}
+
+/// Verifies that the header of a V2-styled archive would be preserved byte-for-byte were the archive rewritten, returning each of the differences found.
+/// Returns an error of kind `InvalidInput` for V1-styled archives, which do not retain their directory (see `verify_roundtrip_dir`).
+pub fn verify_roundtrip<I>(archive: &mut Archive<I>) -> Result<Vec<Difference>, io::Error>
+where
+	I: Read + Seek,
+{
+	if Version::from_declared(archive.declared()) == Version::V1 {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, "directory of archive is not retained"));
+	}
+
+	// Read the original table of entries from the header.
+
+	let mut original = vec![0; archive.len() * ENTRY_SIZE as usize];
+	let src = archive.inner_mut();
+
+	src.seek(SeekFrom::Start(VERSION_2_HEADER_ENTRY_OFFSET))?;
+	src.read_exact(&mut original)?;
+
+	compare(archive.entries(), &original, VERSION_2_HEADER_ENTRY_OFFSET, true)
+}
+
+/// Verifies that the directory of a V1-styled archive, read from `dir`, would be preserved byte-for-byte were the archive rewritten, returning each of the differences found.
+pub fn verify_roundtrip_dir<I, D>(archive: &Archive<I>, dir: &mut D) -> Result<Vec<Difference>, io::Error>
+where
+	D: Read,
+{
+	let mut original = Vec::new();
+
+	dir.read_to_end(&mut original)?;
+
+	compare(archive.entries(), &original, 0, false)
+}
+
+fn compare(entries: &[Entry], original: &[u8], base: u64, v2: bool) -> Result<Vec<Difference>, io::Error> {
+	let mut differences = Vec::new();
+
+	// Compare each of the complete records of the original (any entries missing from the original entirely are not compared).
+
+	for (index, (entry, original)) in entries.iter().zip(original.chunks_exact(ENTRY_SIZE as usize)).enumerate() {
+		let record = base + index as u64 * ENTRY_SIZE;
+		let rewritten = to_record(entry, v2)?;
+
+		// Compare each of the fields of the record in turn.
+
+		let terminator = original[8..].iter().position(|&b| b == NULL_TERMINATOR).map_or(ENTRY_SIZE as usize, |position| 8 + position + 1);
+
+		let fields: [(Field, usize, usize); 5] = match v2 {
+			true => [
+				(Field::Offset, 0, 4),
+				(Field::Length, 4, 6),
+				(Field::Unused, 6, 8),
+				(Field::Name, 8, terminator),
+				(Field::Padding, terminator, ENTRY_SIZE as usize),
+			],
+			false => [
+				(Field::Offset, 0, 4),
+				(Field::Length, 4, 8),
+				(Field::Unused, 8, 8),
+				(Field::Name, 8, terminator),
+				(Field::Padding, terminator, ENTRY_SIZE as usize),
+			],
+		};
+
+		for (field, start, end) in fields {
+			let position = (start..end).find(|&position| original[position] != rewritten[position]);
+
+			if let Some(position) = position {
+				differences.push(Difference {
+					index,
+					field,
+					offset: record + position as u64,
+				});
+			}
+		}
+	}
+
+	// Check for any incomplete entry following the others, which would not be rewritten.
+
+	let end = entries.len() * ENTRY_SIZE as usize;
+
+	if original.len() > end {
+		differences.push(Difference {
+			index: entries.len(),
+			field: Field::Trailing,
+			offset: base + end as u64,
+		});
+	}
+
+	Ok(differences)
+}
+
+fn to_record(entry: &Entry, v2: bool) -> Result<Vec<u8>, io::Error> {
+	let mut record = Vec::with_capacity(ENTRY_SIZE as usize);
+
+	record.write_u32::<LittleEndian>(entry.offset as u32)?;
+
+	match v2 {
+		true => {
+			record.write_u16::<LittleEndian>(entry.length as u16)?;
+			record.write_u16::<LittleEndian>(entry.stream_len.unwrap_or(0) as u16)?; // Length in archive (usually 0)
+		}
+		false => record.write_u32::<LittleEndian>(entry.length as u32)?,
+	}
+
+	record.extend(to_null_terminated(&entry.name));
+
+	Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		read::{Archive, Reader, V1Reader, V2Reader},
+		write::{V2Writer, Writer},
+	};
+
+	use super::{verify_roundtrip, verify_roundtrip_dir, Field};
+
+	#[test]
+	fn test_verify_roundtrip_identical() {
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 2).expect("failed to create writer");
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write first entry");
+		writer.write("LANDSTAL.DFF", &mut Cursor::new(include_bytes!("../test/landstal.dff"))).expect("failed to write second entry");
+
+		img.set_position(0);
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let differences = verify_roundtrip(&mut archive).expect("failed to verify archive");
+
+		assert!(differences.is_empty());
+	}
+
+	#[test]
+	fn test_verify_roundtrip_v2() {
+		let mut bytes = include_bytes!("../test/v2.img").to_vec();
+
+		bytes[14] = 0x01; // Second length field of the first entry, which is preserved.
+
+		let mut img = Cursor::new(bytes);
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let differences = verify_roundtrip(&mut archive).expect("failed to verify archive");
+
+		assert_eq!(differences.len(), 3);
+
+		// The padding following the names would be zeroed.
+
+		assert_eq!(differences[0].index, 0);
+		assert_eq!(differences[0].field, Field::Padding);
+		assert_eq!(differences[0].offset, 26);
+
+		assert_eq!(differences[1].index, 1);
+		assert_eq!(differences[1].field, Field::Padding);
+		assert_eq!(differences[1].offset, 61);
+
+		// The unterminated name would be truncated.
+
+		assert_eq!(differences[2].index, 2);
+		assert_eq!(differences[2].field, Field::Name);
+		assert_eq!(differences[2].offset, 103);
+	}
+
+	#[test]
+	fn test_verify_roundtrip_v1() {
+		let mut dir = Cursor::new(&include_bytes!("../test/v1.dir")[0..70]);
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+
+		let differences =
+			verify_roundtrip_dir(&archive, &mut Cursor::new(&include_bytes!("../test/v1.dir")[0..70])).expect("failed to verify archive");
+
+		assert_eq!(differences.len(), 3);
+
+		assert_eq!(differences[0].field, Field::Padding);
+		assert_eq!(differences[0].offset, 18);
+		assert_eq!(differences[1].field, Field::Padding);
+		assert_eq!(differences[1].offset, 53);
+
+		assert_eq!(differences[2].index, 2);
+		assert_eq!(differences[2].field, Field::Trailing);
+		assert_eq!(differences[2].offset, 64);
+
+		// Any entries missing from a shorter directory are not compared.
+
+		let differences =
+			verify_roundtrip_dir(&archive, &mut Cursor::new(&include_bytes!("../test/v1.dir")[0..4])).expect("failed to verify archive");
+
+		assert!(differences.is_empty());
+
+		// The directory is not retained by V1-styled archives.
+
+		assert!(verify_roundtrip(&mut archive).is_err());
+	}
+}
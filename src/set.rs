@@ -0,0 +1,314 @@
+use std::{
+	collections::HashMap,
+	fs,
+	io::{self, Read, Seek},
+	path::Path,
+};
+
+use crate::{
+	portable::{self, Extracted},
+	read::{Archive, Entry, OpenEntry},
+};
+
+/// Represents a set of archives presenting a single view of their entries, resolving names in the same manner as the games.
+/// Archives are searched in the order they were added (e.g. `gta3.img`, then `gta_int.img`, then `player.img`), such that the first entry with a given name shadows any later entries with the same name.
+/// Names are resolved case-insensitively.
+#[derive(Debug)]
+pub struct ArchiveSet<'a, I> {
+	archives: Vec<Archive<'a, I>>,
+
+	names: HashMap<String, Location>,
+}
+
+/// Represents the location of an entry within a set of archives.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Location {
+	/// The index of the archive within the set.
+	pub archive: usize,
+
+	/// The index of the entry within the archive.
+	pub index: usize,
+}
+
+/// Represents an entry shadowed by an entry with the same name in an earlier archive.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Shadowed {
+	/// The name of the shadowed entry.
+	pub name: String,
+
+	/// The location of the shadowed entry.
+	pub shadowed: Location,
+
+	/// The location of the entry shadowing it.
+	pub by: Location,
+}
+
+impl<'a, I> ArchiveSet<'a, I> {
+	/// Creates a new, empty set of archives.
+	pub fn new() -> Self {
+		Self {
+			archives: Vec::new(),
+			names: HashMap::new(),
+		}
+	}
+
+	/// Adds the specified archive to the end of the set, behind any archives already added.
+	pub fn push(&mut self, archive: Archive<'a, I>) {
+		// Only record names not already resolved by earlier archives (or earlier entries).
+
+		for (index, entry) in archive.iter().enumerate() {
+			self.names.entry(entry.name.to_ascii_lowercase()).or_insert(Location {
+				archive: self.archives.len(),
+				index,
+			});
+		}
+
+		self.archives.push(archive);
+	}
+
+	/// Returns the number of archives in the set.
+	pub fn len(&self) -> usize {
+		self.archives.len()
+	}
+
+	/// Returns whether the set contains no archives.
+	pub fn is_empty(&self) -> bool {
+		self.archives.is_empty()
+	}
+
+	/// Returns the archive at the specified index, if it exists.
+	pub fn archive(&self, index: usize) -> Option<&Archive<'a, I>> {
+		self.archives.get(index)
+	}
+
+	/// Resolves the location of the entry with the specified name, if it exists in any of the archives.
+	pub fn resolve(&self, name: &str) -> Option<Location> {
+		self.names.get(&name.to_ascii_lowercase()).copied()
+	}
+
+	/// Returns the entry with the specified name, if it exists in any of the archives.
+	pub fn get(&self, name: &str) -> Option<&Entry> {
+		let location = self.resolve(name)?;
+
+		self.archives[location.archive].get(location.index)
+	}
+
+	/// Returns an iterator over each of the resolved (i.e. not shadowed) entries, in the order of the archives and then their entries.
+	pub fn iter(&self) -> impl Iterator<Item = (Location, &Entry)> {
+		self.locations().filter(|(location, entry)| self.resolve(&entry.name) == Some(*location))
+	}
+
+	/// Returns each of the entries shadowed by an entry with the same name in an earlier archive (or earlier in the same archive).
+	pub fn shadowed(&self) -> Vec<Shadowed> {
+		self.locations()
+			.filter_map(|(location, entry)| {
+				let by = self.resolve(&entry.name).filter(|&by| by != location)?;
+
+				Some(Shadowed {
+					name: entry.name.clone(),
+					shadowed: location,
+					by,
+				})
+			})
+			.collect()
+	}
+
+	/// Consumes the set, returning each of the archives.
+	pub fn into_archives(self) -> Vec<Archive<'a, I>> {
+		self.archives
+	}
+
+	fn locations(&self) -> impl Iterator<Item = (Location, &Entry)> {
+		self.archives.iter().enumerate().flat_map(|(archive, entries)| {
+			entries.iter().enumerate().map(move |(index, entry)| {
+				(
+					Location {
+						archive,
+						index,
+					},
+					entry,
+				)
+			})
+		})
+	}
+}
+
+impl<I> ArchiveSet<'_, I>
+where
+	I: Read + Seek,
+{
+	/// Opens and returns the entry with the specified name for reading, if it exists in any of the archives.
+	pub fn open(&mut self, name: &str) -> Option<OpenEntry<'_, I>> {
+		let location = self.resolve(name)?;
+
+		self.archives[location.archive].open(location.index)
+	}
+
+	/// Extracts each of the resolved (i.e. not shadowed) entries of the set to files within the `dir` directory (which is created if necessary), as per [`Archive::extract_all`].
+	/// Extraction continues past any entry which fails to extract, returning the location and outcome for each of the entries.
+	pub fn extract_all<P>(&mut self, dir: P) -> Result<Vec<(Location, Extracted)>, io::Error>
+	where
+		P: AsRef<Path>,
+	{
+		fs::create_dir_all(dir.as_ref())?;
+
+		let dir = portable::to_long_path(dir.as_ref());
+		let locations: Vec<Location> = self.iter().map(|(location, _)| location).collect();
+		let names = portable::to_file_names(self.iter().map(|(_, entry)| entry.name.as_str()));
+
+		let extracted = locations
+			.into_iter()
+			.zip(names)
+			.map(|(location, name)| {
+				let result = portable::extract_entry(self.archives[location.archive].open(location.index), &dir.join(&name.file));
+
+				(
+					location,
+					Extracted {
+						index: location.index,
+						name,
+						result,
+					},
+				)
+			})
+			.collect();
+
+		Ok(extracted)
+	}
+}
+
+impl<I> Default for ArchiveSet<'_, I> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a, I> FromIterator<Archive<'a, I>> for ArchiveSet<'a, I> {
+	fn from_iter<T>(iter: T) -> Self
+	where
+		T: IntoIterator<Item = Archive<'a, I>>,
+	{
+		let mut set = Self::new();
+
+		for archive in iter {
+			set.push(archive);
+		}
+
+		set
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		fs,
+		io::{Cursor, Read},
+	};
+
+	use crate::read::{Archive, Entry};
+
+	use super::{ArchiveSet, Location, Shadowed};
+
+	#[test]
+	fn test_archive_set() {
+		let mut first = Cursor::new(vec![1; 4096]);
+		let mut second = Cursor::new(vec![2; 4096]);
+
+		let mut set: ArchiveSet<_> = [
+			Archive::from_entries(&mut first, vec![Entry::new("VIRGO.DFF", 0, 1).expect("failed to create entry")]),
+			Archive::from_entries(
+				&mut second,
+				vec![
+					Entry::new("virgo.dff", 0, 1).expect("failed to create entry"),
+					Entry::new("LANDSTAL.DFF", 1, 1).expect("failed to create entry"),
+				],
+			),
+		]
+		.into_iter()
+		.collect();
+
+		assert_eq!(set.len(), 2);
+		assert_eq!(
+			set.resolve("Virgo.dff"),
+			Some(Location {
+				archive: 0,
+				index: 0,
+			})
+		);
+		assert_eq!(
+			set.resolve("LANDSTAL.DFF"),
+			Some(Location {
+				archive: 1,
+				index: 1,
+			})
+		);
+		assert_eq!(set.resolve("MISSING.DFF"), None);
+
+		assert_eq!(set.iter().map(|(_, entry)| entry.name.as_str()).collect::<Vec<_>>(), ["VIRGO.DFF", "LANDSTAL.DFF"]);
+		assert_eq!(
+			set.shadowed(),
+			[Shadowed {
+				name: "virgo.dff".to_owned(),
+				shadowed: Location {
+					archive: 1,
+					index: 0,
+				},
+				by: Location {
+					archive: 0,
+					index: 0,
+				},
+			}]
+		);
+
+		let mut data = Vec::new();
+
+		set.open("virgo.dff").expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert!(data.iter().all(|&b| b == 1));
+	}
+
+	#[test]
+	fn test_archive_set_extract_all() {
+		let mut first = Cursor::new(vec![1; 4096]);
+		let mut second = Cursor::new(vec![2; 4096]);
+
+		let mut set: ArchiveSet<_> = [
+			Archive::from_entries(&mut first, vec![Entry::new("VIRGO.DFF", 0, 1).expect("failed to create entry")]),
+			Archive::from_entries(
+				&mut second,
+				vec![
+					Entry::new("virgo.dff", 0, 1).expect("failed to create entry"),
+					Entry::new("LANDSTAL.DFF", 1, 1).expect("failed to create entry"),
+				],
+			),
+		]
+		.into_iter()
+		.collect();
+
+		let folder = std::env::temp_dir().join(format!("gta-img-set-all-{}", std::process::id()));
+		let extracted = set.extract_all(&folder).expect("failed to extract set");
+
+		let locations: Vec<Location> = extracted.iter().map(|(location, _)| *location).collect();
+
+		assert_eq!(
+			locations,
+			[
+				Location {
+					archive: 0,
+					index: 0,
+				},
+				Location {
+					archive: 1,
+					index: 1,
+				},
+			]
+		);
+		assert!(extracted.iter().all(|(_, extracted)| extracted.result.as_ref().is_ok_and(|&len| len == 2048)));
+
+		// The shadowing entry (rather than the shadowed entry) should have been extracted.
+
+		assert!(fs::read(folder.join("VIRGO.DFF")).expect("failed to read file").iter().all(|&b| b == 1));
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+}
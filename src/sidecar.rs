@@ -0,0 +1,400 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Version, portable, read::Archive, validate, ENTRY_SIZE, SECTOR_SIZE, VERSION_2_HEADER, VERSION_2_HEADER_ENTRY_OFFSET};
+
+/// Represents a record of the original layout of an archive, such that it can be rebuilt byte-for-byte from its extracted entries.
+/// Sidecars are serialised as JSON, with any raw data encoded as hexadecimal.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Sidecar {
+	/// The number of entries declared by the header of a V2-styled archive, or `None` for a V1-styled archive.
+	pub declared: Option<usize>,
+
+	/// The size, in bytes, of the `img` file.
+	pub size: u64,
+
+	/// Each of the entries, in their original order.
+	pub entries: Vec<SidecarEntry>,
+
+	/// Any data following the entries within the `dir` file of a V1-styled archive.
+	pub trailing: Option<String>,
+
+	/// Any regions of the `img` file not claimed by an entry (or the header) which contain anything other than zero padding.
+	pub gaps: Vec<Gap>,
+}
+
+/// Represents an entry within a sidecar.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SidecarEntry {
+	/// The name of the entry.
+	pub name: String,
+
+	/// The name of the file the entry is extracted to, as per [`portable::to_file_names`].
+	/// This differs from the name of the entry for any entries with names which are not portable, or which are shadowed by an earlier entry with the same name.
+	pub file: String,
+
+	/// The offset, in sectors, of the entry.
+	pub offset: u64,
+
+	/// The length, in sectors, of the entry.
+	pub length: u64,
+
+	/// The index of the earlier entry with the same name shadowing the entry, if any.
+	pub shadowed_by: Option<usize>,
+
+	/// The original record of the entry within the directory, including any padding following the name.
+	pub record: String,
+}
+
+impl SidecarEntry {
+	/// Returns the offset and length, in bytes, of the entry, clamped to the specified size of the `img` file.
+	/// Returns an error of kind `InvalidData` should the range overflow, as the sidecar may have been tampered with.
+	fn range_bytes(&self, size: u64) -> Result<(u64, u64), io::Error> {
+		let overflow = || io::Error::new(io::ErrorKind::InvalidData, "range of entry overflows");
+
+		let start = self.offset.checked_mul(SECTOR_SIZE).ok_or_else(overflow)?.min(size);
+		let end = self.offset.checked_add(self.length).and_then(|end| end.checked_mul(SECTOR_SIZE)).ok_or_else(overflow)?.min(size);
+
+		Ok((start, end - start))
+	}
+}
+
+/// Represents a region of the `img` file not claimed by any entry.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Gap {
+	/// The offset, in bytes, of the region.
+	pub offset: u64,
+
+	/// The data of the region.
+	pub data: String,
+}
+
+impl Sidecar {
+	/// Captures the layout of a V2-styled archive, reading the original records from its header.
+	/// Returns an error of kind `InvalidInput` for V1-styled archives, which do not retain their directory (see [`Sidecar::capture_dir`]).
+	pub fn capture<I>(archive: &mut Archive<I>) -> Result<Self, io::Error>
+	where
+		I: Read + Seek,
+	{
+		if Version::from_declared(archive.declared()) == Version::V1 {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "directory of archive is not retained"));
+		}
+
+		let mut directory = vec![0; archive.len() * ENTRY_SIZE as usize];
+		let src = archive.inner_mut();
+
+		src.seek(SeekFrom::Start(VERSION_2_HEADER_ENTRY_OFFSET))?;
+		src.read_exact(&mut directory)?;
+
+		Self::capture_from(archive, &directory)
+	}
+
+	/// Captures the layout of a V1-styled archive, reading the original records from `dir`.
+	pub fn capture_dir<I, D>(archive: &mut Archive<I>, dir: &mut D) -> Result<Self, io::Error>
+	where
+		I: Read + Seek,
+		D: Read,
+	{
+		let mut directory = Vec::new();
+
+		dir.read_to_end(&mut directory)?;
+
+		Self::capture_from(archive, &directory)
+	}
+
+	/// Serialises the sidecar as JSON.
+	pub fn to_json(&self) -> Result<String, io::Error> {
+		serde_json::to_string_pretty(self).map_err(io::Error::from)
+	}
+
+	/// Deserialises a sidecar from JSON.
+	pub fn from_json(json: &str) -> Result<Self, io::Error> {
+		serde_json::from_str(json).map_err(io::Error::from)
+	}
+
+	/// Rebuilds the `img` file of the archive to `img`, opening the extracted file of each entry with `open`.
+	/// The data of each entry is truncated or padded to its original length, and clamped to the original size of the `img` file.
+	/// Returns an error of kind `InvalidData`, before opening any files, should the file of any entry not be a portable file name (e.g. containing separators or `..`), or should the range of any entry overflow.
+	pub fn rebuild<I, F, R>(&self, img: &mut I, mut open: F) -> Result<(), io::Error>
+	where
+		I: Write + Seek,
+		F: FnMut(&SidecarEntry) -> Result<R, io::Error>,
+		R: Read,
+	{
+		// Check the files of the entries cannot escape the directory they are extracted to, as the sidecar may have been tampered with.
+
+		if self.entries.iter().any(|entry| portable::to_file_name(&entry.file) != entry.file) {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "file of entry is not a portable file name"));
+		}
+
+		let mut end = 0;
+
+		// Write the header of a V2-styled archive.
+
+		if let Some(declared) = self.declared {
+			img.seek(SeekFrom::Start(0))?;
+			img.write_all(&VERSION_2_HEADER)?;
+			img.write_u32::<LittleEndian>(declared as u32)?;
+
+			for entry in &self.entries {
+				img.write_all(&from_hex(&entry.record)?)?;
+			}
+
+			end = VERSION_2_HEADER_ENTRY_OFFSET + self.entries.len() as u64 * ENTRY_SIZE;
+		}
+
+		// Write the data of each entry, followed by any gaps.
+
+		for entry in &self.entries {
+			let (start, len) = entry.range_bytes(self.size)?;
+
+			// Copy no more than the original length of the entry, padding any shortfall with zeroes, such that nothing is allocated beyond the data of the file itself.
+
+			img.seek(SeekFrom::Start(start))?;
+
+			let copied = io::copy(&mut open(entry)?.take(len), img)?;
+
+			io::copy(&mut io::repeat(0).take(len - copied), img)?;
+
+			end = end.max(start + len);
+		}
+
+		for gap in &self.gaps {
+			let data = from_hex(&gap.data)?;
+			let gap_end =
+				gap.offset.checked_add(data.len() as u64).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "range of gap overflows"))?;
+
+			img.seek(SeekFrom::Start(gap.offset))?;
+			img.write_all(&data)?;
+
+			end = end.max(gap_end);
+		}
+
+		// Pad up to the original size, should the archive end with unclaimed zero padding.
+
+		if end < self.size {
+			img.seek(SeekFrom::Start(end))?;
+
+			io::copy(&mut io::repeat(0).take(self.size - end), img)?;
+		}
+
+		img.flush()
+	}
+
+	/// Rebuilds the `dir` file of a V1-styled archive to `dir`.
+	pub fn rebuild_dir<D>(&self, dir: &mut D) -> Result<(), io::Error>
+	where
+		D: Write,
+	{
+		for entry in &self.entries {
+			dir.write_all(&from_hex(&entry.record)?)?;
+		}
+
+		if let Some(trailing) = &self.trailing {
+			dir.write_all(&from_hex(trailing)?)?;
+		}
+
+		dir.flush()
+	}
+
+	fn capture_from<I>(archive: &mut Archive<I>, directory: &[u8]) -> Result<Self, io::Error>
+	where
+		I: Read + Seek,
+	{
+		let declared = archive.declared();
+		let size = archive.stream_len()?;
+
+		// Record each of the entries, along with the portable names of the files they are extracted to.
+
+		let files = portable::to_file_names(archive.iter().map(|entry| entry.name.as_str()));
+		let mut entries: Vec<SidecarEntry> = Vec::new();
+
+		for ((entry, record), file) in archive.iter().zip(directory.chunks_exact(ENTRY_SIZE as usize)).zip(files) {
+			let shadowed_by = entries.iter().position(|earlier| earlier.name.eq_ignore_ascii_case(&entry.name));
+
+			entries.push(SidecarEntry {
+				name: entry.name.clone(),
+				file: file.file,
+				offset: entry.offset,
+				length: entry.length,
+				shadowed_by,
+				record: to_hex(record),
+			});
+		}
+
+		let trailing = match declared {
+			Some(_) => None,
+			None => directory.get(entries.len() * ENTRY_SIZE as usize..).filter(|trailing| !trailing.is_empty()).map(to_hex),
+		};
+
+		// Find the regions not claimed by any entry (or the header), recording the spans of any which are not solely zero padding.
+		// Unlike validation, the padding following the header up to the first sector is included, as it too must be rebuilt.
+
+		let header = match declared {
+			Some(_) => VERSION_2_HEADER_ENTRY_OFFSET + entries.len() as u64 * ENTRY_SIZE,
+			None => 0,
+		};
+
+		let unclaimed: Vec<_> = validate::find_gaps(archive.entries(), None, size)
+			.into_iter()
+			.map(|(start, end)| (start.max(header), end))
+			.filter(|(start, end)| start < end)
+			.collect();
+
+		let mut gaps = Vec::new();
+		let src = archive.inner_mut();
+
+		for (start, end) in unclaimed {
+			validate::read_data(src, start, end, |position, data| {
+				// Join the data onto the previous gap should it follow directly on from it.
+
+				match gaps.last_mut().filter(|gap: &&mut Gap| gap.offset + gap.data.len() as u64 / 2 == position) {
+					Some(gap) => gap.data.push_str(&to_hex(data)),
+					None => gaps.push(Gap {
+						offset: position,
+						data: to_hex(data),
+					}),
+				}
+
+				Ok::<_, io::Error>(())
+			})?;
+		}
+
+		Ok(Self {
+			declared,
+			size,
+			entries,
+			trailing,
+			gaps,
+		})
+	}
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, io::Error> {
+	let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid hexadecimal data");
+
+	if !hex.len().is_multiple_of(2) {
+		return Err(invalid());
+	}
+
+	(0..hex.len()).step_by(2).map(|i| hex.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()).ok_or_else(invalid)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::{self, Cursor, Read};
+
+	use crate::read::{Archive, Reader, V1Reader, V2Reader};
+
+	use super::Sidecar;
+
+	fn extract<I>(archive: &mut Archive<I>) -> Vec<Vec<u8>>
+	where
+		I: Read + std::io::Seek,
+	{
+		(0..archive.len())
+			.map(|index| {
+				let mut data = Vec::new();
+
+				archive.open(index).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+				data
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_rebuild() {
+		let original = include_bytes!("../test/v2.img");
+
+		let mut img = Cursor::new(original);
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let sidecar = Sidecar::capture(&mut archive).expect("failed to capture sidecar");
+		let sidecar = Sidecar::from_json(&sidecar.to_json().expect("failed to serialise sidecar")).expect("failed to deserialise sidecar");
+
+		let files = extract(&mut archive);
+		let mut rebuilt = Cursor::new(Vec::new());
+
+		sidecar
+			.rebuild(&mut rebuilt, |entry| {
+				Ok(Cursor::new(files[sidecar.entries.iter().position(|other| other == entry).expect("expected entry")].clone()))
+			})
+			.expect("failed to rebuild archive");
+
+		assert_eq!(rebuilt.into_inner(), original);
+	}
+
+	#[test]
+	fn test_rebuild_dir() {
+		let original_dir = include_bytes!("../test/v1.dir");
+		let original_img = include_bytes!("../test/v1.img");
+
+		let mut dir = Cursor::new(original_dir);
+		let mut img = Cursor::new(original_img);
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+
+		let sidecar = Sidecar::capture_dir(&mut archive, &mut Cursor::new(original_dir)).expect("failed to capture sidecar");
+
+		let files = extract(&mut archive);
+		let mut rebuilt_dir = Vec::new();
+		let mut rebuilt_img = Cursor::new(Vec::new());
+
+		sidecar.rebuild_dir(&mut rebuilt_dir).expect("failed to rebuild dir");
+		sidecar
+			.rebuild(&mut rebuilt_img, |entry| {
+				Ok(Cursor::new(files[sidecar.entries.iter().position(|other| other == entry).expect("expected entry")].clone()))
+			})
+			.expect("failed to rebuild archive");
+
+		assert_eq!(rebuilt_dir, original_dir);
+		assert_eq!(rebuilt_img.into_inner(), original_img);
+	}
+
+	#[test]
+	fn test_capture_portable_files() {
+		let mut original = include_bytes!("../test/v2.img").to_vec();
+
+		// Rename the first entry such that it would escape the directory it is extracted to.
+
+		original[16..40].fill(0);
+		original[16..28].copy_from_slice(b"../pwned.dff");
+
+		let mut img = Cursor::new(original);
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut sidecar = Sidecar::capture(&mut archive).expect("failed to capture sidecar");
+
+		assert_eq!(sidecar.entries[0].name, "../pwned.dff");
+		assert_eq!(sidecar.entries[0].file, ".._pwned.dff");
+
+		// Rebuilding from a tampered sidecar should fail before opening any files.
+
+		sidecar.entries[0].file = "../pwned.dff".to_owned();
+
+		let result = sidecar.rebuild(&mut Cursor::new(Vec::new()), |_| -> Result<Cursor<Vec<u8>>, io::Error> { panic!("unexpected open") });
+
+		assert_eq!(result.map_err(|e| e.kind()), Err(io::ErrorKind::InvalidData));
+	}
+
+	#[test]
+	fn test_rebuild_overflow() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let mut sidecar = Sidecar::capture(&mut archive).expect("failed to capture sidecar");
+
+		// Rebuilding from a sidecar with an entry extending beyond the range of an offset should fail, rather than overflow.
+
+		sidecar.entries[1].length = u64::MAX;
+
+		let result = sidecar.rebuild(&mut Cursor::new(Vec::new()), |_| Ok(io::empty()));
+
+		assert_eq!(result.map_err(|e| e.kind()), Err(io::ErrorKind::InvalidData));
+	}
+}
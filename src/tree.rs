@@ -0,0 +1,199 @@
+use std::{
+	collections::BTreeMap,
+	io::{self, Read, Seek},
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	portable::{self, Extracted, FileName},
+	read::Archive,
+};
+
+/// Represents the separators interpreted within the names of entries.
+pub const SEPARATORS: [char; 2] = ['/', '\\'];
+
+/// Represents a directory within the tree view of an archive, as interpreted from the names of entries.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct Directory {
+	/// The directories within the directory, keyed by name.
+	pub directories: BTreeMap<String, Directory>,
+
+	/// The files within the directory, as the name of the file and the index of the entry.
+	pub files: Vec<(String, usize)>,
+}
+
+impl Directory {
+	/// Returns the directory at the specified path relative to this directory, if it exists.
+	pub fn get(&self, path: &str) -> Option<&Directory> {
+		components(path).try_fold(self, |directory, component| directory.directories.get(component))
+	}
+
+	/// Returns the total number of files within this directory and all of its directories.
+	pub fn len(&self) -> usize {
+		self.files.len() + self.directories.values().map(Directory::len).sum::<usize>()
+	}
+
+	/// Returns if this directory and all of its directories are void of any files.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns an iterator over each of the files within this directory and all of its directories, recursively.
+	/// Yields the path of each file relative to this directory, alongside the index of the entry.
+	pub fn walk(&self) -> impl Iterator<Item = (PathBuf, usize)> {
+		let mut files = Vec::with_capacity(self.len());
+
+		self.walk_into(Path::new(""), &mut files);
+
+		files.into_iter()
+	}
+
+	fn walk_into(&self, path: &Path, files: &mut Vec<(PathBuf, usize)>) {
+		files.extend(self.files.iter().map(|(name, index)| (path.join(name), *index)));
+
+		for (name, directory) in &self.directories {
+			directory.walk_into(&path.join(name), files);
+		}
+	}
+}
+
+impl<I> Archive<'_, I> {
+	/// Builds a tree view of the archive, interpreting any separators within the names of entries as directories.
+	pub fn tree(&self) -> Directory {
+		let mut root = Directory::default();
+
+		for (index, entry) in self.iter().enumerate() {
+			let mut components: Vec<_> = components(&entry.name).collect();
+
+			// Consider the final component to be the name of the file, ignoring any entries without a name at all.
+
+			let Some(file) = components.pop() else {
+				continue;
+			};
+
+			let directory =
+				components.into_iter().fold(&mut root, |directory, component| directory.directories.entry(component.to_owned()).or_default());
+
+			directory.files.push((file.to_owned(), index));
+		}
+
+		root
+	}
+}
+
+impl<I> Archive<'_, I>
+where
+	I: Read + Seek,
+{
+	/// Extracts each of the entries of the archive to files within the `dir` directory, interpreting any separators within their names as folders (as per [`to_path`]), which are created as necessary.
+	/// Extraction continues past any entry which fails to extract, returning the outcome for each of the entries as per [`Archive::extract_all`].
+	pub fn extract_tree<P>(&mut self, dir: P) -> Result<Vec<Extracted>, io::Error>
+	where
+		P: AsRef<Path>,
+	{
+		let names = self
+			.iter()
+			.map(|entry| FileName {
+				name: entry.name.clone(),
+				file: to_path(&entry.name).to_string_lossy().into_owned(),
+			})
+			.collect();
+
+		self.extract_named(dir.as_ref(), names)
+	}
+}
+
+/// Splits the specified name of an entry into its components, ignoring any empty, current (`.`) or parent (`..`) components.
+pub fn components(name: &str) -> impl Iterator<Item = &str> {
+	name.split(SEPARATORS).filter(|component| !matches!(*component, "" | "." | ".."))
+}
+
+/// Converts the specified name of an entry into a relative path, interpreting any separators as directories.
+/// Each component is made portable as per [`portable::to_file_name`] (e.g. replacing the `:` of drive prefixes), such that the resulting path never escapes the directory it is joined to.
+pub fn to_path(name: &str) -> PathBuf {
+	components(name).map(portable::to_file_name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		fs,
+		io::Cursor,
+		path::{Path, PathBuf},
+	};
+
+	use crate::{
+		read::{Archive, Entry, Reader, V1Reader},
+		write::{V1Writer, Writer},
+	};
+
+	use super::to_path;
+
+	#[test]
+	fn test_to_path() {
+		assert_eq!(to_path("veh/cars/landstal.dff"), Path::new("veh").join("cars").join("landstal.dff"));
+		assert_eq!(to_path("..\\..\\virgo.dff"), Path::new("virgo.dff"));
+		assert_eq!(to_path("/./virgo.dff"), Path::new("virgo.dff"));
+
+		// Drive prefixes and reserved names should be made portable, rather than replacing the directory joined to.
+
+		assert_eq!(to_path("C:\\virgo.dff"), Path::new("C_").join("virgo.dff"));
+		assert_eq!(to_path("C:virgo.dff"), Path::new("C_virgo.dff"));
+		assert_eq!(to_path("veh/CON.dff"), Path::new("veh").join("_CON.dff"));
+
+		assert!(Path::new("target").join(to_path("C:\\virgo.dff")).starts_with("target"));
+	}
+
+	#[test]
+	fn test_tree() {
+		let mut dir = Cursor::new(Vec::new());
+		let mut img = Cursor::new(Vec::new());
+
+		let mut writer = V1Writer::new(&mut dir, &mut img);
+
+		writer.write("veh/cars/landstal.dff", &mut Cursor::new(include_bytes!("../test/landstal.dff"))).expect("failed to write first entry");
+		writer.write("veh\\cars\\virgo.dff", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write second entry");
+		writer.write("root.txd", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write third entry");
+
+		dir.set_position(0);
+
+		let archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+		let tree = archive.tree();
+
+		assert_eq!(tree.len(), 3);
+		assert_eq!(tree.files, vec![("root.txd".to_owned(), 2)]);
+
+		let cars = tree.get("veh/cars").expect("expected directory");
+
+		assert_eq!(cars.files, vec![("landstal.dff".to_owned(), 0), ("virgo.dff".to_owned(), 1)]);
+
+		let walked: Vec<(PathBuf, usize)> = tree.walk().collect();
+
+		assert_eq!(walked[0], (PathBuf::from("root.txd"), 2));
+		assert_eq!(walked[1], (Path::new("veh").join("cars").join("landstal.dff"), 0));
+	}
+
+	#[test]
+	fn test_extract_tree() {
+		let mut data = Cursor::new(vec![1; 6144]);
+		let mut archive = Archive::from_entries(
+			&mut data,
+			vec![
+				Entry::new("veh/cars/LANDSTAL.DFF", 0, 1).expect("failed to create entry"),
+				Entry::new("C:\\VIRGO.DFF", 1, 1).expect("failed to create entry"),
+				Entry::new("ROOT.TXD", 2, 1).expect("failed to create entry"),
+			],
+		);
+
+		let folder = std::env::temp_dir().join(format!("gta-img-tree-{}", std::process::id()));
+		let extracted = archive.extract_tree(&folder).expect("failed to extract archive");
+
+		assert!(extracted.iter().all(|extracted| extracted.result.as_ref().is_ok_and(|&len| len == 2048)));
+
+		assert!(folder.join("veh").join("cars").join("LANDSTAL.DFF").is_file());
+		assert!(folder.join("C_").join("VIRGO.DFF").is_file());
+		assert!(folder.join("ROOT.TXD").is_file());
+
+		fs::remove_dir_all(&folder).expect("failed to remove folder");
+	}
+}
@@ -0,0 +1,982 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	io::{self, Read, Seek, SeekFrom},
+};
+
+use crate::{
+	error::ReadError,
+	event::Event,
+	read::{group_by_name, Archive, Entry},
+	ENTRY_SIZE, RESERVED_CHARACTERS, SECTOR_SIZE, VERSION_2_HEADER_ENTRY_OFFSET,
+};
+
+/// Represents the severity of an issue.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Severity {
+	/// Indicates that the issue is suspicious, but the archive is likely still usable.
+	Warning,
+
+	/// Indicates that the issue is likely to cause the archive to be misread.
+	Error,
+}
+
+/// Represents the kind of an issue.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum IssueKind {
+	/// Indicates that the data of an entry partially overlaps the data of another entry.
+	Overlap,
+
+	/// Indicates that the data of an entry occupies exactly the same range as another entry (e.g. intentionally deduplicated data).
+	Alias,
+
+	/// Indicates that the data of an entry extends beyond the end of the archive.
+	OutOfBounds,
+
+	/// Indicates that the name of an entry is shared (case-insensitively) with another entry.
+	DuplicateName,
+
+	/// Indicates that an entry has a length of zero.
+	EmptyEntry,
+
+	/// Indicates that the name of an entry contains a character outside of printable ASCII.
+	InvalidCharacter,
+
+	/// Indicates that the name of an entry contains a character reserved by either the games or common filesystems.
+	ReservedCharacter,
+
+	/// Indicates that the number of entries declared by the archive does not match the number of entries present.
+	/// The value expected is the number of entries, and the offset (if any) is that of the first data overlapped by the declared entries.
+	CountMismatch,
+
+	/// Indicates that the directory contains unexpected data following the entries (e.g. an incomplete or undeclared entry).
+	TrailingData,
+
+	/// Indicates that the data of an entry lies within the sectors occupied by the header of a V2-styled archive.
+	/// The value expected is the offset, in bytes, at which the data of entries may begin.
+	HeaderOverlap,
+
+	/// Indicates that a region not claimed by any entry contains data other than zero padding (e.g. an entry lost from a corrupted directory).
+	/// The offset is that of the region, and the value expected is the length, in bytes, of the region (all of which is expected to be padding).
+	UnclaimedData,
+
+	/// Indicates that the archive ends before the end of the furthest entry (e.g. a truncated `img` file, or one not matching the directory).
+	/// The offset is the size, in bytes, of the archive, and the value expected is the end of the furthest entry.
+	Truncated,
+
+	/// Indicates that the archive extends beyond the end of the furthest entry (e.g. an incomplete directory, or one not matching the `img` file).
+	/// The offset is the size, in bytes, of the archive, and the value expected is the end of the furthest entry (or of the header, should there be no entries).
+	Oversized,
+}
+
+/// Represents an issue found when validating an archive.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Issue {
+	/// The kind of the issue.
+	pub kind: IssueKind,
+
+	/// The severity of the issue.
+	pub severity: Severity,
+
+	/// The index of the entry concerned, if applicable.
+	pub index: Option<usize>,
+
+	/// The name of the entry concerned, if applicable.
+	pub name: Option<String>,
+
+	/// The offset, in bytes, of the issue within the archive, if applicable.
+	pub offset: Option<u64>,
+
+	/// The index of the other entry involved in the issue (e.g. for overlaps and duplicates), if applicable.
+	pub other: Option<usize>,
+
+	/// The value expected by the check, if applicable.
+	/// Its meaning depends on the kind of the issue (e.g. the number of entries for count mismatches, or a size in bytes for truncated archives), as documented by each [`IssueKind`].
+	/// Issues of any other kind have no value expected.
+	pub expected: Option<u64>,
+}
+
+/// Represents the options for validating an archive.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct ValidateOptions {
+	/// Whether to check for entries overlapping (or aliasing) one another.
+	pub overlaps: bool,
+
+	/// Whether to check for entries extending beyond the end of the archive.
+	pub bounds: bool,
+
+	/// Whether to check for entries sharing the same name.
+	pub duplicates: bool,
+
+	/// Whether to check for entries with a length of zero.
+	pub empty: bool,
+
+	/// Whether to check for names of entries containing invalid characters.
+	pub names: bool,
+
+	/// Whether to check that the regions not claimed by any entry contain only zero padding.
+	/// Note that this reads every unclaimed sector of the archive, and is therefore disabled by default.
+	pub deep: bool,
+
+	/// The strictness with which to classify each of the issues found.
+	pub strictness: Strictness,
+
+	/// The number of entries the archive is expected to contain, if known.
+	pub expected: Option<usize>,
+}
+
+/// Represents a profile classifying each kind of issue as ignored, or reported with a particular severity.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct Strictness {
+	base: Option<Option<Severity>>,
+	overrides: BTreeMap<IssueKind, Option<Severity>>,
+}
+
+/// Represents a report of each of the issues found when validating an archive, arranged by the entries concerned.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct ValidationReport {
+	issues: Vec<Issue>,
+}
+
+impl Issue {
+	/// Creates a new issue of the specified kind concerning the specified entry.
+	fn entry(kind: IssueKind, index: usize, entry: &Entry) -> Self {
+		Self {
+			kind,
+			severity: kind.severity(),
+			index: Some(index),
+			name: Some(entry.name.clone()),
+			offset: Some(entry.offset_bytes()),
+			other: None,
+			expected: None,
+		}
+	}
+
+	/// Creates a new issue of the specified kind concerning the archive as a whole.
+	fn archive(kind: IssueKind, offset: Option<u64>, expected: Option<u64>) -> Self {
+		Self {
+			kind,
+			severity: kind.severity(),
+			index: None,
+			name: None,
+			offset,
+			other: None,
+			expected,
+		}
+	}
+}
+
+impl IssueKind {
+	/// Returns the default severity of issues of this kind.
+	pub fn severity(&self) -> Severity {
+		match self {
+			Self::Overlap => Severity::Error,
+			Self::Alias => Severity::Warning,
+			Self::OutOfBounds => Severity::Error,
+			Self::DuplicateName => Severity::Warning,
+			Self::EmptyEntry => Severity::Warning,
+			Self::InvalidCharacter => Severity::Warning,
+			Self::ReservedCharacter => Severity::Warning,
+			Self::CountMismatch => Severity::Error,
+			Self::TrailingData => Severity::Warning,
+			Self::HeaderOverlap => Severity::Error,
+			Self::UnclaimedData => Severity::Warning,
+			Self::Truncated => Severity::Error,
+			Self::Oversized => Severity::Warning,
+		}
+	}
+}
+
+impl Default for ValidateOptions {
+	fn default() -> Self {
+		Self {
+			overlaps: true,
+			bounds: true,
+			duplicates: true,
+			empty: true,
+			names: true,
+			deep: false,
+			strictness: Strictness::default(),
+			expected: None,
+		}
+	}
+}
+
+impl Strictness {
+	/// Creates a new profile reporting each kind of issue with its default severity.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a new profile reporting every kind of issue as an error.
+	pub fn pedantic() -> Self {
+		Self {
+			base: Some(Some(Severity::Error)),
+			overrides: BTreeMap::new(),
+		}
+	}
+
+	/// Creates a new profile reporting every kind of issue as a warning.
+	pub fn lenient() -> Self {
+		Self {
+			base: Some(Some(Severity::Warning)),
+			overrides: BTreeMap::new(),
+		}
+	}
+
+	/// Sets the severity with which to report the specified kind of issue, or `None` to ignore it entirely.
+	pub fn with(mut self, kind: IssueKind, severity: Option<Severity>) -> Self {
+		self.overrides.insert(kind, severity);
+		self
+	}
+
+	/// Returns the severity with which the specified kind of issue is reported, or `None` if it is ignored.
+	pub fn severity(&self, kind: IssueKind) -> Option<Severity> {
+		match self.overrides.get(&kind) {
+			Some(severity) => *severity,
+			None => self.base.unwrap_or(Some(kind.severity())),
+		}
+	}
+}
+
+impl<I> Archive<'_, I>
+where
+	I: Read + Seek,
+{
+	/// Validates the archive against the specified options, returning each of the issues found.
+	/// Any observer of the archive is also notified of each of the issues.
+	pub fn validate(&mut self, options: &ValidateOptions) -> Result<Vec<Issue>, ReadError> {
+		let mut issues = Vec::new();
+
+		// Check the number of entries declared by the archive (if any), as well as any data following them.
+
+		if let Some(declared) = self.declared() {
+			check_count(self.entries(), declared, &mut issues);
+			check_header(self.entries(), declared, &mut issues);
+		}
+
+		if let Some(expected) = options.expected.filter(|&expected| expected != self.len()) {
+			issues.push(Issue::archive(IssueKind::CountMismatch, None, Some(expected as u64)));
+		}
+
+		if let Some(trailing) = self.trailing() {
+			issues.push(Issue::archive(IssueKind::TrailingData, Some(trailing), None));
+		}
+
+		if options.overlaps {
+			check_overlaps(self.entries(), &mut issues);
+			check_aliases(self.entries(), &mut issues);
+		}
+
+		if options.bounds {
+			let len = self.stream_len()?;
+
+			check_bounds(self.entries(), len, &mut issues);
+			check_size(self.entries(), self.declared(), len, &mut issues);
+		}
+
+		if options.duplicates {
+			check_duplicates(self.entries(), &mut issues);
+		}
+
+		if options.empty {
+			check_empty(self.entries(), &mut issues);
+		}
+
+		if options.names {
+			check_names(self.entries(), &mut issues);
+		}
+
+		if options.deep {
+			let len = self.stream_len()?;
+			let gaps = find_gaps(self.entries(), self.declared(), len);
+
+			check_gaps(self.inner_mut(), &gaps, &mut issues)?;
+		}
+
+		// Classify each of the issues according to the strictness, discarding those which are ignored.
+
+		let issues: Vec<_> = issues
+			.into_iter()
+			.filter_map(|issue| {
+				options.strictness.severity(issue.kind).map(|severity| Issue {
+					severity,
+					..issue
+				})
+			})
+			.collect();
+
+		for issue in &issues {
+			self.notify(&Event::Issue(issue));
+		}
+
+		Ok(issues)
+	}
+
+	/// Validates the archive against the specified options, returning a report of the issues found for each of the entries, and for the archive as a whole.
+	pub fn validate_report(&mut self, options: &ValidateOptions) -> Result<ValidationReport, ReadError> {
+		self.validate(options).map(ValidationReport::from)
+	}
+}
+
+impl ValidationReport {
+	/// Returns each of the issues found, in the order they were found.
+	pub fn issues(&self) -> &[Issue] {
+		&self.issues
+	}
+
+	/// Returns each of the issues concerning the entry at the specified index.
+	pub fn entry(&self, index: usize) -> impl Iterator<Item = &Issue> {
+		self.issues.iter().filter(move |issue| issue.index == Some(index))
+	}
+
+	/// Returns each of the issues concerning the archive as a whole, rather than any particular entry.
+	pub fn archive(&self) -> impl Iterator<Item = &Issue> {
+		self.issues.iter().filter(|issue| issue.index.is_none())
+	}
+
+	/// Returns each of the issues concerning entries, grouped by the index of the entry concerned.
+	pub fn by_entry(&self) -> BTreeMap<usize, Vec<&Issue>> {
+		let mut entries: BTreeMap<usize, Vec<&Issue>> = BTreeMap::new();
+
+		for issue in &self.issues {
+			if let Some(index) = issue.index {
+				entries.entry(index).or_default().push(issue);
+			}
+		}
+
+		entries
+	}
+
+	/// Indicates whether any issue was found.
+	pub fn is_empty(&self) -> bool {
+		self.issues.is_empty()
+	}
+
+	/// Indicates whether any of the issues found is an error, rather than a warning.
+	pub fn has_errors(&self) -> bool {
+		self.issues.iter().any(|issue| issue.severity == Severity::Error)
+	}
+
+	/// Consumes the report, returning each of the issues found.
+	pub fn into_issues(self) -> Vec<Issue> {
+		self.issues
+	}
+}
+
+impl From<Vec<Issue>> for ValidationReport {
+	fn from(issues: Vec<Issue>) -> Self {
+		Self {
+			issues,
+		}
+	}
+}
+
+fn check_count(entries: &[Entry], declared: usize, issues: &mut Vec<Issue>) {
+	if declared != entries.len() {
+		issues.push(Issue::archive(IssueKind::CountMismatch, None, Some(entries.len() as u64)));
+	}
+
+	// Check that the declared entries fit within the header before the data of the first entry begins.
+
+	let end = VERSION_2_HEADER_ENTRY_OFFSET + declared as u64 * ENTRY_SIZE;
+	let first = entries.iter().filter(|entry| entry.length > 0).map(|entry| entry.offset_bytes()).min();
+
+	if let Some(first) = first.filter(|&first| end > first) {
+		issues.push(Issue::archive(IssueKind::CountMismatch, Some(first), Some(first.saturating_sub(VERSION_2_HEADER_ENTRY_OFFSET) / ENTRY_SIZE)));
+	}
+}
+
+fn check_header(entries: &[Entry], declared: usize, issues: &mut Vec<Issue>) {
+	// Calculate the number of sectors occupied by the header, which no entry should lie within.
+
+	let sectors = (VERSION_2_HEADER_ENTRY_OFFSET + declared as u64 * ENTRY_SIZE).div_ceil(SECTOR_SIZE);
+
+	for (index, entry) in entries.iter().enumerate() {
+		if entry.length > 0 && entry.offset < sectors {
+			issues.push(Issue {
+				expected: Some(sectors * SECTOR_SIZE),
+				..Issue::entry(IssueKind::HeaderOverlap, index, entry)
+			});
+		}
+	}
+}
+
+fn check_overlaps(entries: &[Entry], issues: &mut Vec<Issue>) {
+	// Sort the (non-empty) entries by their offset, such that only subsequent entries need to be compared.
+
+	let mut sorted: Vec<_> = entries.iter().enumerate().filter(|(_, entry)| entry.length > 0).collect();
+
+	sorted.sort_by_key(|(index, entry)| (entry.offset, *index));
+
+	for (position, (index, entry)) in sorted.iter().enumerate() {
+		let end = entry.offset + entry.length;
+
+		// Skip any entries sharing the exact same range, which are considered to be aliases instead.
+
+		for (other, _) in sorted[position + 1..]
+			.iter()
+			.take_while(|(_, other)| other.offset < end)
+			.filter(|(_, other)| other.length != entry.length || other.offset != entry.offset)
+		{
+			issues.push(Issue {
+				other: Some(*other),
+				..Issue::entry(IssueKind::Overlap, *index, entry)
+			});
+		}
+	}
+}
+
+fn check_aliases(entries: &[Entry], issues: &mut Vec<Issue>) {
+	let mut ranges: HashMap<(u64, u64), usize> = HashMap::with_capacity(entries.len());
+
+	for (index, entry) in entries.iter().enumerate().filter(|(_, entry)| entry.length > 0) {
+		let first = *ranges.entry((entry.offset, entry.length)).or_insert(index);
+
+		if first != index {
+			issues.push(Issue {
+				other: Some(first),
+				..Issue::entry(IssueKind::Alias, index, entry)
+			});
+		}
+	}
+}
+
+fn check_bounds(entries: &[Entry], len: u64, issues: &mut Vec<Issue>) {
+	for (index, entry) in entries.iter().enumerate() {
+		if entry.end_bytes() > len {
+			issues.push(Issue::entry(IssueKind::OutOfBounds, index, entry));
+		}
+	}
+}
+
+fn check_size(entries: &[Entry], declared: Option<usize>, len: u64, issues: &mut Vec<Issue>) {
+	// Determine the end of the furthest entry, or the end of the header should there be no entries.
+
+	let header = match declared {
+		Some(declared) => (VERSION_2_HEADER_ENTRY_OFFSET + declared as u64 * ENTRY_SIZE).div_ceil(SECTOR_SIZE) * SECTOR_SIZE,
+		None => 0,
+	};
+
+	let end = entries.iter().filter(|entry| entry.length > 0).map(Entry::end_bytes).max();
+
+	match end {
+		Some(end) if end > len => issues.push(Issue::archive(IssueKind::Truncated, Some(len), Some(end))),
+		end if len > end.unwrap_or(0).max(header) => issues.push(Issue::archive(IssueKind::Oversized, Some(len), end.or(Some(header)))),
+		_ => (),
+	}
+}
+
+fn check_empty(entries: &[Entry], issues: &mut Vec<Issue>) {
+	for (index, entry) in entries.iter().enumerate() {
+		if entry.length == 0 {
+			issues.push(Issue::entry(IssueKind::EmptyEntry, index, entry));
+		}
+	}
+}
+
+fn check_duplicates(entries: &[Entry], issues: &mut Vec<Issue>) {
+	let mut firsts = vec![0; entries.len()];
+
+	// Note the first entry of the group of each entry, such that issues are still reported in the order of the entries.
+
+	for (_, indices) in group_by_name(entries) {
+		for &index in &indices {
+			firsts[index] = indices[0];
+		}
+	}
+
+	for (index, entry) in entries.iter().enumerate() {
+		let first = firsts[index];
+
+		if first != index {
+			issues.push(Issue {
+				other: Some(first),
+				..Issue::entry(IssueKind::DuplicateName, index, entry)
+			});
+		}
+	}
+}
+
+pub(crate) fn find_gaps(entries: &[Entry], declared: Option<usize>, len: u64) -> Vec<(u64, u64)> {
+	// Determine where the data begins, which for V2-styled archives is after the sectors occupied by the header.
+
+	let start = match declared {
+		Some(declared) => (VERSION_2_HEADER_ENTRY_OFFSET + declared as u64 * ENTRY_SIZE).div_ceil(SECTOR_SIZE) * SECTOR_SIZE,
+		None => 0,
+	};
+
+	// Sort the (non-empty) ranges by their offset, such that any unclaimed regions lie between subsequent ranges.
+
+	let mut ranges: Vec<_> = entries.iter().filter(|entry| entry.length > 0).map(|entry| (entry.offset_bytes(), entry.end_bytes())).collect();
+
+	ranges.sort_unstable();
+
+	let mut gaps = Vec::new();
+	let mut position = start;
+
+	for (offset, end) in ranges.into_iter().chain([(len, len)]) {
+		let offset = offset.min(len);
+
+		if offset > position {
+			gaps.push((position, offset));
+		}
+
+		position = position.max(end);
+	}
+
+	gaps
+}
+
+/// Reads the region from `offset` to `end` a sector at a time (aligned to sectors), passing each containing data (other than zeroes) to `f` along with its offset.
+pub(crate) fn read_data<R, F, E>(src: &mut R, offset: u64, end: u64, mut f: F) -> Result<(), E>
+where
+	R: Read + Seek,
+	F: FnMut(u64, &[u8]) -> Result<(), E>,
+	E: From<io::Error>,
+{
+	let mut buffer = vec![0u8; SECTOR_SIZE as usize];
+	let mut position = offset;
+
+	src.seek(SeekFrom::Start(offset))?;
+
+	while position < end {
+		let size = ((position / SECTOR_SIZE + 1) * SECTOR_SIZE).min(end) - position;
+		let data = &mut buffer[..size as usize];
+
+		src.read_exact(data)?;
+
+		if data.iter().any(|&byte| byte != 0) {
+			f(position, data)?;
+		}
+
+		position += size;
+	}
+
+	Ok(())
+}
+
+fn check_gaps<R>(src: &mut R, gaps: &[(u64, u64)], issues: &mut Vec<Issue>) -> Result<(), io::Error>
+where
+	R: Read + Seek,
+{
+	let mut buffer = vec![0u8; SECTOR_SIZE as usize];
+
+	for &(offset, end) in gaps {
+		src.seek(SeekFrom::Start(offset))?;
+
+		let mut position = offset;
+
+		// Read the region a sector at a time, reporting the start of the region if anything other than zero is found.
+
+		while position < end {
+			let size = (end - position).min(SECTOR_SIZE) as usize;
+
+			src.read_exact(&mut buffer[..size])?;
+
+			if buffer[..size].iter().any(|&byte| byte != 0) {
+				issues.push(Issue {
+					expected: Some(end - offset),
+					..Issue::archive(IssueKind::UnclaimedData, Some(offset), None)
+				});
+
+				break;
+			}
+
+			position += size as u64;
+		}
+	}
+
+	Ok(())
+}
+
+fn check_names(entries: &[Entry], issues: &mut Vec<Issue>) {
+	for (index, entry) in entries.iter().enumerate() {
+		if entry.name.chars().any(|c| !c.is_ascii_graphic() && c != ' ') {
+			issues.push(Issue::entry(IssueKind::InvalidCharacter, index, entry));
+		}
+
+		if entry.name.chars().any(|c| RESERVED_CHARACTERS.contains(&c)) {
+			issues.push(Issue::entry(IssueKind::ReservedCharacter, index, entry));
+		}
+	}
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Warning => write!(f, "warning"),
+			Self::Error => write!(f, "error"),
+		}
+	}
+}
+
+impl fmt::Display for IssueKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Overlap => write!(f, "overlapping entry"),
+			Self::Alias => write!(f, "aliased entry"),
+			Self::OutOfBounds => write!(f, "entry out of bounds"),
+			Self::DuplicateName => write!(f, "duplicate name"),
+			Self::EmptyEntry => write!(f, "empty entry"),
+			Self::InvalidCharacter => write!(f, "invalid character in name"),
+			Self::ReservedCharacter => write!(f, "reserved character in name"),
+			Self::CountMismatch => write!(f, "entry count mismatch"),
+			Self::TrailingData => write!(f, "trailing data in directory"),
+			Self::HeaderOverlap => write!(f, "entry overlaps header"),
+			Self::UnclaimedData => write!(f, "unclaimed data"),
+			Self::Truncated => write!(f, "archive truncated"),
+			Self::Oversized => write!(f, "archive oversized"),
+		}
+	}
+}
+
+impl fmt::Display for Issue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}: {}", self.severity, self.kind)?;
+
+		if let (Some(index), Some(name)) = (self.index, &self.name) {
+			write!(f, " [#{} {}]", index, name)?;
+		}
+
+		if let Some(offset) = self.offset {
+			write!(f, " at offset {}", offset)?;
+		}
+
+		if let Some(other) = self.other {
+			write!(f, " (with {})", other)?;
+		}
+
+		if let Some(expected) = self.expected {
+			write!(f, " (expected {})", expected)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Cursor;
+
+	use crate::{
+		read::{Archive, Attributes, Entry, Reader, V1Reader, V2Reader},
+		write::{V2Writer, Writer},
+	};
+
+	use super::{
+		check_aliases, check_count, check_duplicates, check_empty, check_gaps, check_header, check_names, check_overlaps, check_size, find_gaps,
+		IssueKind, Severity, Strictness, ValidateOptions,
+	};
+
+	fn entry(name: &str, offset: u64, length: u64) -> Entry {
+		Entry {
+			name: name.to_owned(),
+			offset,
+			length,
+			stream_len: None,
+			raw_name: None,
+			attributes: Attributes::default(),
+		}
+	}
+
+	#[test]
+	fn test_validate_valid() {
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 2).expect("failed to create writer");
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write first entry");
+		writer.write("LANDSTAL.DFF", &mut Cursor::new(include_bytes!("../test/landstal.dff"))).expect("failed to write second entry");
+
+		img.set_position(0);
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let issues = archive.validate(&ValidateOptions::default()).expect("failed to validate archive");
+
+		assert!(issues.is_empty());
+	}
+
+	#[test]
+	fn test_validate_bounds() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+		let issues = archive.validate(&ValidateOptions::default()).expect("failed to validate archive");
+
+		assert_eq!(issues.len(), 3);
+
+		assert_eq!(issues[0].kind, IssueKind::OutOfBounds);
+		assert_eq!(issues[0].severity, Severity::Error);
+		assert_eq!(issues[0].index, Some(1));
+		assert_eq!(issues[0].offset, Some(2048));
+
+		assert_eq!(issues[1].kind, IssueKind::OutOfBounds);
+		assert_eq!(issues[1].index, Some(2));
+		assert_eq!(issues[1].offset, Some(6144));
+
+		assert_eq!(issues[2].kind, IssueKind::Truncated);
+		assert_eq!(issues[2].offset, Some(4096));
+		assert_eq!(issues[2].expected, Some(22528));
+	}
+
+	#[test]
+	fn test_validate_strictness() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+
+		let options = ValidateOptions {
+			strictness: Strictness::pedantic().with(IssueKind::OutOfBounds, Some(Severity::Warning)).with(IssueKind::Truncated, None),
+			..ValidateOptions::default()
+		};
+
+		let issues = archive.validate(&options).expect("failed to validate archive");
+
+		assert_eq!(issues.len(), 2);
+		assert!(issues.iter().all(|issue| issue.severity == Severity::Warning));
+
+		let options = ValidateOptions {
+			strictness: Strictness::new().with(IssueKind::OutOfBounds, None).with(IssueKind::Truncated, None),
+			..ValidateOptions::default()
+		};
+
+		assert!(archive.validate(&options).expect("failed to validate archive").is_empty());
+	}
+
+	#[test]
+	fn test_strictness_severity() {
+		assert_eq!(Strictness::new().severity(IssueKind::Overlap), Some(Severity::Error));
+		assert_eq!(Strictness::new().severity(IssueKind::DuplicateName), Some(Severity::Warning));
+		assert_eq!(Strictness::pedantic().severity(IssueKind::DuplicateName), Some(Severity::Error));
+		assert_eq!(Strictness::lenient().severity(IssueKind::Overlap), Some(Severity::Warning));
+		assert_eq!(Strictness::lenient().with(IssueKind::Overlap, None).severity(IssueKind::Overlap), None);
+	}
+
+	#[test]
+	fn test_check_count() {
+		let entries = vec![entry("A", 1, 1), entry("B", 2, 1)];
+		let mut issues = Vec::new();
+
+		check_count(&entries, 2, &mut issues);
+
+		assert!(issues.is_empty());
+
+		check_count(&entries, 100, &mut issues);
+
+		assert_eq!(issues.len(), 2);
+		assert_eq!(issues[0].kind, IssueKind::CountMismatch);
+		assert_eq!(issues[0].expected, Some(2));
+		assert_eq!(issues[1].kind, IssueKind::CountMismatch);
+		assert_eq!(issues[1].offset, Some(2048));
+		assert_eq!(issues[1].expected, Some(63));
+	}
+
+	#[test]
+	fn test_check_header() {
+		let entries = vec![entry("A", 0, 1), entry("B", 1, 1), entry("C", 0, 0)];
+		let mut issues = Vec::new();
+
+		check_header(&entries, 3, &mut issues);
+
+		assert_eq!(issues.len(), 1);
+		assert_eq!(issues[0].kind, IssueKind::HeaderOverlap);
+		assert_eq!(issues[0].index, Some(0));
+		assert_eq!(issues[0].expected, Some(2048));
+	}
+
+	#[test]
+	fn test_validate_trailing() {
+		let mut dir = Cursor::new(&include_bytes!("../test/v1.dir")[0..40]);
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+		let issues = archive.validate(&ValidateOptions::default()).expect("failed to validate archive");
+
+		assert_eq!(issues.len(), 2);
+		assert_eq!(issues[0].kind, IssueKind::TrailingData);
+		assert_eq!(issues[0].offset, Some(32));
+		assert_eq!(issues[1].kind, IssueKind::Oversized);
+		assert_eq!(issues[1].expected, Some(2048));
+	}
+
+	#[test]
+	fn test_validate_expected() {
+		let mut img = Cursor::new(include_bytes!("../test/v2.img"));
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let options = ValidateOptions {
+			bounds: false,
+			expected: Some(4),
+			..ValidateOptions::default()
+		};
+
+		let issues = archive.validate(&options).expect("failed to validate archive");
+
+		assert_eq!(issues.len(), 1);
+		assert_eq!(issues[0].kind, IssueKind::CountMismatch);
+		assert_eq!(issues[0].expected, Some(4));
+	}
+
+	#[test]
+	fn test_check_size() {
+		let entries = vec![entry("A", 1, 1), entry("B", 2, 2)];
+		let mut issues = Vec::new();
+
+		check_size(&entries, Some(2), 8192, &mut issues);
+		check_size(&entries, None, 0, &mut issues);
+		check_size(&entries, None, 10240, &mut issues);
+		check_size(&[], Some(2), 2048, &mut issues);
+		check_size(&[], Some(2), 4096, &mut issues);
+
+		assert_eq!(issues.len(), 3);
+		assert_eq!(issues[0].kind, IssueKind::Truncated);
+		assert_eq!(issues[0].expected, Some(8192));
+		assert_eq!(issues[1].kind, IssueKind::Oversized);
+		assert_eq!(issues[1].offset, Some(10240));
+		assert_eq!(issues[2].kind, IssueKind::Oversized);
+		assert_eq!(issues[2].expected, Some(2048));
+	}
+
+	#[test]
+	fn test_check_overlaps() {
+		let entries = vec![entry("A", 0, 4), entry("B", 2, 1), entry("C", 4, 1), entry("D", 3, 0)];
+		let mut issues = Vec::new();
+
+		check_overlaps(&entries, &mut issues);
+
+		assert_eq!(issues.len(), 1);
+		assert_eq!(issues[0].kind, IssueKind::Overlap);
+		assert_eq!(issues[0].index, Some(0));
+		assert_eq!(issues[0].other, Some(1));
+	}
+
+	#[test]
+	fn test_check_aliases() {
+		let entries = vec![entry("A", 0, 2), entry("B", 0, 2), entry("C", 1, 2), entry("D", 0, 2)];
+		let mut overlaps = Vec::new();
+		let mut aliases = Vec::new();
+
+		check_overlaps(&entries, &mut overlaps);
+		check_aliases(&entries, &mut aliases);
+
+		assert_eq!(overlaps.len(), 3);
+		assert!(overlaps.iter().all(|issue| issue.kind == IssueKind::Overlap && issue.other == Some(2)));
+
+		assert_eq!(aliases.len(), 2);
+		assert!(aliases.iter().all(|issue| issue.kind == IssueKind::Alias && issue.other == Some(0)));
+		assert_eq!(aliases[0].index, Some(1));
+		assert_eq!(aliases[1].index, Some(3));
+	}
+
+	#[test]
+	fn test_check_duplicates() {
+		let entries = vec![entry("VIRGO.DFF", 0, 1), entry("LANDSTAL.DFF", 1, 1), entry("virgo.dff", 2, 1)];
+		let mut issues = Vec::new();
+
+		check_duplicates(&entries, &mut issues);
+
+		assert_eq!(issues.len(), 1);
+		assert_eq!(issues[0].index, Some(2));
+		assert_eq!(issues[0].other, Some(0));
+	}
+
+	#[test]
+	fn test_check_empty() {
+		let entries = vec![entry("VIRGO.DFF", 0, 1), entry("LANDSTAL.DFF", 1, 0)];
+		let mut issues = Vec::new();
+
+		check_empty(&entries, &mut issues);
+
+		assert_eq!(issues.len(), 1);
+		assert_eq!(issues[0].index, Some(1));
+		assert_eq!(issues[0].kind, IssueKind::EmptyEntry);
+	}
+
+	#[test]
+	fn test_validate_report() {
+		let mut dir = Cursor::new(include_bytes!("../test/v1.dir"));
+		let mut img = Cursor::new(include_bytes!("../test/v1.img"));
+
+		let mut archive: Archive<_> = V1Reader::new(&mut dir, &mut img).read().expect("failed to read archive");
+		let report = archive.validate_report(&ValidateOptions::default()).expect("failed to validate archive");
+
+		assert!(report.has_errors());
+		assert_eq!(report.issues().len(), 3);
+
+		assert_eq!(report.entry(0).count(), 0);
+		assert_eq!(report.entry(1).map(|issue| issue.kind).collect::<Vec<_>>(), [IssueKind::OutOfBounds]);
+		assert_eq!(report.archive().map(|issue| issue.kind).collect::<Vec<_>>(), [IssueKind::Truncated]);
+
+		assert_eq!(report.by_entry().keys().copied().collect::<Vec<_>>(), [1, 2]);
+	}
+
+	#[test]
+	fn test_find_gaps() {
+		let entries = vec![entry("A", 2, 1), entry("B", 4, 2), entry("C", 5, 1), entry("D", 0, 0)];
+
+		assert_eq!(find_gaps(&entries, Some(4), 8 * 2048), vec![(2048, 4096), (6144, 8192), (12288, 16384)]);
+		assert_eq!(find_gaps(&entries, None, 5 * 2048), vec![(0, 4096), (6144, 8192)]);
+	}
+
+	#[test]
+	fn test_check_gaps() {
+		let mut img = vec![0u8; 4 * 2048];
+
+		img[2048 + 100] = 1;
+
+		let mut issues = Vec::new();
+
+		check_gaps(&mut Cursor::new(img), &[(0, 2048), (2048, 6144), (6144, 8192)], &mut issues).expect("failed to check gaps");
+
+		assert_eq!(issues.len(), 1);
+		assert_eq!(issues[0].kind, IssueKind::UnclaimedData);
+		assert_eq!(issues[0].offset, Some(2048));
+		assert_eq!(issues[0].expected, Some(4096));
+	}
+
+	#[test]
+	fn test_validate_deep() {
+		let mut img = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 2).expect("failed to create writer");
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write first entry");
+		writer.write("LANDSTAL.DFF", &mut Cursor::new(include_bytes!("../test/landstal.dff"))).expect("failed to write second entry");
+
+		// Append a sector of data not claimed by either entry.
+
+		img.get_mut().extend_from_slice(&[0xFF; 2048]);
+		img.set_position(0);
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		let options = ValidateOptions {
+			deep: true,
+			..ValidateOptions::default()
+		};
+
+		let issues = archive.validate(&options).expect("failed to validate archive");
+
+		assert_eq!(issues.len(), 2);
+		assert_eq!(issues[0].kind, IssueKind::Oversized);
+		assert_eq!(issues[1].kind, IssueKind::UnclaimedData);
+		assert_eq!(issues[1].offset, Some(6144));
+	}
+
+	#[test]
+	fn test_check_names() {
+		let entries = vec![entry("VIRGO.DFF", 0, 1), entry("VIRGO\u{1}.DFF", 1, 1), entry("VIRGÖ.DFF", 2, 1), entry("VEH/VIRGO.DFF", 3, 1)];
+		let mut issues = Vec::new();
+
+		check_names(&entries, &mut issues);
+
+		assert_eq!(issues.len(), 3);
+		assert_eq!(issues[0].index, Some(1));
+		assert_eq!(issues[1].index, Some(2));
+		assert_eq!(issues[2].index, Some(3));
+		assert_eq!(issues[2].kind, IssueKind::ReservedCharacter);
+	}
+}
@@ -0,0 +1,223 @@
+use std::{
+	fs::{self, File},
+	io,
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	error::ReadError,
+	game::Version,
+	paths::V1Paths,
+	read::{Entry, Reader, V1Reader, V2Reader},
+	set::Location,
+};
+
+/// Represents the extension of `img` files.
+const IMG_EXTENSION: &str = "img";
+
+/// Represents the archives discovered within a game installation, the entries of which are read lazily upon first being queried.
+#[derive(Debug)]
+pub struct Workspace {
+	root: PathBuf,
+
+	archives: Vec<ArchivePaths>,
+	entries: Vec<Option<Vec<Entry>>>,
+}
+
+/// Represents the paths of the files making up an archive discovered within a workspace.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum ArchivePaths {
+	/// Indicates a V1-styled archive, made up of an `img` file and a `dir` file.
+	V1(V1Paths),
+
+	/// Indicates a V2-styled archive, made up of a single `img` file.
+	V2(PathBuf),
+}
+
+impl Workspace {
+	/// Scans the specified game installation directory (and any folders within it) for archives, including those outside of the `models` folder (e.g. `player.img` and `cuts.img`).
+	/// Each `img` file is identified as a V2-styled archive by its header, or otherwise as a V1-styled archive by an accompanying `dir` file, with any other `img` files being ignored.
+	pub fn scan<P>(root: P) -> Result<Self, io::Error>
+	where
+		P: Into<PathBuf>,
+	{
+		let root = root.into();
+
+		let mut paths = Vec::new();
+
+		find_imgs(&root, &mut paths)?;
+
+		// Sort the paths, as the order of directory listings is unspecified.
+
+		paths.sort();
+
+		let mut archives = Vec::new();
+
+		for img in paths {
+			if is_v2(&img)? {
+				archives.push(ArchivePaths::V2(img));
+			} else if let Ok(paths) = V1Paths::from_img(&img) {
+				archives.push(ArchivePaths::V1(paths));
+			}
+		}
+
+		Ok(Self {
+			root,
+			entries: vec![None; archives.len()],
+			archives,
+		})
+	}
+
+	/// Returns the game installation directory scanned.
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	/// Returns the paths of each of the archives discovered.
+	pub fn archives(&self) -> &[ArchivePaths] {
+		&self.archives
+	}
+
+	/// Returns the entries of the archive at the specified index, reading them if not already read.
+	pub fn entries(&mut self, index: usize) -> Result<&[Entry], ReadError> {
+		let paths = self.archives.get(index).ok_or(ReadError::InvalidIndex(index))?;
+
+		if self.entries[index].is_none() {
+			self.entries[index] = Some(paths.read_entries()?);
+		}
+
+		Ok(self.entries[index].as_deref().unwrap_or_default())
+	}
+
+	/// Finds each of the entries with the specified name across all of the archives, reading the entries of any archives not already read.
+	/// Names are compared case-insensitively, in line with the behaviour of the games.
+	pub fn find(&mut self, name: &str) -> Result<Vec<Location>, ReadError> {
+		let mut locations = Vec::new();
+
+		for archive in 0..self.archives.len() {
+			for (index, entry) in self.entries(archive)?.iter().enumerate() {
+				if entry.name.eq_ignore_ascii_case(name) {
+					locations.push(Location {
+						archive,
+						index,
+					});
+				}
+			}
+		}
+
+		Ok(locations)
+	}
+}
+
+impl ArchivePaths {
+	/// Returns the path of the `img` file.
+	pub fn img(&self) -> &Path {
+		match self {
+			Self::V1(paths) => &paths.img,
+			Self::V2(img) => img,
+		}
+	}
+
+	/// Returns the version of the archive.
+	pub fn version(&self) -> Version {
+		match self {
+			Self::V1(_) => Version::V1,
+			Self::V2(_) => Version::V2,
+		}
+	}
+
+	/// Reads the entries of the archive.
+	pub(crate) fn read_entries(&self) -> Result<Vec<Entry>, ReadError> {
+		let mut entries = match self {
+			Self::V1(paths) => {
+				let (mut dir, mut img) = paths.open()?;
+
+				V1Reader::new(&mut dir, &mut img).read()?.into_entries()
+			}
+			Self::V2(img) => {
+				let mut img = File::open(img)?;
+
+				V2Reader::new(&mut img).read()?.into_entries()
+			}
+		};
+
+		// Note the archive each of the entries was read from.
+
+		for entry in &mut entries {
+			entry.attributes.source = Some(self.img().to_path_buf());
+		}
+
+		Ok(entries)
+	}
+}
+
+fn find_imgs(path: &Path, imgs: &mut Vec<PathBuf>) -> Result<(), io::Error> {
+	for file in fs::read_dir(path)? {
+		let file = file?;
+		let path = file.path();
+
+		if file.file_type()?.is_dir() {
+			find_imgs(&path, imgs)?;
+		} else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case(IMG_EXTENSION)) {
+			imgs.push(path);
+		}
+	}
+
+	Ok(())
+}
+
+fn is_v2(img: &Path) -> Result<bool, io::Error> {
+	Ok(crate::detect(&mut File::open(img)?)? == Version::V2)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{fs, path::PathBuf};
+
+	use crate::{game::Version, set::Location};
+
+	use super::Workspace;
+
+	fn copy_fixtures(name: &str) -> PathBuf {
+		let folder = std::env::temp_dir().join(format!("gta-img-workspace-{}-{}", name, std::process::id()));
+
+		fs::create_dir_all(&folder).expect("failed to create folder");
+
+		for file in ["v1.img", "v1.dir", "v2.img", "virgo.dff"] {
+			fs::copy(PathBuf::from("test").join(file), folder.join(file)).expect("failed to copy fixture");
+		}
+
+		folder
+	}
+
+	#[test]
+	fn test_workspace() {
+		let folder = copy_fixtures("scan");
+		let mut workspace = Workspace::scan(&folder).expect("failed to scan workspace");
+
+		assert_eq!(workspace.archives().len(), 2);
+		assert_eq!(workspace.archives()[0].img(), folder.join("v1.img"));
+		assert_eq!(workspace.archives()[0].version(), Version::V1);
+		assert_eq!(workspace.archives()[1].version(), Version::V2);
+
+		assert_eq!(
+			workspace.find("virgo.dff").expect("failed to find entry"),
+			[
+				Location {
+					archive: 0,
+					index: 0,
+				},
+				Location {
+					archive: 1,
+					index: 0,
+				}
+			]
+		);
+
+		assert_eq!(workspace.entries(1).expect("failed to read entries").len(), 3);
+		assert_eq!(workspace.entries(1).expect("failed to read entries")[0].attributes.source, Some(folder.join("v2.img")));
+		assert!(workspace.entries(2).is_err());
+
+		fs::remove_dir_all(folder).expect("failed to remove folder");
+	}
+}
@@ -2,39 +2,61 @@ use std::io::{self, Read, Seek, Write};
 
 use byteorder::{LittleEndian, WriteBytesExt};
 
-use crate::{error::WriteError, NAME_SIZE, NULL_TERMINATOR, SECTOR_SIZE, VERSION_2_HEADER};
-
-/// Represents the offset for where the entries are located in the header of a V2-styled archive.
-const VERSION_2_HEADER_ENTRY_OFFSET: usize = 8;
-
-/// Represents the size of an individual entry in the header of a V2-styled archive.
-const VERSION_2_HEADER_ENTRY_SIZE: usize = 32;
+use crate::{
+	error::WriteError,
+	event::{Event, Handle, Observer},
+	game::{GameProfile, Version, Violation},
+	limit::Limit,
+	read::Entry,
+	ENTRY_SIZE, NAME_SIZE, NULL_TERMINATOR, RESERVED_CHARACTERS, SECTOR_SIZE, VERSION_2_HEADER, VERSION_2_HEADER_ENTRY_OFFSET,
+};
 
 /// Represents a writer of V1-styled archives, to both an `img` file and a `dir` file.
 #[derive(Debug)]
-pub struct V1Writer<'a, 'b, D, I>
+pub struct V1Writer<D, I>
 where
 	D: Write,
 	I: Write + Seek,
 {
-	dir: &'b mut D,
-	img: &'a mut I,
+	dir: D,
+	img: I,
 
 	sector: u64,
+
+	written: usize,
+
+	options: WriteOptions,
+
+	observer: Option<Handle>,
 }
 
 /// Represents a writer of V2-styled archives, to a single `img` file.
 #[derive(Debug)]
-pub struct V2Writer<'a, I>
+pub struct V2Writer<I>
 where
 	I: Write + Seek,
 {
-	img: &'a mut I,
+	img: I,
 
 	sector: u64,
 
 	entries: usize,
 	written: usize,
+
+	options: WriteOptions,
+
+	observer: Option<Handle>,
+}
+
+/// Represents the options for writing archives.
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct WriteOptions {
+	/// Whether to reject names of entries containing control characters or characters reserved by either the games or common filesystems.
+	/// Note that this includes the separators used by names interpreted as folders.
+	pub reject_reserved: bool,
+
+	/// The profile of the game the archive is intended for, any violations of which are rejected, if any.
+	pub profile: Option<GameProfile>,
 }
 
 /// Represents a generic archive writer that can persist archives.
@@ -43,30 +65,94 @@ pub trait Writer {
 	fn write<T>(&mut self, name: &str, src: &mut T) -> Result<(), WriteError>
 	where
 		T: Read;
+
+	/// Attempts to write a single entry described by `entry` from `src` to the head, preserving any of its properties the writer can represent beyond its name (e.g. [`Entry::raw_name`] or [`Entry::stream_len`]).
+	/// By default, only the name of the entry is preserved.
+	fn write_entry<T>(&mut self, entry: &Entry, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.write(&entry.name, src)
+	}
+
+	/// Attempts to write data from `src` to the head without it being claimed by any entry (e.g. to carry data hidden between entries through a rebuild).
+	/// Returns an error of kind `Unsupported` unless implemented by the writer.
+	fn write_unclaimed<T>(&mut self, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		let _ = src;
+
+		Err(io::Error::from(io::ErrorKind::Unsupported).into())
+	}
 }
 
-impl<'a, 'b, D, I> V1Writer<'a, 'b, D, I>
+impl<D, I> V1Writer<D, I>
 where
 	D: Write,
 	I: Write + Seek,
 {
 	/// Creates a new V1-styled writer with the specified `dir` destination and specified `img` destination.
-	pub fn new(dir: &'b mut D, img: &'a mut I) -> Self {
+	/// Either destination may be provided by value, or by mutable reference.
+	pub fn new(dir: D, img: I) -> Self {
 		Self {
 			dir,
 			img,
 			sector: 0,
+			written: 0,
+			options: WriteOptions::default(),
+			observer: None,
 		}
 	}
+
+	/// Sets the options for writing the archive.
+	pub fn with_options(mut self, options: WriteOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Sets the observer to be notified of events raised when writing entries to the archive.
+	pub fn with_observer<O>(mut self, observer: O) -> Self
+	where
+		O: Observer + 'static,
+	{
+		self.observer = Some(Handle::new(observer));
+		self
+	}
+
+	/// Returns the length, in bytes, of the data committed to the `img` destination so far.
+	/// The data of a rejected entry is overwritten by the next entry written, but should it be the last, the destination should be truncated to this length (as `Write` cannot truncate).
+	pub fn len_bytes(&self) -> u64 {
+		self.sector * SECTOR_SIZE
+	}
+
+	/// Consumes the writer, returning the `dir` destination and the `img` destination.
+	/// Note that this does not finish writing the archive.
+	pub fn into_inner(self) -> (D, I) {
+		(self.dir, self.img)
+	}
+
+	/// Finishes writing the archive, flushing both destinations and returning the number of entries written.
+	/// Consumes the writer, such that no further entries may be written.
+	pub fn finish(mut self) -> Result<usize, WriteError> {
+		self.dir.flush()?;
+		self.img.flush()?;
+
+		Ok(self.written)
+	}
 }
 
-impl<'a, I> V2Writer<'a, I>
+impl<I> V2Writer<I>
 where
 	I: Write + Seek,
 {
 	/// Creates a new V2-styled writer with the specified `img` destination.
 	/// Immediately writes the V2-styled header with the prefix and (expected) number of entries.
-	pub fn new(img: &'a mut I, entries: usize) -> Result<Self, io::Error> {
+	/// Returns an error of kind `InvalidInput` (wrapping the exceeded limit) if the number of entries cannot be represented.
+	/// The destination may be provided by value, or by mutable reference.
+	pub fn new(mut img: I, entries: usize) -> Result<Self, io::Error> {
+		Limit::V2Count.check(entries as u64).map_err(|exceeded| io::Error::new(io::ErrorKind::InvalidInput, exceeded))?;
+
 		// Write the fixed header and (expected) number of entries.
 
 		img.seek(io::SeekFrom::Start(0u64))?;
@@ -76,18 +162,61 @@ where
 
 		// Calculate the initial sector accommodating the size of the header.
 
-		let sector = (VERSION_2_HEADER_ENTRY_OFFSET as u64 + (VERSION_2_HEADER_ENTRY_SIZE as u64 * entries as u64)).div_ceil(SECTOR_SIZE);
+		let sector = (VERSION_2_HEADER_ENTRY_OFFSET + ENTRY_SIZE * entries as u64).div_ceil(SECTOR_SIZE);
 
 		Ok(Self {
 			img,
 			sector,
 			entries,
 			written: 0,
+			options: WriteOptions::default(),
+			observer: None,
 		})
 	}
+
+	/// Sets the options for writing the archive.
+	pub fn with_options(mut self, options: WriteOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Sets the observer to be notified of events raised when writing entries to the archive.
+	pub fn with_observer<O>(mut self, observer: O) -> Self
+	where
+		O: Observer + 'static,
+	{
+		self.observer = Some(Handle::new(observer));
+		self
+	}
+
+	/// Returns the length, in bytes, of the data committed to the `img` destination so far.
+	/// The data of a rejected entry is overwritten by the next entry written, but should it be the last, the destination should be truncated to this length (as `Write` cannot truncate).
+	pub fn len_bytes(&self) -> u64 {
+		self.sector * SECTOR_SIZE
+	}
+
+	/// Consumes the writer, returning the destination.
+	/// Note that this does not finish writing the archive, and so does not amend the number of entries in the header.
+	pub fn into_inner(self) -> I {
+		self.img
+	}
+
+	/// Finishes writing the archive, flushing the destination and returning the number of entries written.
+	/// Should fewer entries have been written than expected, the number of entries in the header is amended accordingly.
+	/// Consumes the writer, such that no further entries may be written.
+	pub fn finish(mut self) -> Result<usize, WriteError> {
+		if self.written < self.entries {
+			self.img.seek(io::SeekFrom::Start(VERSION_2_HEADER.len() as u64))?;
+			self.img.write_u32::<LittleEndian>(self.written as u32)?;
+		}
+
+		self.img.flush()?;
+
+		Ok(self.written)
+	}
 }
 
-impl<D, I> Writer for V1Writer<'_, '_, D, I>
+impl<D, I> Writer for V1Writer<D, I>
 where
 	D: Write,
 	I: Write + Seek,
@@ -96,96 +225,280 @@ where
 	where
 		T: Read,
 	{
-		// Seek to the offset for the data.
+		self.write_with(name, None, src)
+	}
+
+	fn write_entry<T>(&mut self, entry: &Entry, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.write_with(&entry.name, entry.raw_name.as_ref().map(|raw| &raw[..]), src)
+	}
+
+	fn write_unclaimed<T>(&mut self, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.sector += write_data(&mut self.img, self.sector, src)?;
+
+		Ok(())
+	}
+}
+
+impl<D, I> V1Writer<D, I>
+where
+	D: Write,
+	I: Write + Seek,
+{
+	fn write_with<T>(&mut self, name: &str, raw_name: Option<&[u8]>, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		// Check the name can be represented as-is, and that the entry would begin at a representable offset.
+
+		check_name(self.written, name, &self.options)?;
+		check_limit(self.written, name, Limit::Offset, self.sector)?;
+		check_profile(self.written, name, &self.options, |profile| profile.check_version(Version::V1).and_then(|_| profile.check_count(self.written + 1)))?;
+
+		// Write the data of the entry, attributing any error to it.
 
 		let offset = self.sector;
+		let length = write_data(&mut self.img, offset, src).map_err(|error| WriteError::entry(self.written, name, error))?;
 
-		self.img.seek(io::SeekFrom::Start(offset * SECTOR_SIZE))?;
+		// Check the length of the entry can be represented as-is, before committing the entry.
 
-		// Copy the source to the current sector in the archive.
+		let checked = check_limit(self.written, name, Limit::V1Length, length)
+			.and_then(|_| check_limit(self.written, name, Limit::Offset, offset + length))
+			.and_then(|_| check_profile(self.written, name, &self.options, |profile| profile.check_size((offset + length) * SECTOR_SIZE)));
+
+		if let Err(error) = checked {
+			return Err(rewind(&mut self.img, offset, error));
+		}
 
-		let bytes = io::copy(src, self.img)?;
+		self.write_record(name, raw_name, offset, length).map_err(|error| WriteError::entry(self.written, name, error))?;
 
-		// Pad the remainder as necessary.
+		notify_written(&self.observer, self.written, name, length);
+		notify_budget(&self.observer, &self.options, self.written, length);
 
-		let length = bytes.div_ceil(SECTOR_SIZE);
-		let remainder = remainder_padded_bytes(length, bytes);
+		self.sector += length;
+		self.written += 1;
 
-		self.img.write_all(&remainder)?;
+		Ok(())
+	}
 
+	fn write_record(&mut self, name: &str, raw_name: Option<&[u8]>, offset: u64, length: u64) -> Result<(), io::Error> {
 		// Write the properties of the entry.
 
 		self.dir.write_u32::<LittleEndian>(offset as u32)?;
 		self.dir.write_u32::<LittleEndian>(length as u32)?;
 
-		// Write the name as a null-terminated string.
+		// Write the name as a null-terminated string, or as its original bytes if known.
 
-		self.dir.write_all(&to_null_terminated(name))?;
-
-		self.sector += length;
+		self.dir.write_all(&raw_name.map_or_else(|| to_null_terminated(name), <[u8]>::to_vec))?;
 
 		Ok(())
 	}
 }
 
-impl<I> Writer for V2Writer<'_, I>
+impl<I> Writer for V2Writer<I>
 where
 	I: Write + Seek,
 {
 	fn write<T>(&mut self, name: &str, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.write_with(name, None, None, src)
+	}
+
+	fn write_entry<T>(&mut self, entry: &Entry, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.write_with(&entry.name, entry.raw_name.as_ref().map(|raw| &raw[..]), entry.stream_len, src)
+	}
+
+	fn write_unclaimed<T>(&mut self, src: &mut T) -> Result<(), WriteError>
+	where
+		T: Read,
+	{
+		self.sector += write_data(&mut self.img, self.sector, src)?;
+
+		Ok(())
+	}
+}
+
+impl<I> V2Writer<I>
+where
+	I: Write + Seek,
+{
+	fn write_with<T>(&mut self, name: &str, raw_name: Option<&[u8]>, stream_len: Option<u64>, src: &mut T) -> Result<(), WriteError>
 	where
 		T: Read,
 	{
 		// Check if we have capacity for another entry.
 
 		if self.written >= self.entries {
-			return Err(WriteError::InsufficientHeaderSize);
+			return Err(WriteError::InsufficientHeaderSize {
+				index: self.written,
+				name: name.to_owned(),
+			});
 		}
 
-		// Seek to the offset for the data.
+		// Check the name can be represented as-is, and that the entry would begin at a representable offset.
+
+		check_name(self.written, name, &self.options)?;
+		check_limit(self.written, name, Limit::Offset, self.sector)?;
+		check_limit(self.written, name, Limit::V2Length, stream_len.unwrap_or(0))?;
+		check_profile(self.written, name, &self.options, |profile| profile.check_version(Version::V2).and_then(|_| profile.check_count(self.written + 1)))?;
+
+		// Write the data of the entry, attributing any error to it.
 
 		let offset = self.sector;
+		let length = write_data(&mut self.img, offset, src).map_err(|error| WriteError::entry(self.written, name, error))?;
 
-		self.img.seek(io::SeekFrom::Start(offset * SECTOR_SIZE))?;
+		// Check the length of the entry can be represented as-is, before committing the entry.
 
-		// Copy the source to the current sector in the archive.
+		let checked = check_limit(self.written, name, Limit::V2Length, length)
+			.and_then(|_| check_limit(self.written, name, Limit::Offset, offset + length))
+			.and_then(|_| check_profile(self.written, name, &self.options, |profile| profile.check_size((offset + length) * SECTOR_SIZE)));
 
-		let bytes = io::copy(src, self.img)?;
+		if let Err(error) = checked {
+			return Err(rewind(&mut self.img, offset, error));
+		}
 
-		// Pad the remainder as necessary.
+		self.write_record(name, raw_name, offset, length, stream_len).map_err(|error| WriteError::entry(self.written, name, error))?;
 
-		let length = bytes.div_ceil(SECTOR_SIZE);
-		let remainder = remainder_padded_bytes(length, bytes);
+		notify_written(&self.observer, self.written, name, length);
+		notify_budget(&self.observer, &self.options, self.written, length);
 
-		self.img.write_all(&remainder)?;
+		self.sector += length;
+		self.written += 1;
 
+		Ok(())
+	}
+
+	fn write_record(&mut self, name: &str, raw_name: Option<&[u8]>, offset: u64, length: u64, stream_len: Option<u64>) -> Result<(), io::Error> {
 		// Seek to the offset for the header.
 
 		self.img
-			.seek(io::SeekFrom::Start(VERSION_2_HEADER_ENTRY_OFFSET as u64 + (VERSION_2_HEADER_ENTRY_SIZE as u64 * self.written as u64)))?;
+			.seek(io::SeekFrom::Start(VERSION_2_HEADER_ENTRY_OFFSET + ENTRY_SIZE * self.written as u64))?;
 
 		// Write the properties of the entry.
 
 		self.img.write_u32::<LittleEndian>(offset as u32)?;
 		self.img.write_u16::<LittleEndian>(length as u16)?;
-		self.img.write_u16::<LittleEndian>(0u16)?; // Unused (always 0)
-
-		// Write the name as a null-terminated string.
+		self.img.write_u16::<LittleEndian>(stream_len.unwrap_or(0) as u16)?; // Length in archive (usually 0)
 
-		self.img.write_all(&to_null_terminated(name))?;
+		// Write the name as a null-terminated string, or as its original bytes if known.
 
-		self.sector += length;
-		self.written += 1;
+		self.img.write_all(&raw_name.map_or_else(|| to_null_terminated(name), <[u8]>::to_vec))?;
 
 		Ok(())
 	}
 }
 
+fn write_data<I, T>(img: &mut I, offset: u64, src: &mut T) -> Result<u64, io::Error>
+where
+	I: Write + Seek,
+	T: Read,
+{
+	// Seek to the offset for the data.
+
+	img.seek(io::SeekFrom::Start(offset * SECTOR_SIZE))?;
+
+	// Copy the source to the current sector in the archive.
+
+	let bytes = io::copy(src, img)?;
+
+	// Pad the remainder as necessary.
+
+	let length = bytes.div_ceil(SECTOR_SIZE);
+	let remainder = remainder_padded_bytes(length, bytes);
+
+	img.write_all(&remainder)?;
+
+	Ok(length)
+}
+
+fn rewind<I>(img: &mut I, offset: u64, error: WriteError) -> WriteError
+where
+	I: Seek,
+{
+	// Return to the start of the rejected entry, such that its data is overwritten by the next entry.
+
+	match img.seek(io::SeekFrom::Start(offset * SECTOR_SIZE)) {
+		Ok(_) => error,
+		Err(error) => error.into(),
+	}
+}
+
+fn check_limit(index: usize, name: &str, limit: Limit, value: u64) -> Result<(), WriteError> {
+	limit.check(value).map_err(|exceeded| WriteError::LimitExceeded {
+		index,
+		name: name.to_owned(),
+		exceeded,
+	})
+}
+
+fn notify_written(observer: &Option<Handle>, index: usize, name: &str, length: u64) {
+	if let Some(observer) = observer {
+		observer.notify(&Event::EntryWritten {
+			index,
+			name,
+			length,
+		});
+	}
+}
+
+fn notify_budget(observer: &Option<Handle>, options: &WriteOptions, index: usize, length: u64) {
+	if let (Some(observer), Some(profile)) = (observer, &options.profile) {
+		if let Err(violation) = profile.check_budget(index, length * SECTOR_SIZE) {
+			observer.notify(&Event::Violation(&violation));
+		}
+	}
+}
+
+fn check_name(index: usize, name: &str, options: &WriteOptions) -> Result<(), WriteError> {
+	// Check that the name fits without being truncated.
+
+	check_limit(index, name, Limit::NameLength, name.chars().count() as u64)?;
+
+	// Check that each character fits within a single byte, rather than silently dropping it.
+	// Also check for control and reserved characters, if rejected.
+
+	let reject_reserved = options.reject_reserved || options.profile.is_some_and(|profile| profile.reject_reserved);
+	let invalid = |c: char| u8::try_from(c).is_err() || (reject_reserved && (c.is_ascii_control() || RESERVED_CHARACTERS.contains(&c)));
+
+	match name.chars().find(|&c| invalid(c)) {
+		Some(character) => Err(WriteError::InvalidName {
+			index,
+			name: name.to_owned(),
+			character,
+		}),
+		None => Ok(()),
+	}
+}
+
+fn check_profile<F>(index: usize, name: &str, options: &WriteOptions, check: F) -> Result<(), WriteError>
+where
+	F: FnOnce(&GameProfile) -> Result<(), Violation>,
+{
+	match &options.profile {
+		Some(profile) => check(profile).map_err(|violation| WriteError::ProfileViolation {
+			index,
+			name: name.to_owned(),
+			violation,
+		}),
+		None => Ok(()),
+	}
+}
+
 fn remainder_padded_bytes(sectors: u64, bytes: u64) -> Vec<u8> {
 	vec![0; ((sectors * SECTOR_SIZE).saturating_sub(bytes)) as usize]
 }
 
-fn to_null_terminated(string: &str) -> Vec<u8> {
+pub(crate) fn to_null_terminated(string: &str) -> Vec<u8> {
 	#[rustfmt::skip]
 	let bytes = string.chars()
 		.flat_map(u8::try_from)
@@ -197,17 +510,24 @@ fn to_null_terminated(string: &str) -> Vec<u8> {
 }
 
 #[cfg(test)]
+#[allow(clippy::zero_prefixed_literal)]
 mod tests {
-	use std::io::Cursor;
+	use std::io::{self, Cursor, Read};
 
-	use crate::{error::WriteError, write::V2Writer};
+	use crate::{
+		error::WriteError,
+		game::{Game, GameProfile},
+		limit::{Exceeded, Limit},
+		read::{Archive, Entry, Reader, V2Reader},
+		write::V2Writer,
+	};
 
-	use super::{to_null_terminated, V1Writer, Writer};
+	use super::{to_null_terminated, V1Writer, WriteOptions, Writer};
 
 	#[test]
 	pub fn test_to_name_truncate() {
 		let string = "SomebodyOnceToldMeWorldGonnaRollMe";
-		let slice = to_null_terminated(&string);
+		let slice = to_null_terminated(string);
 
 		assert_eq!(slice, vec![b'S', b'o', b'm', b'e', b'b', b'o', b'd', b'y', b'O', b'n', b'c', b'e', b'T', b'o', b'l', b'd', b'M', b'e', b'W', b'o', b'r', b'l', b'd', 0]); // SomebodyOnceToldMeWorld
 		assert_eq!(slice.len(), 24);
@@ -216,7 +536,7 @@ mod tests {
 	#[test]
 	pub fn test_to_name() {
 		let string = "VIRGO.DFF";
-		let slice = to_null_terminated(&string);
+		let slice = to_null_terminated(string);
 
 		assert_eq!(slice, vec![b'V', b'I', b'R', b'G', b'O', b'.', b'D', b'F', b'F', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // VIRGO.DFF
 		assert_eq!(slice.len(), 24);
@@ -284,6 +604,235 @@ mod tests {
 		assert_eq!(bytes.len(), 6144);
 	}
 
+	#[test]
+	pub fn test_write_invalid_name() {
+		let mut dir: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+
+		let mut writer = V1Writer::new(&mut dir, &mut img);
+
+		let mut virgo: Cursor<_> = Cursor::new(include_bytes!("../test/virgo.dff"));
+
+		let result = writer.write("VIRGO\u{2603}.DFF", &mut virgo);
+
+		assert!(matches!(
+			result,
+			Err(WriteError::InvalidName {
+				character: '\u{2603}',
+				..
+			})
+		));
+		assert!(dir.get_ref().is_empty());
+		assert!(img.get_ref().is_empty());
+	}
+
+	#[test]
+	pub fn test_write_reserved_name() {
+		let mut dir: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+
+		let mut virgo: Cursor<_> = Cursor::new(include_bytes!("../test/virgo.dff"));
+
+		V1Writer::new(&mut dir, &mut img).write("VEH/VIRGO.DFF", &mut virgo).expect("failed to write entry");
+
+		let result = V1Writer::new(&mut dir, &mut img)
+			.with_options(WriteOptions {
+				reject_reserved: true,
+				..Default::default()
+			})
+			.write("VEH/VIRGO.DFF", &mut virgo);
+
+		assert!(matches!(
+			result,
+			Err(WriteError::InvalidName {
+				character: '/',
+				..
+			})
+		));
+	}
+
+	#[test]
+	pub fn test_write_name_too_long() {
+		let mut dir: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+
+		let result = V1Writer::new(&mut dir, &mut img).write("abcdefghijklmnopqrstuvwx", &mut Cursor::new(include_bytes!("../test/virgo.dff")));
+
+		assert!(matches!(
+			result,
+			Err(WriteError::LimitExceeded {
+				index: 0,
+				exceeded: Exceeded {
+					limit: Limit::NameLength,
+					value: 24,
+				},
+				..
+			})
+		));
+		assert!(dir.get_ref().is_empty());
+	}
+
+	#[test]
+	pub fn test_write_entry_error() {
+		let mut dir: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+
+		let mut writer = V1Writer::new(&mut dir, &mut img);
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write entry");
+
+		// Write from a source which always fails.
+
+		struct Failing;
+
+		impl Read for Failing {
+			fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+				Err(io::Error::other("unreadable"))
+			}
+		}
+
+		let result = writer.write("LANDSTAL.DFF", &mut Failing);
+
+		assert!(matches!(
+			result,
+			Err(WriteError::EntryError {
+				index: 1,
+				ref name,
+				..
+			}) if name == "LANDSTAL.DFF"
+		));
+	}
+
+	#[test]
+	pub fn test_write_by_value() {
+		let mut writer = V1Writer::new(Cursor::new(Vec::new()), Cursor::new(Vec::new()));
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write entry");
+
+		let (dir, img) = writer.into_inner();
+
+		assert_eq!(dir.get_ref().len(), 32);
+		assert_eq!(img.get_ref().len(), 2048);
+	}
+
+	#[test]
+	pub fn test_write_v2_entry() {
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 1).expect("failed to create writer");
+
+		let mut entry = Entry::new("VIRGO.DFF", 0, 0).expect("failed to create entry");
+		entry.stream_len = Some(3);
+
+		writer.write_entry(&entry, &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write entry");
+
+		img.set_position(0);
+
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.get(0).map(|entry| entry.stream_len), Some(Some(3)));
+		assert_eq!(archive.get(0).map(|entry| entry.length), Some(1));
+	}
+
+	#[test]
+	pub fn test_write_v2_entry_raw_name() {
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 1).expect("failed to create writer");
+
+		let mut raw_name = [0; 24];
+
+		raw_name[..9].copy_from_slice(b"\xFFIRGO.DFF");
+		raw_name[10..].copy_from_slice(b"hidden bytes!!");
+
+		let mut entry = Entry::new("?IRGO.DFF", 0, 0).expect("failed to create entry");
+		entry.raw_name = Some(raw_name);
+
+		writer.write_entry(&entry, &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write entry");
+
+		img.set_position(0);
+
+		let archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+
+		assert_eq!(archive.get(0).and_then(|entry| entry.raw_name), Some(raw_name));
+	}
+
+	#[test]
+	pub fn test_write_v2_rejected() {
+		let options = WriteOptions {
+			profile: Some(GameProfile {
+				max_size: 3 * 2048,
+				..Game::SanAndreas.profile()
+			}),
+			..Default::default()
+		};
+
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 3).expect("failed to create writer").with_options(options);
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(vec![1; 2048])).expect("failed to write entry");
+
+		// Reject an entry which would exceed the maximum size, after its data has been written.
+
+		assert!(matches!(writer.write("LANDSTAL.DFF", &mut Cursor::new(vec![2; 4096])), Err(WriteError::ProfileViolation { .. })));
+		assert_eq!(writer.len_bytes(), 2 * 2048);
+
+		// The next entry should overwrite the data of the rejected entry.
+
+		writer.write("ZEBRA.DFF", &mut Cursor::new(vec![3; 2048])).expect("failed to write entry");
+
+		let len = writer.len_bytes();
+
+		writer.finish().expect("failed to finish archive");
+
+		// Truncate the remainder of the data of the rejected entry.
+
+		assert_eq!(img.get_ref().len(), 4 * 2048);
+
+		img.get_mut().truncate(len as usize);
+		img.set_position(0);
+
+		let mut archive: Archive<_> = V2Reader::new(&mut img).read().expect("failed to read archive");
+		let mut data = Vec::new();
+
+		archive.open(1).expect("expected entry").read_to_end(&mut data).expect("failed to read entry");
+
+		assert_eq!(archive.len(), 2);
+		assert_eq!(data, vec![3; 2048]);
+	}
+
+	#[test]
+	pub fn test_write_v2_entry_stream_len_exceeded() {
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 1).expect("failed to create writer");
+
+		let mut entry = Entry::new("VIRGO.DFF", 0, 0).expect("failed to create entry");
+		entry.stream_len = Some(65536);
+
+		let result = writer.write_entry(&entry, &mut Cursor::new(include_bytes!("../test/virgo.dff")));
+
+		assert!(matches!(
+			result,
+			Err(WriteError::LimitExceeded {
+				index: 0,
+				exceeded: Exceeded {
+					limit: Limit::V2Length,
+					value: 65536,
+				},
+				..
+			})
+		));
+	}
+
+	#[test]
+	pub fn test_write_v2_finish() {
+		let mut img: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+		let mut writer = V2Writer::new(&mut img, 3).expect("failed to create writer");
+
+		writer.write("VIRGO.DFF", &mut Cursor::new(include_bytes!("../test/virgo.dff"))).expect("failed to write entry");
+
+		assert_eq!(writer.finish().expect("failed to finish archive"), 1);
+		assert_eq!(img.get_ref()[4..8], [1, 0, 0, 0]);
+	}
+
 	#[test]
 	pub fn test_write_v2_space() {
 		let mut img: Cursor<_> = Cursor::new(Vec::new());
@@ -297,6 +846,6 @@ mod tests {
 		let second_write = writer.write("LANDSTAL.DFF", &mut landstal);
 
 		assert!(matches!(first_write, Ok(())));
-		assert!(matches!(second_write, Err(WriteError::InsufficientHeaderSize)));
+		assert!(matches!(second_write, Err(WriteError::InsufficientHeaderSize { index: 1, .. })));
 	}
 }